@@ -6,7 +6,7 @@ use libfuzzer_sys::fuzz_target;
 fn errors_equal(value_error: &JsonError, jiter_error: &JiterError) {
     let jiter_error_type = match &jiter_error.error_type {
         JiterErrorType::JsonError(json_error_type) => json_error_type,
-        JiterErrorType::WrongType { .. } => panic!("Expected JsonError, found WrongType"),
+        other => panic!("Expected JsonError, found {other:?}"),
     };
     assert_eq!(&value_error.error_type, jiter_error_type);
     assert_eq!(value_error.index, jiter_error.index);
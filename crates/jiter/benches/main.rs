@@ -4,7 +4,7 @@ use std::hint::black_box;
 use std::fs::File;
 use std::io::Read;
 
-use jiter::{Jiter, JsonValue, LazyIndexMap, PartialMode, Peek};
+use jiter::{Jiter, JsonValue, JsonValuePool, JsonValueRc, LazyIndexMap, PartialMode, Peek};
 use serde_json::Value;
 
 fn read_file(path: &str) -> String {
@@ -160,6 +160,16 @@ fn jiter_iter_floats_array(path: &str, bench: &mut Bencher) {
     })
 }
 
+fn floats_array_jiter_read_array(bench: &mut Bencher) {
+    let json = read_file("./benches/floats_array.json");
+    let json_data = black_box(json.as_bytes());
+    bench.iter(|| {
+        let mut jiter = Jiter::new(json_data);
+        let floats = jiter.read_f64_array().unwrap();
+        black_box(floats);
+    })
+}
+
 fn jiter_string(path: &str, bench: &mut Bencher) {
     let json = read_file(path);
     let json_data = black_box(json.as_bytes());
@@ -263,6 +273,22 @@ fn string_array_jiter_value_owned(bench: &mut Bencher) {
     })
 }
 
+fn string_array_jiter_value_str(bench: &mut Bencher) {
+    let json = read_file("./benches/string_array.json");
+    bench.iter(|| {
+        let v = JsonValue::parse_str(black_box(&json), false).unwrap();
+        black_box(v)
+    })
+}
+
+fn unicode_jiter_value_str(bench: &mut Bencher) {
+    let json = read_file("./benches/unicode.json");
+    bench.iter(|| {
+        let v = JsonValue::parse_str(black_box(&json), false).unwrap();
+        black_box(v)
+    })
+}
+
 fn medium_response_jiter_value_owned(bench: &mut Bencher) {
     let json = read_file("./benches/medium_response.json");
     let json_data = json.as_bytes();
@@ -272,6 +298,166 @@ fn medium_response_jiter_value_owned(bench: &mut Bencher) {
     })
 }
 
+fn medium_response_jiter_value_pooled(bench: &mut Bencher) {
+    let json = read_file("./benches/medium_response.json");
+    let json_data = json.as_bytes();
+    let pool = JsonValuePool::new();
+    bench.iter(|| {
+        let v = pool.parse(black_box(json_data), false).unwrap();
+        black_box(v);
+    })
+}
+
+/// clone a handle to every array and object in the value, then drop them all
+fn clone_containers_arc<'a>(value: &JsonValue<'a>, clones: &mut Vec<JsonValue<'a>>) {
+    match value {
+        JsonValue::Array(array) => {
+            clones.push(value.clone());
+            array.iter().for_each(|v| clone_containers_arc(v, clones));
+        }
+        JsonValue::Object(object) => {
+            clones.push(value.clone());
+            object.iter().for_each(|(_, v)| clone_containers_arc(v, clones));
+        }
+        _ => (),
+    }
+}
+
+fn clone_containers_rc<'a>(value: &JsonValueRc<'a>, clones: &mut Vec<JsonValueRc<'a>>) {
+    match value {
+        JsonValueRc::Array(array) => {
+            clones.push(value.clone());
+            array.iter().for_each(|v| clone_containers_rc(v, clones));
+        }
+        JsonValueRc::Object(object) => {
+            clones.push(value.clone());
+            object.iter().for_each(|(_, v)| clone_containers_rc(v, clones));
+        }
+        _ => (),
+    }
+}
+
+fn medium_response_jiter_value_clone_drop(bench: &mut Bencher) {
+    let json = read_file("./benches/medium_response.json");
+    let value = JsonValue::parse(json.as_bytes(), false).unwrap();
+    bench.iter(|| {
+        let mut clones = Vec::new();
+        for _ in 0..100 {
+            clone_containers_arc(black_box(&value), &mut clones);
+        }
+        black_box(clones)
+    })
+}
+
+fn medium_response_jiter_value_rc_clone_drop(bench: &mut Bencher) {
+    let json = read_file("./benches/medium_response.json");
+    let value = JsonValueRc::parse(json.as_bytes(), false).unwrap();
+    bench.iter(|| {
+        let mut clones = Vec::new();
+        for _ in 0..100 {
+            clone_containers_rc(black_box(&value), &mut clones);
+        }
+        black_box(clones)
+    })
+}
+
+fn medium_response_jiter_value_rc(bench: &mut Bencher) {
+    let json = read_file("./benches/medium_response.json");
+    let json_data = json.as_bytes();
+    bench.iter(|| {
+        let v = JsonValueRc::parse(black_box(json_data), false).unwrap();
+        black_box(v)
+    })
+}
+
+/// an array of strings full of escapes, each longer than the last so the string scratch buffer has to grow
+fn escaped_strings_json() -> String {
+    let strings: Vec<String> = (1..500)
+        .map(|i| format!("\"{}\"", r#"line\n\t\"quoted\""#.repeat(i)))
+        .collect();
+    format!("[{}]", strings.join(", "))
+}
+
+fn jiter_iter_escaped_strings(jiter: &mut Jiter) {
+    jiter.next_array().unwrap();
+    black_box(jiter.known_str().unwrap().len());
+    while jiter.array_step().unwrap().is_some() {
+        black_box(jiter.known_str().unwrap().len());
+    }
+    jiter.finish().unwrap();
+}
+
+fn escaped_strings_jiter_iter(bench: &mut Bencher) {
+    let json = escaped_strings_json();
+    let json_data = black_box(json.as_bytes());
+    bench.iter(|| {
+        let mut jiter = Jiter::new(json_data);
+        jiter_iter_escaped_strings(&mut jiter);
+    })
+}
+
+fn escaped_strings_jiter_iter_scratch(bench: &mut Bencher) {
+    let json = escaped_strings_json();
+    let json_data = black_box(json.as_bytes());
+    bench.iter(|| {
+        let mut jiter = Jiter::new(json_data).with_string_scratch_capacity(json_data.len());
+        jiter_iter_escaped_strings(&mut jiter);
+    })
+}
+
+/// objects nested a few arrays deep, so cloning a `Jiter` has to copy its stack of arrays
+fn nested_union_json() -> String {
+    let elements = vec![r#"{"kind": "b", "values": [1, 2, 3]}"#; 1000].join(", ");
+    format!("[[[[{elements}]]]]")
+}
+
+/// Start reading the next value as a `{"kind": "a", ...}` union member, returning whether it is one.
+fn try_kind_a(jiter: &mut Jiter) -> bool {
+    matches!(jiter.next_object(), Ok(Some("kind"))) && matches!(jiter.next_str(), Ok("a"))
+}
+
+fn nested_union_jiter_snapshot(bench: &mut Bencher) {
+    let json = nested_union_json();
+    let json_data = black_box(json.as_bytes());
+    bench.iter(|| {
+        let mut jiter = Jiter::new(json_data);
+        for _ in 0..4 {
+            jiter.next_array().unwrap();
+        }
+        loop {
+            let snapshot = jiter.snapshot();
+            if !try_kind_a(&mut jiter) {
+                jiter.restore(&snapshot);
+                jiter.next_skip().unwrap();
+            }
+            if jiter.array_step().unwrap().is_none() {
+                break;
+            }
+        }
+    })
+}
+
+fn nested_union_jiter_clone(bench: &mut Bencher) {
+    let json = nested_union_json();
+    let json_data = black_box(json.as_bytes());
+    bench.iter(|| {
+        let mut jiter = Jiter::new(json_data);
+        for _ in 0..4 {
+            jiter.next_array().unwrap();
+        }
+        loop {
+            let checkpoint = jiter.clone();
+            if !try_kind_a(&mut jiter) {
+                jiter = checkpoint;
+                jiter.next_skip().unwrap();
+            }
+            if jiter.array_step().unwrap().is_none() {
+                break;
+            }
+        }
+    })
+}
+
 fn x100_serde_iter(bench: &mut Bencher) {
     serde_str("./benches/x100.json", bench);
 }
@@ -314,6 +500,7 @@ benchmark_group!(
     bigints_array_jiter_value,
     bigints_array_serde_value,
     floats_array_jiter_iter,
+    floats_array_jiter_read_array,
     floats_array_jiter_skip,
     floats_array_jiter_value,
     floats_array_serde_value,
@@ -325,12 +512,20 @@ benchmark_group!(
     medium_response_jiter_skip,
     medium_response_jiter_value,
     medium_response_jiter_value_owned,
+    medium_response_jiter_value_pooled,
+    medium_response_jiter_value_rc,
+    medium_response_jiter_value_clone_drop,
+    medium_response_jiter_value_rc_clone_drop,
     medium_response_serde_value,
     x100_jiter_iter,
     x100_jiter_skip,
     x100_jiter_value,
     x100_serde_iter,
     x100_serde_value,
+    escaped_strings_jiter_iter,
+    escaped_strings_jiter_iter_scratch,
+    nested_union_jiter_snapshot,
+    nested_union_jiter_clone,
     sentence_jiter_iter,
     sentence_jiter_skip,
     sentence_jiter_value,
@@ -338,6 +533,7 @@ benchmark_group!(
     unicode_jiter_iter,
     unicode_jiter_skip,
     unicode_jiter_value,
+    unicode_jiter_value_str,
     unicode_serde_value,
     pass1_jiter_iter,
     pass1_jiter_skip,
@@ -351,6 +547,7 @@ benchmark_group!(
     string_array_jiter_skip,
     string_array_jiter_value,
     string_array_jiter_value_owned,
+    string_array_jiter_value_str,
     string_array_serde_value,
     true_array_jiter_iter,
     true_array_jiter_skip,
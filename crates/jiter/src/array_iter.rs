@@ -0,0 +1,82 @@
+use crate::jiter::{Jiter, JiterResult};
+use crate::parse::Peek;
+
+/// An [Iterator] over the elements of an array, yielding the [Peek] of each element, see [Jiter::array_iter].
+///
+/// The iterator holds the mutable borrow of the [Jiter] for as long as it's alive, so elements are read through
+/// [ArrayIter::jiter], which is positioned at the element just yielded:
+///
+/// ```rust
+/// use jiter::Jiter;
+///
+/// let mut jiter = Jiter::new(br#"[1, "two", 3]"#);
+/// let mut iter = jiter.array_iter().unwrap();
+/// let mut ints = Vec::new();
+/// while let Some(peek) = iter.next() {
+///     let peek = peek.unwrap();
+///     if peek.is_num() {
+///         ints.push(iter.jiter().known_int(peek).unwrap());
+///     }
+/// }
+/// assert_eq!(ints.len(), 2);
+/// jiter.finish().unwrap();
+/// ```
+///
+/// Any element, or part of an element, which isn't read before the next call to `next` is skipped, so
+/// a `for` loop which only looks at the peeks also works. The iterator ends after the first error.
+///
+/// If iteration stops early, e.g. with `break`, the jiter is left inside the array, use
+/// [Jiter::skip_to_depth] with the depth from before the array to skip the rest of it.
+pub struct ArrayIter<'a, 'j> {
+    jiter: &'a mut Jiter<'j>,
+    /// the depth inside the array, see [Jiter::current_depth]
+    depth: usize,
+    /// the peek of the next element, `None` once the end of the array is reached
+    next: Option<Peek>,
+    /// whether an element has been yielded, so the parser must move past it before the next element
+    step: bool,
+}
+
+impl<'a, 'j> ArrayIter<'a, 'j> {
+    pub(crate) fn new(jiter: &'a mut Jiter<'j>) -> JiterResult<Self> {
+        let depth = jiter.current_depth() + 1;
+        let next = jiter.next_array()?;
+        Ok(Self {
+            jiter,
+            depth,
+            next,
+            step: false,
+        })
+    }
+
+    /// The jiter, to read the element whose peek was just yielded, e.g. with [Jiter::known_int].
+    pub fn jiter(&mut self) -> &mut Jiter<'j> {
+        self.jiter
+    }
+
+    fn step(&mut self) -> JiterResult<Option<Peek>> {
+        if self.jiter.current_depth() > self.depth {
+            self.jiter.skip_to_depth(self.depth)?;
+        } else {
+            self.jiter.skip_pending_value()?;
+        }
+        self.jiter.array_step()
+    }
+}
+
+impl Iterator for ArrayIter<'_, '_> {
+    type Item = JiterResult<Peek>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.step {
+            self.step = false;
+            match self.step() {
+                Ok(next) => self.next = next,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        let peek = self.next.take()?;
+        self.step = true;
+        Some(Ok(peek))
+    }
+}
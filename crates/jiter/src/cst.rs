@@ -0,0 +1,279 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::errors::{json_err, json_error, JsonResult, DEFAULT_RECURSION_LIMIT};
+use crate::number_decoder::NumberRange;
+use crate::parse::{comment_len, Parser, Peek};
+use crate::string_decoder::{StringDecoder, Tape};
+
+/// A concrete syntax tree of a JSON document which keeps its comments, see [parse_cst].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstDocument<'j> {
+    pub value: CstNode<'j>,
+    /// Comments after the value.
+    pub trailing_comments: Vec<Comment<'j>>,
+}
+
+/// A value in a [CstDocument], with the comments which come before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstNode<'j> {
+    /// Comments between the previous token and this value.
+    pub leading_comments: Vec<Comment<'j>>,
+    /// Where the value is in the JSON data, without its comments.
+    pub range: Range<usize>,
+    pub kind: CstKind<'j>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstKind<'j> {
+    Null,
+    Bool(bool),
+    /// Numbers are kept as they're written, so they can be reproduced exactly.
+    Number(&'j str),
+    Str(Cow<'j, str>),
+    Array {
+        elements: Vec<CstNode<'j>>,
+        /// Comments after the last element, before the `]`.
+        trailing_comments: Vec<Comment<'j>>,
+    },
+    Object {
+        members: Vec<CstMember<'j>>,
+        /// Comments after the last member, before the `}`.
+        trailing_comments: Vec<Comment<'j>>,
+    },
+}
+
+/// A key and value in a [CstKind::Object].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstMember<'j> {
+    /// Comments between the previous token and the key.
+    pub leading_comments: Vec<Comment<'j>>,
+    pub key: Cow<'j, str>,
+    /// Where the key is in the JSON data, including its quotes.
+    pub key_range: Range<usize>,
+    /// The value, comments either side of the `:` are its leading comments.
+    pub value: CstNode<'j>,
+}
+
+/// A `// line` or `/* block */` comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment<'j> {
+    /// The comment including its delimiters, but not the newline ending a line comment.
+    pub text: &'j str,
+    /// Where the comment is in the JSON data.
+    pub range: Range<usize>,
+}
+
+impl Comment<'_> {
+    /// Whether this is a `/* block */` comment rather than a `// line` comment.
+    pub fn is_block(&self) -> bool {
+        self.text.starts_with("/*")
+    }
+}
+
+/// Parse JSON which may contain `//` and `/* */` comments into a [CstDocument], keeping the comments.
+///
+/// Each comment is attached to the value, key or closing bracket which follows it, so a formatter
+/// can reproduce them. Ranges are kept for every value, key and comment, so blank lines and other
+/// whitespace can be recovered from the data if needed.
+///
+/// ```rust
+/// use jiter::{parse_cst, CstKind};
+///
+/// let doc = parse_cst(b"// config\n{\"debug\": /* for now */ true}").unwrap();
+/// assert_eq!(doc.value.leading_comments[0].text, "// config");
+/// let CstKind::Object { members, .. } = doc.value.kind else {
+///     panic!("expected an object");
+/// };
+/// assert_eq!(members[0].key, "debug");
+/// assert_eq!(members[0].value.leading_comments[0].text, "/* for now */");
+/// assert_eq!(members[0].value.kind, CstKind::Bool(true));
+/// ```
+pub fn parse_cst(data: &[u8]) -> JsonResult<CstDocument<'_>> {
+    let mut cst_parser = CstParser {
+        data,
+        parser: Parser::new(data),
+        tape: Tape::default(),
+    };
+    let leading_comments = cst_parser.comments()?;
+    let value = cst_parser.value(leading_comments, DEFAULT_RECURSION_LIMIT)?;
+    let trailing_comments = cst_parser.comments()?;
+    cst_parser.parser.finish()?;
+    Ok(CstDocument {
+        value,
+        trailing_comments,
+    })
+}
+
+struct CstParser<'j> {
+    data: &'j [u8],
+    parser: Parser<'j>,
+    tape: Tape,
+}
+
+impl<'j> CstParser<'j> {
+    /// Read any comments, and the whitespace around them, at the current position.
+    fn comments(&mut self) -> JsonResult<Vec<Comment<'j>>> {
+        let mut comments = Vec::new();
+        while self.parser.eat_whitespace() == Some(b'/') {
+            let start = self.parser.index;
+            // if it's not a comment, let the caller report the unexpected `/`
+            let Some(len) = comment_len(&self.data[start..]) else {
+                break;
+            };
+            let end = start + len;
+            let text = std::str::from_utf8(&self.data[start..end])
+                .map_err(|e| json_error!(InvalidUnicodeCodePoint, start + e.valid_up_to()))?;
+            if text.starts_with("/*") && (len < 4 || !text.ends_with("*/")) {
+                return json_err!(EofWhileParsingComment, self.data.len());
+            }
+            comments.push(Comment {
+                text,
+                range: start..end,
+            });
+            self.parser.index = end;
+        }
+        Ok(comments)
+    }
+
+    fn value(&mut self, leading_comments: Vec<Comment<'j>>, recursion_limit: u8) -> JsonResult<CstNode<'j>> {
+        let start = self.parser.index;
+        let peek = self.parser.peek()?;
+        let kind = match peek {
+            Peek::Null => {
+                self.parser.consume_null()?;
+                CstKind::Null
+            }
+            Peek::True => {
+                self.parser.consume_true()?;
+                CstKind::Bool(true)
+            }
+            Peek::False => {
+                self.parser.consume_false()?;
+                CstKind::Bool(false)
+            }
+            Peek::String => CstKind::Str(
+                self.parser
+                    .consume_string::<StringDecoder>(&mut self.tape, false)?
+                    .into(),
+            ),
+            Peek::Array => self.array(recursion_limit)?,
+            Peek::Object => self.object(recursion_limit)?,
+            _ => match self.parser.consume_number::<NumberRange>(peek.into_inner(), false) {
+                Ok(number) => {
+                    let text =
+                        std::str::from_utf8(&self.data[number.range]).map_err(|_| json_error!(InvalidNumber, start))?;
+                    CstKind::Number(text)
+                }
+                Err(_) if !peek.is_num() => return json_err!(ExpectedSomeValue, start),
+                Err(e) => return Err(e),
+            },
+        };
+        Ok(CstNode {
+            leading_comments,
+            range: start..self.parser.index,
+            kind,
+        })
+    }
+
+    fn array(&mut self, recursion_limit: u8) -> JsonResult<CstKind<'j>> {
+        self.parser.index += 1;
+        let mut elements = Vec::new();
+        let mut comments = self.comments()?;
+        if self.parser.eat_whitespace() == Some(b']') {
+            self.parser.index += 1;
+            return Ok(CstKind::Array {
+                elements,
+                trailing_comments: comments,
+            });
+        }
+        loop {
+            if recursion_limit == 0 {
+                return json_err!(RecursionLimitExceeded, self.parser.index);
+            }
+            elements.push(self.value(comments, recursion_limit - 1)?);
+            comments = self.comments()?;
+            match self.parser.eat_whitespace() {
+                Some(b',') => {
+                    self.parser.index += 1;
+                    comments.extend(self.comments()?);
+                }
+                Some(b']') => {
+                    self.parser.index += 1;
+                    return Ok(CstKind::Array {
+                        elements,
+                        trailing_comments: comments,
+                    });
+                }
+                Some(_) => return json_err!(ExpectedListCommaOrEnd, self.parser.index),
+                None => return json_err!(EofWhileParsingList, self.parser.index),
+            }
+        }
+    }
+
+    fn object(&mut self, recursion_limit: u8) -> JsonResult<CstKind<'j>> {
+        self.parser.index += 1;
+        let mut members = Vec::new();
+        let mut comments = self.comments()?;
+        if self.parser.eat_whitespace() == Some(b'}') {
+            self.parser.index += 1;
+            return Ok(CstKind::Object {
+                members,
+                trailing_comments: comments,
+            });
+        }
+        loop {
+            self.expect_key()?;
+            let key_start = self.parser.index;
+            let key = self
+                .parser
+                .consume_string::<StringDecoder>(&mut self.tape, false)?
+                .into();
+            let key_range = key_start..self.parser.index;
+
+            let mut value_comments = self.comments()?;
+            match self.parser.eat_whitespace() {
+                Some(b':') => self.parser.index += 1,
+                Some(_) => return json_err!(ExpectedColon, self.parser.index),
+                None => return json_err!(EofWhileParsingObject, self.parser.index),
+            }
+            value_comments.extend(self.comments()?);
+            if recursion_limit == 0 {
+                return json_err!(RecursionLimitExceeded, self.parser.index);
+            }
+            let value = self.value(value_comments, recursion_limit - 1)?;
+            members.push(CstMember {
+                leading_comments: comments,
+                key,
+                key_range,
+                value,
+            });
+
+            comments = self.comments()?;
+            match self.parser.eat_whitespace() {
+                Some(b',') => {
+                    self.parser.index += 1;
+                    comments.extend(self.comments()?);
+                }
+                Some(b'}') => {
+                    self.parser.index += 1;
+                    return Ok(CstKind::Object {
+                        members,
+                        trailing_comments: comments,
+                    });
+                }
+                Some(_) => return json_err!(ExpectedObjectCommaOrEnd, self.parser.index),
+                None => return json_err!(EofWhileParsingObject, self.parser.index),
+            }
+        }
+    }
+
+    /// Check the next token starts a key.
+    fn expect_key(&mut self) -> JsonResult<()> {
+        match self.parser.eat_whitespace() {
+            Some(b'"') => Ok(()),
+            Some(_) => json_err!(KeyMustBeAString, self.parser.index),
+            None => json_err!(EofWhileParsingObject, self.parser.index),
+        }
+    }
+}
@@ -13,6 +13,25 @@ pub enum JsonErrorType {
     /// happens when getting the `Decimal` type or constructing a decimal fails
     InternalError(String),
 
+    /// An array or object has more elements than allowed by
+    /// [ParseOptions::max_array_elements](crate::ParseOptions::max_array_elements) or
+    /// [ParseOptions::max_object_entries](crate::ParseOptions::max_object_entries).
+    TooManyElements,
+
+    /// An object key doesn't sort after the key before it, see
+    /// [ParseOptions::require_sorted_keys](crate::ParseOptions::require_sorted_keys).
+    KeysNotSorted(String),
+
+    /// The total length of strings and keys exceeds
+    /// [ParseOptions::max_total_string_bytes](crate::ParseOptions::max_total_string_bytes).
+    TooManyStringBytes,
+
+    /// The data isn't an array, see [JsonValue::parse_array_best_effort](crate::JsonValue::parse_array_best_effort).
+    ExpectedArray,
+
+    /// EOF while parsing a `/* ... */` comment, see [parse_cst](crate::parse_cst).
+    EofWhileParsingComment,
+
     /// NOTE: all errors from here on are copied from serde_json
     /// [src/error.rs](https://github.com/serde-rs/json/blob/v1.0.107/src/error.rs#L236)
     /// with `Io` and `Message` removed
@@ -85,6 +104,11 @@ impl std::fmt::Display for JsonErrorType {
             Self::FloatExpectingInt => f.write_str("float value was found where an int was expected"),
             Self::DuplicateKey(s) => write!(f, "Detected duplicate key {s:?}"),
             Self::InternalError(s) => write!(f, "Internal error: {s:?}"),
+            Self::TooManyElements => f.write_str("too many elements in array or object"),
+            Self::TooManyStringBytes => f.write_str("too many bytes in strings"),
+            Self::ExpectedArray => f.write_str("expected an array"),
+            Self::EofWhileParsingComment => f.write_str("EOF while parsing a comment"),
+            Self::KeysNotSorted(s) => write!(f, "key {s:?} is not sorted after the previous key"),
             Self::EofWhileParsingList => f.write_str("EOF while parsing a list"),
             Self::EofWhileParsingObject => f.write_str("EOF while parsing an object"),
             Self::EofWhileParsingString => f.write_str("EOF while parsing a string"),
@@ -111,6 +135,42 @@ impl std::fmt::Display for JsonErrorType {
     }
 }
 
+impl JsonErrorType {
+    /// The name of the error type, e.g. `"EofWhileParsingValue"`, for grouping errors in logs and metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FloatExpectingInt => "FloatExpectingInt",
+            Self::DuplicateKey(_) => "DuplicateKey",
+            Self::InternalError(_) => "InternalError",
+            Self::TooManyElements => "TooManyElements",
+            Self::TooManyStringBytes => "TooManyStringBytes",
+            Self::ExpectedArray => "ExpectedArray",
+            Self::EofWhileParsingComment => "EofWhileParsingComment",
+            Self::KeysNotSorted(_) => "KeysNotSorted",
+            Self::EofWhileParsingList => "EofWhileParsingList",
+            Self::EofWhileParsingObject => "EofWhileParsingObject",
+            Self::EofWhileParsingString => "EofWhileParsingString",
+            Self::EofWhileParsingValue => "EofWhileParsingValue",
+            Self::ExpectedColon => "ExpectedColon",
+            Self::ExpectedListCommaOrEnd => "ExpectedListCommaOrEnd",
+            Self::ExpectedObjectCommaOrEnd => "ExpectedObjectCommaOrEnd",
+            Self::ExpectedSomeIdent => "ExpectedSomeIdent",
+            Self::ExpectedSomeValue => "ExpectedSomeValue",
+            Self::InvalidEscape => "InvalidEscape",
+            Self::InvalidNumber => "InvalidNumber",
+            Self::NumberOutOfRange => "NumberOutOfRange",
+            Self::InvalidUnicodeCodePoint => "InvalidUnicodeCodePoint",
+            Self::ControlCharacterWhileParsingString => "ControlCharacterWhileParsingString",
+            Self::KeyMustBeAString => "KeyMustBeAString",
+            Self::LoneLeadingSurrogateInHexEscape => "LoneLeadingSurrogateInHexEscape",
+            Self::TrailingComma => "TrailingComma",
+            Self::TrailingCharacters => "TrailingCharacters",
+            Self::UnexpectedEndOfHexEscape => "UnexpectedEndOfHexEscape",
+            Self::RecursionLimitExceeded => "RecursionLimitExceeded",
+        }
+    }
+}
+
 pub type JsonResult<T> = Result<T, JsonError>;
 
 /// Represents an error from parsing JSON
@@ -136,6 +196,16 @@ impl JsonError {
         format!("{} at {}", self.error_type, position)
     }
 
+    /// Get the details of this error in a structured form, see [ErrorDetail].
+    pub fn to_structured(&self, json_data: &[u8]) -> ErrorDetail {
+        ErrorDetail::new(
+            self.error_type.name(),
+            &self.error_type,
+            self.index,
+            &self.get_position(json_data),
+        )
+    }
+
     pub(crate) fn allowed_if_partial(&self) -> bool {
         matches!(
             self.error_type,
@@ -200,11 +270,86 @@ impl std::fmt::Display for JsonType {
     }
 }
 
-/// Enum representing either a [JsonErrorType] or a WrongType error.
+/// Enum representing either a [JsonErrorType] or an error from reading the wrong type or key.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum JiterErrorType {
     JsonError(JsonErrorType),
-    WrongType { expected: JsonType, actual: JsonType },
+    WrongType {
+        expected: JsonType,
+        actual: JsonType,
+    },
+    /// The next key was not the one expected, `found` is `None` if the object ended instead.
+    UnexpectedKey {
+        expected: String,
+        found: Option<String>,
+    },
+    /// An object key couldn't be parsed into the requested type, the message describes why.
+    InvalidKey {
+        key: String,
+        message: String,
+    },
+    /// An object had a key which wasn't one of the expected fields.
+    UnknownField {
+        field: String,
+    },
+    /// An object was missing a required field.
+    MissingField {
+        field: String,
+    },
+    /// A string wasn't one of the `expected` variant names.
+    UnknownVariant {
+        expected: Vec<String>,
+        found: String,
+    },
+    /// A string was rejected by the predicate passed to `Jiter::next_str_where`.
+    ValidationFailed {
+        value: String,
+    },
+    /// An array had more elements than the buffer it was being read into.
+    ArrayTooLong {
+        capacity: usize,
+    },
+    /// An array read with `Jiter::next_tuple` or entered with `Jiter::expect_array_len` didn't have `expected`
+    /// elements, if it was too long `found` is `expected + 1` since reading stops at the first extra element.
+    WrongArrayLength {
+        expected: usize,
+        found: usize,
+    },
+    /// A string read with `Jiter::next_base64` was not valid base64, the message describes why.
+    #[cfg(feature = "base64")]
+    InvalidBase64(String),
+    /// A string read with `Jiter::next_chrono_datetime` or `Jiter::next_time_datetime` was not a valid
+    /// RFC 3339 date-time, the message describes why.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    InvalidDateTime(String),
+    /// An error raised by a `serde::Deserialize` implementation while reading with `jiter::from_slice`,
+    /// the message describes why.
+    #[cfg(feature = "serde")]
+    Custom(String),
+}
+
+impl JiterErrorType {
+    /// The name of the error type, e.g. `"WrongType"`, or the [JsonErrorType::name] for JSON errors.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::JsonError(error_type) => error_type.name(),
+            Self::WrongType { .. } => "WrongType",
+            Self::UnexpectedKey { .. } => "UnexpectedKey",
+            Self::InvalidKey { .. } => "InvalidKey",
+            Self::UnknownField { .. } => "UnknownField",
+            Self::MissingField { .. } => "MissingField",
+            Self::UnknownVariant { .. } => "UnknownVariant",
+            Self::ValidationFailed { .. } => "ValidationFailed",
+            Self::ArrayTooLong { .. } => "ArrayTooLong",
+            Self::WrongArrayLength { .. } => "WrongArrayLength",
+            #[cfg(feature = "base64")]
+            Self::InvalidBase64(_) => "InvalidBase64",
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            Self::InvalidDateTime(_) => "InvalidDateTime",
+            #[cfg(feature = "serde")]
+            Self::Custom(_) => "Custom",
+        }
+    }
 }
 
 impl std::fmt::Display for JiterErrorType {
@@ -214,6 +359,48 @@ impl std::fmt::Display for JiterErrorType {
             Self::WrongType { expected, actual } => {
                 write!(f, "expected {expected} but found {actual}")
             }
+            Self::UnexpectedKey { expected, found } => match found {
+                Some(found) => write!(f, "expected key \"{expected}\" but found \"{found}\""),
+                None => write!(f, "expected key \"{expected}\" but found end of object"),
+            },
+            Self::InvalidKey { key, message } => write!(f, "invalid key \"{key}\": {message}"),
+            Self::UnknownField { field } => write!(f, "unknown field \"{field}\""),
+            Self::MissingField { field } => write!(f, "missing field \"{field}\""),
+            Self::UnknownVariant { expected, found } => {
+                write!(f, "expected one of [{}] but found \"{found}\"", expected.join(", "))
+            }
+            Self::ValidationFailed { value } => write!(f, "string \"{value}\" failed validation"),
+            Self::ArrayTooLong { capacity } => write!(f, "array has more than {capacity} elements"),
+            Self::WrongArrayLength { expected, found } if found > expected => {
+                write!(f, "expected an array of {expected} elements but found more")
+            }
+            Self::WrongArrayLength { expected, found } => {
+                write!(f, "expected an array of {expected} elements but found {found}")
+            }
+            #[cfg(feature = "base64")]
+            Self::InvalidBase64(message) => write!(f, "invalid base64: {message}"),
+            #[cfg(any(feature = "chrono", feature = "time"))]
+            Self::InvalidDateTime(message) => write!(f, "invalid datetime: {message}"),
+            #[cfg(feature = "serde")]
+            Self::Custom(message) => f.write_str(message),
+        }
+    }
+}
+
+/// An error from converting a [JsonValue](crate::JsonValue) into a Rust type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ValueConversionError {
+    /// The value is not the type required for the conversion.
+    WrongType { expected: JsonType, actual: JsonType },
+    /// The value is an integer which is too large for the target type.
+    NumberOutOfRange,
+}
+
+impl std::fmt::Display for ValueConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongType { expected, actual } => write!(f, "expected {expected} but found {actual}"),
+            Self::NumberOutOfRange => f.write_str("number out of range"),
         }
     }
 }
@@ -231,6 +418,8 @@ impl std::fmt::Display for JiterError {
     }
 }
 
+impl std::error::Error for JiterError {}
+
 impl JiterError {
     pub(crate) fn new(error_type: JiterErrorType, index: usize) -> Self {
         Self { error_type, index }
@@ -245,9 +434,39 @@ impl JiterError {
         format!("{} at {}", self.error_type, position)
     }
 
+    /// Get the details of this error in a structured form, see [ErrorDetail].
+    pub fn to_structured(&self, jiter: &Jiter) -> ErrorDetail {
+        ErrorDetail::new(
+            self.error_type.name(),
+            &self.error_type,
+            self.index,
+            &self.get_position(jiter),
+        )
+    }
+
     pub(crate) fn wrong_type(expected: JsonType, actual: JsonType, index: usize) -> Self {
         Self::new(JiterErrorType::WrongType { expected, actual }, index)
     }
+
+    pub(crate) fn unexpected_key(expected: &str, found: Option<&str>, index: usize) -> Self {
+        Self::new(
+            JiterErrorType::UnexpectedKey {
+                expected: expected.to_owned(),
+                found: found.map(ToOwned::to_owned),
+            },
+            index,
+        )
+    }
+
+    pub(crate) fn unknown_variant<'a>(expected: impl Iterator<Item = &'a str>, found: &str, index: usize) -> Self {
+        Self::new(
+            JiterErrorType::UnknownVariant {
+                expected: expected.map(ToOwned::to_owned).collect(),
+                found: found.to_owned(),
+            },
+            index,
+        )
+    }
 }
 
 impl From<JsonError> for JiterError {
@@ -259,6 +478,51 @@ impl From<JsonError> for JiterError {
     }
 }
 
+/// The details of an error in a structured form, from [JsonError::to_structured] or [JiterError::to_structured].
+///
+/// Useful for emitting machine-readable errors to logs or metrics, with the `serde` feature enabled
+/// this implements `serde::Serialize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorDetail {
+    /// The name of the error type, e.g. `"EofWhileParsingValue"`, see [JiterErrorType::name].
+    pub error_type: &'static str,
+    /// The index in the data where the error occurred.
+    pub index: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number, starting at 1.
+    pub column: usize,
+    /// The error message, without the position.
+    pub message: String,
+}
+
+impl ErrorDetail {
+    fn new(error_type: &'static str, message: &impl std::fmt::Display, index: usize, position: &LinePosition) -> Self {
+        Self {
+            error_type,
+            index,
+            line: position.line,
+            column: position.column,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorDetail {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ErrorDetail", 5)?;
+        state.serialize_field("error_type", self.error_type)?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("line", &self.line)?;
+        state.serialize_field("column", &self.column)?;
+        state.serialize_field("message", &self.message)?;
+        state.end()
+    }
+}
+
 /// Represents a line and column in a file or input string, used for both errors and value positions.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LinePosition {
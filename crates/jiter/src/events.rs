@@ -0,0 +1,150 @@
+use std::borrow::Cow;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigInt;
+
+use crate::jiter::{Jiter, JiterResult};
+use crate::number_decoder::{NumberAny, NumberInt};
+use crate::parse::Peek;
+
+/// A structural event from [Jiter::events].
+///
+/// Strings and keys borrow from the JSON data unless they contain escapes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent<'j> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    #[cfg(feature = "num-bigint")]
+    BigInt(BigInt),
+    Float(f64),
+    Str(Cow<'j, str>),
+    StartArray,
+    EndArray,
+    StartObject,
+    /// An object key, the next event is the start of its value.
+    Key(Cow<'j, str>),
+    EndObject,
+}
+
+/// What the iterator should read next.
+enum Next<'j> {
+    /// a value, with its peek if that's already known
+    Value(Option<Peek>),
+    Key(Cow<'j, str>),
+    /// the next element or key of the innermost container, or its end
+    Step,
+    EndArray,
+    EndObject,
+    Done,
+}
+
+/// An [Iterator] over the structural events of a JSON value, see [Jiter::events].
+pub struct JiterEvents<'a, 'j> {
+    jiter: &'a mut Jiter<'j>,
+    /// whether each container we're currently inside is an object, innermost last
+    stack: Vec<bool>,
+    next: Next<'j>,
+}
+
+impl<'a, 'j> JiterEvents<'a, 'j> {
+    pub(crate) fn new(jiter: &'a mut Jiter<'j>) -> Self {
+        Self {
+            jiter,
+            stack: Vec::new(),
+            next: Next::Value(None),
+        }
+    }
+
+    fn value(&mut self, peek: Option<Peek>) -> JiterResult<JsonEvent<'j>> {
+        let peek = match peek {
+            Some(peek) => peek,
+            None => self.jiter.peek()?,
+        };
+        let event = match peek {
+            Peek::Null => {
+                self.jiter.known_null()?;
+                JsonEvent::Null
+            }
+            Peek::True | Peek::False => JsonEvent::Bool(self.jiter.known_bool(peek)?),
+            Peek::String => JsonEvent::Str(self.jiter.known_scalar_str(peek)?),
+            Peek::Array => {
+                self.next = match self.jiter.known_array()? {
+                    Some(peek) => {
+                        self.stack.push(false);
+                        Next::Value(Some(peek))
+                    }
+                    None => Next::EndArray,
+                };
+                return Ok(JsonEvent::StartArray);
+            }
+            Peek::Object => {
+                self.next = match self.jiter.known_object_cow()? {
+                    Some(key) => {
+                        self.stack.push(true);
+                        Next::Key(key)
+                    }
+                    None => Next::EndObject,
+                };
+                return Ok(JsonEvent::StartObject);
+            }
+            _ => match self.jiter.known_number(peek)? {
+                NumberAny::Int(NumberInt::Int(int)) => JsonEvent::Int(int),
+                #[cfg(feature = "num-bigint")]
+                NumberAny::Int(NumberInt::BigInt(big_int)) => JsonEvent::BigInt(big_int),
+                NumberAny::Float(float) => JsonEvent::Float(float),
+            },
+        };
+        self.after_value();
+        Ok(event)
+    }
+
+    fn step(&mut self) -> JiterResult<JsonEvent<'j>> {
+        if self.stack.last() == Some(&true) {
+            if let Some(key) = self.jiter.next_key_cow()? {
+                self.next = Next::Value(None);
+                Ok(JsonEvent::Key(key))
+            } else {
+                self.stack.pop();
+                self.after_value();
+                Ok(JsonEvent::EndObject)
+            }
+        } else if let Some(peek) = self.jiter.array_step()? {
+            self.value(Some(peek))
+        } else {
+            self.stack.pop();
+            self.after_value();
+            Ok(JsonEvent::EndArray)
+        }
+    }
+
+    fn after_value(&mut self) {
+        self.next = if self.stack.is_empty() { Next::Done } else { Next::Step };
+    }
+}
+
+impl<'j> Iterator for JiterEvents<'_, 'j> {
+    type Item = JiterResult<JsonEvent<'j>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // if reading the event fails, `next` is left as `Done` so the iterator ends after the error
+        let result = match std::mem::replace(&mut self.next, Next::Done) {
+            Next::Done => return None,
+            Next::Value(peek) => self.value(peek),
+            Next::Key(key) => {
+                self.next = Next::Value(None);
+                Ok(JsonEvent::Key(key))
+            }
+            Next::Step => self.step(),
+            Next::EndArray => {
+                self.after_value();
+                Ok(JsonEvent::EndArray)
+            }
+            Next::EndObject => {
+                self.after_value();
+                Ok(JsonEvent::EndObject)
+            }
+        };
+        Some(result)
+    }
+}
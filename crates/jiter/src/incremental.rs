@@ -0,0 +1,154 @@
+use crate::errors::{JiterError, JiterErrorType, JsonErrorType};
+use crate::jiter::{Jiter, JiterResult};
+use crate::value::JsonValue;
+use crate::{ParseOptions, PartialMode};
+
+/// Parses a single top-level JSON array whose data arrives in chunks, e.g. from a socket, yielding each
+/// element as soon as it's complete rather than waiting for the whole array.
+///
+/// Chunks may split the data anywhere, including inside strings and numbers. An element is yielded once the
+/// `,` or `]` after it has arrived, so a number at the end of a chunk isn't mistaken for a complete one.
+/// An incomplete element is parsed again from its start when more data is pushed, so very large elements
+/// are best pushed in large chunks.
+///
+/// ```rust
+/// use jiter::{IncrementalParser, JsonValue};
+///
+/// let mut parser = IncrementalParser::new();
+/// parser.push_bytes(br#"[{"a": "hel"#);
+/// assert_eq!(parser.try_next_value().unwrap(), None);
+/// parser.push_bytes(br#"lo"}, 12"#);
+/// assert_eq!(parser.try_next_value().unwrap(), Some(jiter::json!({"a": "hello"})));
+/// assert_eq!(parser.try_next_value().unwrap(), None);
+/// parser.push_bytes(b"3]");
+/// assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(123)));
+/// assert_eq!(parser.try_next_value().unwrap(), None);
+/// parser.finish().unwrap();
+/// ```
+#[derive(Debug, Default)]
+pub struct IncrementalParser {
+    buffer: Vec<u8>,
+    /// the index in `buffer` of the first byte which hasn't been consumed
+    index: usize,
+    /// the number of consumed bytes which have been removed from the start of `buffer`
+    offset: usize,
+    state: State,
+    options: ParseOptions,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// waiting for the opening `[`
+    #[default]
+    BeforeArray,
+    /// `index` is at the start of the next element
+    AtElement,
+    /// the closing `]` has been read
+    Done,
+}
+
+impl IncrementalParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `options` when parsing, [ParseOptions::partial_mode] is ignored since incomplete data
+    /// is handled by waiting for more.
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = ParseOptions {
+            partial_mode: PartialMode::Off,
+            ..options
+        };
+        self
+    }
+
+    /// Add the next chunk of data.
+    pub fn push_bytes(&mut self, data: &[u8]) {
+        // drop consumed data first, so the buffer only holds the current element and anything after it
+        if self.index > 0 {
+            self.buffer.drain(..self.index);
+            self.offset += self.index;
+            self.index = 0;
+        }
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Get the next element of the array if the data for it has all arrived, `None` if more data is needed
+    /// or the array has ended, see [IncrementalParser::is_done].
+    ///
+    /// Error indexes are relative to the start of all the data pushed, not the current chunk.
+    pub fn try_next_value(&mut self) -> JiterResult<Option<JsonValue<'static>>> {
+        let started = self.state == State::BeforeArray;
+        let data = &self.buffer[self.index..];
+        let len = data.len();
+        let mut jiter = Jiter::new(data).with_options(self.options);
+        let result = match self.state {
+            State::BeforeArray => jiter.next_array().map(|first| {
+                self.index += jiter.current_index();
+                self.state = if first.is_some() { State::AtElement } else { State::Done };
+                None
+            }),
+            State::AtElement => jiter.next_value_owned().and_then(|value| {
+                let next = jiter.array_step()?;
+                self.index += jiter.current_index();
+                if next.is_none() {
+                    self.state = State::Done;
+                }
+                Ok(Some(value))
+            }),
+            State::Done => jiter.finish().map(|()| None),
+        };
+        drop(jiter);
+        match result {
+            // the array has just been entered, so look for its first element
+            Ok(None) if started && self.state == State::AtElement => self.try_next_value(),
+            Err(e) if is_incomplete(&e, len) => Ok(None),
+            Err(mut e) => {
+                e.index += self.offset + self.index;
+                Err(e)
+            }
+            ok => ok,
+        }
+    }
+
+    /// Whether the closing `]` of the array has been read, so there are no more elements.
+    pub fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// Check the data has ended correctly once all of it has been pushed, error if the array isn't complete,
+    /// or anything but whitespace follows it.
+    pub fn finish(&mut self) -> JiterResult<()> {
+        if self.is_done() {
+            Jiter::new(&self.buffer[self.index..]).finish().map_err(|mut e| {
+                e.index += self.offset + self.index;
+                e
+            })
+        } else {
+            let error_type = match self.state {
+                State::BeforeArray => JsonErrorType::EofWhileParsingValue,
+                _ => JsonErrorType::EofWhileParsingList,
+            };
+            Err(JiterError::new(
+                JiterErrorType::JsonError(error_type),
+                self.offset + self.buffer.len(),
+            ))
+        }
+    }
+}
+
+/// Whether `error` may be because the data so far, `len` bytes long, ends part way through a value,
+/// so more data might fix it.
+fn is_incomplete(error: &JiterError, len: usize) -> bool {
+    match &error.error_type {
+        JiterErrorType::JsonError(
+            JsonErrorType::EofWhileParsingList
+            | JsonErrorType::EofWhileParsingObject
+            | JsonErrorType::EofWhileParsingString
+            | JsonErrorType::EofWhileParsingValue,
+        ) => true,
+        // e.g. a number ending in `.` or `e`, or a truncated `true`
+        JiterErrorType::JsonError(_) => error.index >= len,
+        _ => false,
+    }
+}
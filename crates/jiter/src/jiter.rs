@@ -1,30 +1,154 @@
-use crate::errors::{json_error, JiterError, JsonType, LinePosition, DEFAULT_RECURSION_LIMIT};
-use crate::number_decoder::{NumberAny, NumberFloat, NumberInt, NumberRange};
-use crate::parse::{Parser, Peek};
-use crate::string_decoder::{StringDecoder, StringDecoderRange, Tape};
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::Hasher;
+use std::io::Write;
+use std::ops::Range;
+use std::str::FromStr;
+
+use smallvec::{smallvec, SmallVec};
+
+use crate::array_iter::ArrayIter;
+use crate::errors::{json_error, JiterError, JiterErrorType, JsonResult, JsonType, LinePosition};
+use crate::events::JiterEvents;
+use crate::number_decoder::{
+    AbstractNumberDecoder, FloatSource, NumberAny, NumberFloat, NumberInt, NumberIntLenient, NumberRange,
+};
+use crate::parse::{Parser, ParserSnapshot, Peek, StackTop};
+use crate::string_decoder::{
+    EscapeHandler, StringDecoder, StringDecoderRange, StringDecoderRawRange, StringOutput, Tape,
+};
 use crate::value::{take_value_borrowed, take_value_owned, take_value_skip, JsonValue};
-use crate::{JsonError, JsonErrorType, PartialMode};
+use crate::{JsonError, JsonErrorType, ParseOptions};
 
 pub type JiterResult<T> = Result<T, JiterError>;
 
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// The base64 alphabet used by [Jiter::next_base64].
+#[cfg(feature = "base64")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    /// The standard alphabet, using `+` and `/`.
+    Standard,
+    /// The URL and filename safe alphabet, using `-` and `_`.
+    UrlSafe,
+}
+
+#[cfg(feature = "base64")]
+const BASE64_CONFIG: base64::engine::GeneralPurposeConfig = base64::engine::GeneralPurposeConfig::new()
+    .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent);
+#[cfg(feature = "base64")]
+const BASE64_STANDARD: base64::engine::GeneralPurpose =
+    base64::engine::GeneralPurpose::new(&base64::alphabet::STANDARD, BASE64_CONFIG);
+#[cfg(feature = "base64")]
+const BASE64_URL_SAFE: base64::engine::GeneralPurpose =
+    base64::engine::GeneralPurpose::new(&base64::alphabet::URL_SAFE, BASE64_CONFIG);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// An array entered with [Jiter::expect_array_len], and the number of elements found in it so far.
+#[derive(Debug, Clone, Copy)]
+struct ArrayLength {
+    /// the value of `containers.len()` inside the array
+    depth: usize,
+    expected: usize,
+    found: usize,
+}
+
+/// The position of a [Jiter] to go back to, see [Jiter::snapshot].
+///
+/// Only the state of the innermost array or object is kept, rather than the whole stack of arrays
+/// and objects the jiter is inside, which makes taking and restoring a snapshot cheaper than cloning
+/// the jiter.
+#[derive(Debug, Clone)]
+pub struct JiterSnapshot {
+    parser: ParserSnapshot,
+    containers: StackTop<Container>,
+    array_lengths: StackTop<ArrayLength>,
+}
+
+/// Writer for trace events, see [Jiter::with_trace].
+struct Trace<'w>(Box<dyn Write + Send + Sync + 'w>);
+
+impl fmt::Debug for Trace<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Trace")
+    }
+}
+
+impl Trace<'_> {
+    fn event(&mut self, index: usize, event: fmt::Arguments<'_>) {
+        // tracing is a debugging aid, so failing to write an event shouldn't stop parsing
+        let _ = writeln!(self.0, "{event} @ {index}");
+    }
+}
+
+/// Write a trace event if tracing is enabled, this only borrows the `trace` field so it can be used
+/// while a key or string borrowed from the tape is still alive.
+macro_rules! trace {
+    ($jiter:ident, $index:expr, $($arg:tt)+) => {
+        if let Some(trace) = &mut $jiter.trace {
+            trace.event($index, format_args!($($arg)+));
+        }
+    };
+}
+
+/// How [Jiter::next_object_fields] handles keys which aren't one of the expected fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownFields {
+    /// Skip their values.
+    Skip,
+    /// Read their values and return them.
+    Collect,
+    /// Return an [UnknownField](crate::JiterErrorType::UnknownField) error.
+    Error,
+}
+
 /// A JSON iterator.
 #[derive(Debug)]
 pub struct Jiter<'j> {
     data: &'j [u8],
     parser: Parser<'j>,
+    /// scratch buffer strings and keys with escapes are decoded into, reused for every string and key
     tape: Tape,
-    allow_inf_nan: bool,
+    options: ParseOptions,
+    /// arrays and objects the parser is currently inside, innermost last
+    containers: Vec<Container>,
+    trace: Option<Trace<'j>>,
+    /// `decode` from the float decoder used by `next_float`, see [Jiter::with_number_parser]
+    float_decoder: FloatDecodeFn,
+    /// `decode_leading_decimal_point` from the same float decoder, see [ParseOptions::allow_leading_decimal_point]
+    leading_decimal_point_decoder: LeadingDecimalPointDecodeFn,
+    /// keys returned from the registry rather than the JSON data when they match, see [Jiter::with_known_keys]
+    known_keys: &'static [&'static str],
+    /// arrays with a required length the parser is currently inside, innermost last
+    array_lengths: Vec<ArrayLength>,
+    /// whether strings read directly may be truncated, see [Jiter::with_allow_partial_strings]
     allow_partial_strings: bool,
 }
 
+type FloatDecodeFn = fn(&[u8], usize, u8, bool) -> JsonResult<(f64, usize)>;
+type LeadingDecimalPointDecodeFn = fn(&[u8], usize) -> JsonResult<(f64, usize)>;
+
 impl Clone for Jiter<'_> {
-    /// Clone a `Jiter`. Like the default implementation, but a new empty `tape` is used.
+    /// Clone a `Jiter`. Like the default implementation, but a new empty `tape` is used,
+    /// and the clone doesn't write trace events or report progress.
     fn clone(&self) -> Self {
         Self {
             data: self.data,
             parser: self.parser.clone(),
             tape: Tape::default(),
-            allow_inf_nan: self.allow_inf_nan,
+            options: self.options,
+            containers: self.containers.clone(),
+            trace: None,
+            float_decoder: self.float_decoder,
+            leading_decimal_point_decoder: self.leading_decimal_point_decoder,
+            known_keys: self.known_keys,
+            array_lengths: self.array_lengths.clone(),
             allow_partial_strings: self.allow_partial_strings,
         }
     }
@@ -41,21 +165,250 @@ impl<'j> Jiter<'j> {
             data,
             parser: Parser::new(data),
             tape: Tape::default(),
-            allow_inf_nan: false,
+            options: ParseOptions::default(),
+            containers: Vec::new(),
+            trace: None,
+            float_decoder: NumberFloat::decode,
+            leading_decimal_point_decoder: NumberFloat::decode_leading_decimal_point,
+            known_keys: &[],
+            array_lengths: Vec::new(),
             allow_partial_strings: false,
         }
     }
 
+    /// Constructs a new `Jiter` from a `str`.
+    ///
+    /// Since `data` is known to be valid UTF-8, strings and keys which aren't ASCII are returned without
+    /// validating them again, which makes reading them faster than with [Jiter::new].
+    #[allow(clippy::should_implement_trait)] // `FromStr` can't borrow from the input
+    pub fn from_str(data: &'j str) -> Self {
+        Self {
+            parser: Parser::from_str(data),
+            ..Self::new(data.as_bytes())
+        }
+    }
+
+    /// Presize the scratch buffer which strings and keys containing escapes are decoded into.
+    ///
+    /// The buffer is reused for every string and key read with this `Jiter`, so it only reallocates
+    /// when a string is longer than any decoded before; presizing it avoids those reallocations too.
+    pub fn with_string_scratch_capacity(mut self, capacity: usize) -> Self {
+        self.tape.reserve(capacity);
+        self
+    }
+
+    /// The current capacity of the scratch buffer, see [Jiter::with_string_scratch_capacity].
+    pub fn string_scratch_capacity(&self) -> usize {
+        self.tape.capacity()
+    }
+
+    /// Set all [ParseOptions] at once, replacing any options set previously.
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self.parser.set_options(options);
+        self
+    }
+
     pub fn with_allow_inf_nan(mut self) -> Self {
-        self.allow_inf_nan = true;
+        self.options.allow_inf_nan = true;
+        self
+    }
+
+    /// Error if arrays and objects are nested more than `limit` deep when reading or skipping values,
+    /// see [ParseOptions::recursion_limit].
+    pub fn with_recursion_limit(mut self, limit: u8) -> Self {
+        self.options.recursion_limit = Some(limit);
+        self
+    }
+
+    /// Allow control characters (`\x00` to `\x1F`), like tabs and newlines, unescaped in strings and keys.
+    pub fn with_allow_raw_control_chars(mut self) -> Self {
+        self.options.allow_raw_control_chars = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Allow JSON5 whitespace between tokens, see [ParseOptions::extended_whitespace].
+    pub fn with_extended_whitespace(mut self) -> Self {
+        self.options.extended_whitespace = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Skip `//` and `/* */` comments, see [ParseOptions::allow_comments].
+    pub fn with_allow_comments(mut self) -> Self {
+        self.options.allow_comments = true;
+        self.parser.set_options(self.options);
         self
     }
 
+    /// Skip comments as with [Jiter::with_allow_comments], and record the range of each comment skipped
+    /// by appending it to `buffer`, retrieve the buffer with [Jiter::take_comments].
+    ///
+    /// This lets tools report or strip comments without building a syntax tree, see [parse_cst](crate::parse_cst)
+    /// to keep comments along with the values they precede.
+    pub fn with_comment_sink(mut self, buffer: Vec<Range<usize>>) -> Self {
+        self.parser.comments = Some(buffer);
+        self.with_allow_comments()
+    }
+
+    /// Take the comment ranges recorded so far, see [Jiter::with_comment_sink], later comments are still recorded.
+    ///
+    /// Returns an empty `Vec` if there's no comment sink.
+    pub fn take_comments(&mut self) -> Vec<Range<usize>> {
+        match &mut self.parser.comments {
+            Some(comments) => std::mem::take(comments),
+            None => Vec::new(),
+        }
+    }
+
+    /// Error if any array has more than `max` elements, see [ParseOptions::max_array_elements].
+    pub fn with_max_array_elements(mut self, max: usize) -> Self {
+        self.options.max_array_elements = Some(max);
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Error if any object has more than `max` entries, see [ParseOptions::max_object_entries].
+    pub fn with_max_object_entries(mut self, max: usize) -> Self {
+        self.options.max_object_entries = Some(max);
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Error if the total length of decoded strings and keys exceeds `max` bytes,
+    /// see [ParseOptions::max_total_string_bytes].
+    pub fn with_max_total_string_bytes(mut self, max: usize) -> Self {
+        self.options.max_total_string_bytes = Some(max);
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Register keys which, when found in an object, are returned as these `&'static str`s rather than
+    /// as slices of the JSON data, so callers can compare keys by pointer, e.g. `key.as_ptr() == ID.as_ptr()`.
+    ///
+    /// Keys which don't match are returned as usual. `keys` is searched linearly, so should be small.
+    ///
+    /// ```rust
+    /// use jiter::Jiter;
+    ///
+    /// static KEYS: &[&str] = &["id", "name"];
+    ///
+    /// let mut jiter = Jiter::new(br#"{"id": 1, "other": 2}"#).with_known_keys(KEYS);
+    /// let key = jiter.next_object().unwrap().unwrap();
+    /// assert_eq!(key.as_ptr(), KEYS[0].as_ptr());
+    /// jiter.next_int().unwrap();
+    /// assert_eq!(jiter.next_key().unwrap(), Some("other"));
+    /// ```
+    pub fn with_known_keys(mut self, keys: &'static [&'static str]) -> Self {
+        self.known_keys = keys;
+        self
+    }
+
+    /// Use `handler` to decide what to do with unknown escapes in strings and keys, e.g. to accept `\'`.
+    ///
+    /// ```rust
+    /// use jiter::{EscapeAction, Jiter};
+    ///
+    /// let mut jiter = Jiter::new(br#""it\'s""#).with_escape_handler(|c| match c {
+    ///     b'\'' => EscapeAction::Replace('\''),
+    ///     _ => EscapeAction::Error,
+    /// });
+    /// assert_eq!(jiter.next_str().unwrap(), "it's");
+    /// ```
+    pub fn with_escape_handler(mut self, handler: EscapeHandler) -> Self {
+        self.options.escape_handler = Some(handler);
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// When reading values, replace arrays and objects nested beyond the recursion limit with `null`
+    /// rather than erroring, see [ParseOptions::max_depth_error_recovery].
+    pub fn with_max_depth_error_recovery(mut self) -> Self {
+        self.options.max_depth_error_recovery = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// When reading values, read `NaN`, `Infinity` and `-Infinity` as `null`, like JavaScript's `JSON.stringify`,
+    /// see [ParseOptions::nan_inf_as_null].
+    pub fn with_nan_inf_as_null(mut self) -> Self {
+        self.options.nan_inf_as_null = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Read the JavaScript literal `undefined` as `null`, see [ParseOptions::undefined_as_null].
+    pub fn with_undefined_as_null(mut self) -> Self {
+        self.options.undefined_as_null = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Allow numbers with no digits before the decimal point like `.5`, see
+    /// [ParseOptions::allow_leading_decimal_point].
+    pub fn with_allow_leading_decimal_point(mut self) -> Self {
+        self.options.allow_leading_decimal_point = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Error if object keys aren't in sorted order, see [ParseOptions::require_sorted_keys].
+    pub fn with_require_sorted_keys(mut self) -> Self {
+        self.options.require_sorted_keys = true;
+        self.parser.set_options(self.options);
+        self
+    }
+
+    /// Allow strings read with [Jiter::next_str], [Jiter::next_bytes] and similar methods to be truncated.
+    ///
+    /// Values read with [Jiter::next_value] are still parsed strictly, set [ParseOptions::partial_mode]
+    /// with [Jiter::with_options] to allow partial values.
     pub fn with_allow_partial_strings(mut self) -> Self {
         self.allow_partial_strings = true;
         self
     }
 
+    /// Whether strings read directly may be truncated, either by [Jiter::with_allow_partial_strings]
+    /// or [ParseOptions::partial_mode].
+    fn partial_strings(&self) -> bool {
+        self.allow_partial_strings || self.options.partial_mode.allow_trailing_str()
+    }
+
+    /// Use the float decoder `D` instead of the default [NumberFloat] when reading floats with
+    /// [Jiter::next_float] and the methods built on it, e.g. for a specific rounding mode or to reject
+    /// subnormal numbers.
+    ///
+    /// Numbers read by [Jiter::next_value] and [Jiter::next_number] are not affected.
+    pub fn with_number_parser<D: AbstractNumberDecoder<Output = f64>>(mut self) -> Self {
+        self.float_decoder = D::decode;
+        self.leading_decimal_point_decoder = D::decode_leading_decimal_point;
+        self
+    }
+
+    /// Write a line to `writer` for each structural event as the parser advances, to help debug parsing logic.
+    ///
+    /// Events are the start and end of arrays and objects, object keys, and the type of each value read,
+    /// each followed by the parser's index at that point, e.g. `[ @ 0`, `key(foo) @ 7` or `int @ 8`.
+    /// When tracing is not enabled, the only cost is checking whether it is.
+    pub fn with_trace(mut self, writer: impl Write + Send + Sync + 'j) -> Self {
+        self.trace = Some(Trace(Box::new(writer)));
+        self
+    }
+
+    /// Call `callback` with the current index roughly every `every_n_bytes` bytes as the data is parsed,
+    /// including while reading values with e.g. [Jiter::next_value], to report progress on large inputs.
+    ///
+    /// Progress is checked before each token, so the callback may be called late after a long string.
+    /// When no callback is set, the only cost is one comparison per token.
+    ///
+    /// The callback may borrow from the caller, e.g. a progress bar, and like [Jiter::with_trace] it must be
+    /// `Send` and `Sync` so the `Jiter` still is. Clones of the `Jiter` don't call it.
+    pub fn with_progress(mut self, every_n_bytes: usize, callback: impl FnMut(usize) + Send + Sync + 'j) -> Self {
+        self.parser.set_progress(every_n_bytes, callback);
+        self
+    }
+
     /// Get the current [LinePosition] of the parser.
     pub fn current_position(&self) -> LinePosition {
         self.parser.current_position()
@@ -66,11 +419,86 @@ impl<'j> Jiter<'j> {
         self.parser.index
     }
 
+    /// Take a [JiterSnapshot] of the current position, to go back to with [Jiter::restore], e.g. to try
+    /// reading a value one way and fall back to another if that fails.
+    pub fn snapshot(&self) -> JiterSnapshot {
+        JiterSnapshot {
+            parser: self.parser.snapshot(),
+            containers: StackTop::new(&self.containers),
+            array_lengths: StackTop::new(&self.array_lengths),
+        }
+    }
+
+    /// Go back to the position `snapshot` was taken at, the same snapshot can be restored any number of times.
+    ///
+    /// The snapshot must be restored before the jiter leaves the array or object the snapshot was taken in,
+    /// the arrays and objects entered since are dropped but those left can't be recovered. Options and the
+    /// comment sink buffer taken with [Jiter::take_comments] aren't restored.
+    pub fn restore(&mut self, snapshot: &JiterSnapshot) {
+        self.parser.restore(&snapshot.parser);
+        snapshot.containers.restore(&mut self.containers);
+        snapshot.array_lengths.restore(&mut self.array_lengths);
+    }
+
+    /// Get the number of arrays and objects the parser is currently inside.
+    ///
+    /// This is `0` at the top level, entering a non-empty array or object (e.g. via [Jiter::next_array]
+    /// or [Jiter::next_object]) increases the depth, reaching the end of it decreases the depth.
+    pub fn current_depth(&self) -> usize {
+        self.containers.len()
+    }
+
+    /// Get the type of the innermost array or object the parser is currently inside, `None` at the top level.
+    ///
+    /// This is [JsonType::Array] or [JsonType::Object], tracked the same way as [Jiter::current_depth].
+    pub fn current_container(&self) -> Option<JsonType> {
+        self.containers.last().map(|container| match container {
+            Container::Array => JsonType::Array,
+            Container::Object => JsonType::Object,
+        })
+    }
+
     /// Get a slice of the underlying JSON data from `start` to `current_index`.
     pub fn slice_to_current(&self, start: usize) -> &'j [u8] {
         &self.data[start..self.current_index()]
     }
 
+    /// Create a new `Jiter` which parses `raw`, a slice of this `Jiter`'s data, e.g. from [Jiter::slice_to_current].
+    ///
+    /// The new `Jiter` has default options, so a value captured under one set of options can be re-parsed
+    /// with another without copying it. Indexes, including those of errors, and positions are relative
+    /// to the whole of this `Jiter`'s data, not to `raw`.
+    ///
+    /// ```rust
+    /// use jiter::Jiter;
+    ///
+    /// let data = br#"{"meta": [1, NaN]}"#;
+    /// let mut jiter = Jiter::new(data).with_allow_inf_nan();
+    /// assert_eq!(jiter.next_object().unwrap(), Some("meta"));
+    /// let peek = jiter.peek().unwrap();
+    /// let start = jiter.current_index();
+    /// jiter.known_skip(peek).unwrap();
+    /// let raw = jiter.slice_to_current(start);
+    /// assert_eq!(raw, b"[1, NaN]");
+    ///
+    /// // re-parse the captured value strictly, the error index is within `data`
+    /// let e = jiter.subparser(raw).next_value().unwrap_err();
+    /// assert_eq!(e.index, 13);
+    /// ```
+    ///
+    /// # Panics
+    /// If `raw` is not a slice of this `Jiter`'s data.
+    pub fn subparser(&self, raw: &'j [u8]) -> Jiter<'j> {
+        let start = (raw.as_ptr() as usize).wrapping_sub(self.data.as_ptr() as usize);
+        assert!(
+            start <= self.data.len() && raw.len() <= self.data.len() - start,
+            "`raw` is not a slice of this Jiter's data"
+        );
+        let mut jiter = Jiter::new(&self.data[..start + raw.len()]);
+        jiter.parser.index = start;
+        jiter
+    }
+
     /// Convert an error index to a [LinePosition].
     ///
     /// # Arguments
@@ -84,6 +512,58 @@ impl<'j> Jiter<'j> {
         self.parser.peek().map_err(Into::into)
     }
 
+    /// Skip a UTF-8 byte order mark if the parser is at the start of the data, then skip any whitespace.
+    pub fn skip_bom_and_whitespace(&mut self) {
+        if self.parser.index == 0 && self.data.starts_with(UTF8_BOM) {
+            self.parser.index = UTF8_BOM.len();
+        }
+        self.parser.eat_whitespace();
+    }
+
+    /// Peek at the first JSON value in the data, skipping a leading byte order mark and whitespace.
+    ///
+    /// Unlike [Jiter::peek], this returns `Ok(None)` if the data is empty or only whitespace, so an empty
+    /// document can be told apart from a truncated one.
+    pub fn first_peek(&mut self) -> JiterResult<Option<Peek>> {
+        self.skip_bom_and_whitespace();
+        match self.data.get(self.parser.index) {
+            Some(&next) => Ok(Some(self.parser.peek_of(next))),
+            None => Ok(None),
+        }
+    }
+
+    /// Peek at the next document in newline-delimited JSON (JSONL / NDJSON) data, where each line holds one value.
+    ///
+    /// Returns `Ok(None)` once only whitespace is left. Any whitespace, including blank lines, is allowed between
+    /// documents, but documents after the first must start on a new line, so e.g. `{} {}` is an error.
+    /// The previous document must have been read completely, e.g. with [Jiter::next_value] or [Jiter::next_skip].
+    ///
+    /// ```rust
+    /// use jiter::{Jiter, JsonValue};
+    ///
+    /// let mut jiter = Jiter::new(b"{\"a\": 1}\n[true]\r\n\n2");
+    /// let mut values = Vec::new();
+    /// while let Some(peek) = jiter.next_document().unwrap() {
+    ///     values.push(jiter.known_value(peek).unwrap());
+    /// }
+    /// assert_eq!(values, [jiter::json!({"a": 1}), jiter::json!([true]), JsonValue::Int(2)]);
+    /// ```
+    pub fn next_document(&mut self) -> JiterResult<Option<Peek>> {
+        let previous_end = self.parser.index;
+        let Some(next) = self.parser.eat_whitespace() else {
+            return Ok(None);
+        };
+        if previous_end > 0 && !self.data[previous_end..self.parser.index].contains(&b'\n') {
+            return Err(json_error!(TrailingCharacters, self.parser.index).into());
+        }
+        let peek = self.parser.peek_of(next);
+        match peek {
+            Peek::Null | Peek::True | Peek::False | Peek::String | Peek::Array | Peek::Object => Ok(Some(peek)),
+            _ if self.is_num(peek) => Ok(Some(peek)),
+            _ => Err(json_error!(ExpectedSomeValue, self.parser.index).into()),
+        }
+    }
+
     /// Assuming the next value is `null`, consume it. Error if it is not `null`, or is invalid JSON.
     pub fn next_null(&mut self) -> JiterResult<()> {
         let peek = self.peek()?;
@@ -95,6 +575,7 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is `null`, consume it.
     pub fn known_null(&mut self) -> JiterResult<()> {
+        trace!(self, self.parser.index, "null");
         self.parser.consume_null()?;
         Ok(())
     }
@@ -110,6 +591,7 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is `true` or `false`, parse it.
     pub fn known_bool(&mut self, peek: Peek) -> JiterResult<bool> {
+        trace!(self, self.parser.index, "bool");
         match peek {
             Peek::True => {
                 self.parser.consume_true()?;
@@ -134,8 +616,9 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is a number, parse it.
     pub fn known_number(&mut self, peek: Peek) -> JiterResult<NumberAny> {
+        trace!(self, self.parser.index, "number");
         self.parser
-            .consume_number::<NumberAny>(peek.into_inner(), self.allow_inf_nan)
+            .consume_number::<NumberAny>(peek.into_inner(), self.options.allow_inf_nan)
             .map_err(|e| self.maybe_number_error(e, JsonType::Int, peek))
     }
 
@@ -147,8 +630,32 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is an integer, parse it.
     pub fn known_int(&mut self, peek: Peek) -> JiterResult<NumberInt> {
+        trace!(self, self.parser.index, "int");
+        self.parser
+            .consume_number::<NumberInt>(peek.into_inner(), self.options.allow_inf_nan)
+            .map_err(|e| {
+                if e.error_type == JsonErrorType::FloatExpectingInt {
+                    JiterError::wrong_type(JsonType::Int, JsonType::Float, self.parser.index)
+                } else {
+                    self.maybe_number_error(e, JsonType::Int, peek)
+                }
+            })
+    }
+
+    /// Like [Jiter::next_int], but integers with leading zeros like `007` are accepted, see [NumberIntLenient].
+    ///
+    /// # Returns
+    /// The integer, and whether it had leading zeros.
+    pub fn next_int_lenient(&mut self) -> JiterResult<(NumberInt, bool)> {
+        let peek = self.peek()?;
+        self.known_int_lenient(peek)
+    }
+
+    /// Knowing the next value is an integer, parse it, accepting leading zeros.
+    pub fn known_int_lenient(&mut self, peek: Peek) -> JiterResult<(NumberInt, bool)> {
+        trace!(self, self.parser.index, "int");
         self.parser
-            .consume_number::<NumberInt>(peek.into_inner(), self.allow_inf_nan)
+            .consume_number::<NumberIntLenient>(peek.into_inner(), self.options.allow_inf_nan)
             .map_err(|e| {
                 if e.error_type == JsonErrorType::FloatExpectingInt {
                     JiterError::wrong_type(JsonType::Int, JsonType::Float, self.parser.index)
@@ -166,9 +673,31 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is a float, parse it.
     pub fn known_float(&mut self, peek: Peek) -> JiterResult<f64> {
-        self.parser
-            .consume_number::<NumberFloat>(peek.into_inner(), self.allow_inf_nan)
-            .map_err(|e| self.maybe_number_error(e, JsonType::Float, peek))
+        trace!(self, self.parser.index, "float");
+        match self.decode_float(peek.into_inner()) {
+            Ok((float, index)) => {
+                self.parser.index = index;
+                Ok(float)
+            }
+            Err(e) => Err(self.maybe_number_error(e, JsonType::Float, peek)),
+        }
+    }
+
+    /// Assuming the next value is a float, consume it along with its [FloatSource].
+    ///
+    /// This lets callers tell a literal `Infinity` or `NaN` apart from a finite number which overflowed to `inf`,
+    /// e.g. `1e999`. Error if it is not a float, or is invalid JSON.
+    pub fn next_float_with_source(&mut self) -> JiterResult<(f64, FloatSource)> {
+        let peek = self.peek()?;
+        self.known_float_with_source(peek)
+    }
+
+    /// Knowing the next value is a float, parse it along with its [FloatSource].
+    pub fn known_float_with_source(&mut self, peek: Peek) -> JiterResult<(f64, FloatSource)> {
+        let start = self.parser.index;
+        let float = self.known_float(peek)?;
+        let source = FloatSource::classify(float, &self.data[start..self.parser.index]);
+        Ok((float, source))
     }
 
     /// Assuming the next value is a number, consume it and return bytes from the original JSON data.
@@ -179,9 +708,10 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is a number, parse it and return bytes from the original JSON data.
     fn known_number_bytes(&mut self, peek: Peek) -> JiterResult<&[u8]> {
+        trace!(self, self.parser.index, "number");
         match self
             .parser
-            .consume_number::<NumberRange>(peek.into_inner(), self.allow_inf_nan)
+            .consume_number::<NumberRange>(peek.into_inner(), self.options.allow_inf_nan)
         {
             Ok(numbe_range) => Ok(&self.data[numbe_range.range]),
             Err(e) => Err(self.maybe_number_error(e, JsonType::Float, peek)),
@@ -199,12 +729,171 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is a string, parse it.
     pub fn known_str(&mut self) -> JiterResult<&str> {
-        match self
+        Ok(self.known_str_output()?.as_str())
+    }
+
+    /// Like [Jiter::known_str], but strings without escapes are borrowed from the data rather than the tape.
+    pub(crate) fn known_str_output(&mut self) -> JiterResult<StringOutput<'_, 'j>> {
+        trace!(self, self.parser.index, "string");
+        let partial_strings = self.partial_strings();
+        let output = self
             .parser
-            .consume_string::<StringDecoder>(&mut self.tape, self.allow_partial_strings)
-        {
-            Ok(output) => Ok(output.as_str()),
-            Err(e) => Err(e.into()),
+            .consume_string::<StringDecoder>(&mut self.tape, partial_strings)?;
+        Ok(output)
+    }
+
+    /// Assuming the next value is a string, consume it and check it with `predicate`.
+    ///
+    /// Error if it is not a string, `predicate` returns `false`, or it is invalid JSON.
+    /// If `predicate` rejects the string, the [JiterErrorType::ValidationFailed] error points at its start.
+    pub fn next_str_where(&mut self, predicate: impl FnOnce(&str) -> bool) -> JiterResult<&str> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_str_where(predicate),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it and check it with `predicate`.
+    pub fn known_str_where(&mut self, predicate: impl FnOnce(&str) -> bool) -> JiterResult<&str> {
+        let index = self.parser.index;
+        let s = self.known_str()?;
+        if predicate(s) {
+            Ok(s)
+        } else {
+            Err(JiterError::new(
+                JiterErrorType::ValidationFailed { value: s.to_owned() },
+                index,
+            ))
+        }
+    }
+
+    /// Assuming the next value is a string, consume it and return the value of the variant it names.
+    ///
+    /// `variants` maps names to values, e.g. `&[("active", Status::Active), ("closed", Status::Closed)]`.
+    /// Error if it is not a string, is not one of the names, or is invalid JSON.
+    pub fn next_enum<T: Copy>(&mut self, variants: &[(&str, T)]) -> JiterResult<T> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_enum(variants),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it and return the value of the variant it names.
+    pub fn known_enum<T: Copy>(&mut self, variants: &[(&str, T)]) -> JiterResult<T> {
+        let index = self.parser.index;
+        let s = self.known_str()?;
+        match variants.iter().find(|(name, _)| *name == s) {
+            Some((_, value)) => Ok(*value),
+            None => Err(JiterError::unknown_variant(
+                variants.iter().map(|(name, _)| *name),
+                s,
+                index,
+            )),
+        }
+    }
+
+    /// Assuming the next value is a string, consume it and decode it as base64 using `alphabet`.
+    ///
+    /// Padding is optional. Error if it is not a string, is not valid base64, or is invalid JSON.
+    #[cfg(feature = "base64")]
+    pub fn next_base64(&mut self, alphabet: Base64Alphabet) -> JiterResult<Vec<u8>> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_base64(alphabet),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it and decode it as base64 using `alphabet`.
+    #[cfg(feature = "base64")]
+    pub fn known_base64(&mut self, alphabet: Base64Alphabet) -> JiterResult<Vec<u8>> {
+        use base64::Engine;
+
+        let start = self.parser.index;
+        let s = self.known_str()?;
+        let result = match alphabet {
+            Base64Alphabet::Standard => BASE64_STANDARD.decode(s),
+            Base64Alphabet::UrlSafe => BASE64_URL_SAFE.decode(s),
+        };
+        result.map_err(|e| JiterError::new(JiterErrorType::InvalidBase64(e.to_string()), start))
+    }
+
+    /// Assuming the next value is a string, consume it and parse it as an RFC 3339 date-time using `chrono`.
+    ///
+    /// Error if it is not a string, is not a valid RFC 3339 date-time, or is invalid JSON.
+    #[cfg(feature = "chrono")]
+    pub fn next_chrono_datetime(&mut self) -> JiterResult<chrono::DateTime<chrono::FixedOffset>> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_chrono_datetime(),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it as an RFC 3339 date-time using `chrono`.
+    #[cfg(feature = "chrono")]
+    pub fn known_chrono_datetime(&mut self) -> JiterResult<chrono::DateTime<chrono::FixedOffset>> {
+        let start = self.parser.index;
+        let s = self.known_str()?;
+        chrono::DateTime::parse_from_rfc3339(s)
+            .map_err(|e| JiterError::new(JiterErrorType::InvalidDateTime(e.to_string()), start))
+    }
+
+    /// Assuming the next value is a string, consume it and parse it as an RFC 3339 date-time using `time`.
+    ///
+    /// Error if it is not a string, is not a valid RFC 3339 date-time, or is invalid JSON.
+    #[cfg(feature = "time")]
+    pub fn next_time_datetime(&mut self) -> JiterResult<time::OffsetDateTime> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_time_datetime(),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it as an RFC 3339 date-time using `time`.
+    #[cfg(feature = "time")]
+    pub fn known_time_datetime(&mut self) -> JiterResult<time::OffsetDateTime> {
+        let start = self.parser.index;
+        let s = self.known_str()?;
+        time::OffsetDateTime::parse(s, &time::format_description::well_known::Rfc3339)
+            .map_err(|e| JiterError::new(JiterErrorType::InvalidDateTime(e.to_string()), start))
+    }
+
+    /// Assuming the next value is a string or a number, consume it and return its text.
+    ///
+    /// Strings are decoded, numbers are returned exactly as they appear in the JSON data. This is useful
+    /// for fields which are sometimes quoted and sometimes not, e.g. `"42"` or `42`.
+    /// Error if it is not a string or number, or is invalid JSON.
+    pub fn next_scalar_str(&mut self) -> JiterResult<Cow<'j, str>> {
+        let peek = self.peek()?;
+        self.known_scalar_str(peek)
+    }
+
+    /// Knowing the next value is a string or a number, parse it and return its text.
+    pub fn known_scalar_str(&mut self, peek: Peek) -> JiterResult<Cow<'j, str>> {
+        if peek == Peek::String {
+            trace!(self, self.parser.index, "string");
+            let partial_strings = self.partial_strings();
+            let output = self
+                .parser
+                .consume_string::<StringDecoder>(&mut self.tape, partial_strings)?;
+            Ok(output.into())
+        } else if self.is_num(peek) {
+            trace!(self, self.parser.index, "number");
+            let start = self.parser.index;
+            let range = self
+                .parser
+                .consume_number::<NumberRange>(peek.into_inner(), self.options.allow_inf_nan)?
+                .range;
+            let data: &'j [u8] = self.data;
+            // numbers are always ASCII, so this can't fail
+            let number = std::str::from_utf8(&data[range]).map_err(|_| json_error!(InvalidNumber, start))?;
+            Ok(Cow::Borrowed(number))
+        } else {
+            Err(self.wrong_type(JsonType::String, peek))
         }
     }
 
@@ -219,13 +908,108 @@ impl<'j> Jiter<'j> {
 
     /// Knowing the next value is a string, parse it and return bytes from the original JSON data.
     pub fn known_bytes(&mut self) -> JiterResult<&[u8]> {
+        trace!(self, self.parser.index, "string");
+        let partial_strings = self.partial_strings();
         let range = self
             .parser
-            .consume_string::<StringDecoderRange>(&mut self.tape, self.allow_partial_strings)?;
+            .consume_string::<StringDecoderRange>(&mut self.tape, partial_strings)?;
         Ok(&self.data[range])
     }
 
+    /// Assuming the next value is a string, consume it and return the bytes between the quotes, along with
+    /// whether the string contains any escape sequences.
+    ///
+    /// *WARNING:* the bytes are not validated as UTF-8 and escape sequences are not decoded, they're returned
+    /// exactly as they appear in the JSON data. Escapes are still checked to be valid JSON escapes.
+    /// If the flag is `false`, the bytes are the string's content, otherwise escapes need to be decoded.
+    pub fn next_raw_str_bytes(&mut self) -> JiterResult<(&'j [u8], bool)> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::String => self.known_raw_str_bytes(),
+            _ => Err(self.wrong_type(JsonType::String, peek)),
+        }
+    }
+
+    /// Knowing the next value is a string, parse it and return the raw bytes between the quotes and whether
+    /// the string contains escapes, see [Jiter::next_raw_str_bytes].
+    pub fn known_raw_str_bytes(&mut self) -> JiterResult<(&'j [u8], bool)> {
+        trace!(self, self.parser.index, "string");
+        let partial_strings = self.partial_strings();
+        let (range, has_escapes) = self
+            .parser
+            .consume_string::<StringDecoderRawRange>(&mut self.tape, partial_strings)?;
+        let data: &'j [u8] = self.data;
+        Ok((&data[range], has_escapes))
+    }
+
+    /// Assuming the next value is a string, find the number of bytes between its quotes without moving the parser.
+    ///
+    /// This is the length of the string as it appears in the JSON data, with escapes, so it's an upper bound
+    /// on the length of the decoded string and can be used to size a buffer before reading the string.
+    /// Error if it is not a string, or is invalid JSON.
+    pub fn peek_string_byte_len(&self) -> JiterResult<usize> {
+        let mut parser = self.parser.clone();
+        let peek = parser.peek()?;
+        if peek != Peek::String {
+            // only clone the whole jiter when there's an error to build
+            let mut jiter = self.clone();
+            jiter.parser = parser;
+            return Err(jiter.wrong_type(JsonType::String, peek));
+        }
+        // `StringDecoderRange` doesn't decode escapes, so the tape is never written to
+        let range = parser.consume_string::<StringDecoderRange>(&mut Tape::default(), self.partial_strings())?;
+        Ok(range.len())
+    }
+
+    /// Peek at whether the next value is an empty array or object, without consuming it.
+    ///
+    /// # Returns
+    /// `Some(true)` for `[]` or `{}` (including with whitespace inside), `Some(false)` for a non-empty
+    /// array or object, and `None` if the next value is not an array or object.
+    pub fn peek_is_empty_container(&mut self) -> JiterResult<Option<bool>> {
+        let close = match self.peek()? {
+            Peek::Array => b']',
+            Peek::Object => b'}',
+            _ => return Ok(None),
+        };
+        let mut parser = self.parser.clone();
+        parser.index += 1;
+        match parser.eat_whitespace() {
+            Some(next) => Ok(Some(next == close)),
+            None if close == b']' => Err(json_error!(EofWhileParsingList, parser.index).into()),
+            None => Err(json_error!(EofWhileParsingObject, parser.index).into()),
+        }
+    }
+
+    /// Peek at whether the next value is an integer rather than a float, without consuming it.
+    ///
+    /// Use this to choose between [Jiter::next_int] and [Jiter::next_float] rather than retrying after a
+    /// [FloatExpectingInt](crate::JsonErrorType::FloatExpectingInt) error. Error if the number is invalid JSON.
+    ///
+    /// # Returns
+    /// `Some(true)` for an integer, `Some(false)` for a number with a fraction or exponent, or `NaN`/`Infinity`,
+    /// and `None` if the next value is not a number.
+    pub fn peek_number_is_integer(&mut self) -> JiterResult<Option<bool>> {
+        let peek = self.peek()?;
+        let (number, _) = if self.parser.at_leading_decimal_point(peek.into_inner()) {
+            NumberRange::decode_leading_decimal_point(self.data, self.parser.index)?
+        } else if peek.is_num() {
+            NumberRange::decode(
+                self.data,
+                self.parser.index,
+                peek.into_inner(),
+                self.options.allow_inf_nan,
+            )?
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(number.is_int))
+    }
+
     /// Parse the next JSON value and return it as a [JsonValue]. Error if it is invalid JSON.
+    ///
+    /// Strings and object keys without escapes are [Cow::Borrowed] from the JSON data, only those with
+    /// escapes are copied.
     pub fn next_value(&mut self) -> JiterResult<JsonValue<'j>> {
         let peek = self.peek()?;
         self.known_value(peek)
@@ -236,13 +1020,33 @@ impl<'j> Jiter<'j> {
     /// # Arguments
     /// - `peek`: The [Peek] of the next JSON value.
     pub fn known_value(&mut self, peek: Peek) -> JiterResult<JsonValue<'j>> {
+        self.known_value_with_limit(peek, self.options.recursion_limit_or_default())
+    }
+
+    /// Parse the next JSON value and return it as a [JsonValue], limiting how deeply nested the value may be.
+    ///
+    /// `recursion_limit` applies only to this value, and replaces the default limit for it. This is useful
+    /// for reading an untrusted value which is embedded in a larger trusted document.
+    /// Error if it is invalid JSON or is nested more deeply than `recursion_limit`.
+    pub fn next_value_with_limit(&mut self, recursion_limit: u8) -> JiterResult<JsonValue<'j>> {
+        let peek = self.peek()?;
+        self.known_value_with_limit(peek, recursion_limit)
+    }
+
+    /// Parse the next JSON value and return it as a [JsonValue], limiting how deeply nested the value may be.
+    ///
+    /// # Arguments
+    /// - `peek`: The [Peek] of the next JSON value.
+    /// - `recursion_limit`: The maximum nesting depth of the value, see [Jiter::next_value_with_limit].
+    pub fn known_value_with_limit(&mut self, peek: Peek, recursion_limit: u8) -> JiterResult<JsonValue<'j>> {
+        trace!(self, self.parser.index, "value");
         take_value_borrowed(
             peek,
             &mut self.parser,
             &mut self.tape,
-            DEFAULT_RECURSION_LIMIT,
-            self.allow_inf_nan,
-            PartialMode::Off,
+            recursion_limit,
+            self.options.allow_inf_nan,
+            self.options.partial_mode,
         )
         .map_err(Into::into)
     }
@@ -262,16 +1066,52 @@ impl<'j> Jiter<'j> {
     /// # Arguments
     /// - `peek`: The [Peek] of the next JSON value.
     pub fn known_skip(&mut self, peek: Peek) -> JiterResult<()> {
+        trace!(self, self.parser.index, "skip");
         take_value_skip(
             peek,
             &mut self.parser,
             &mut self.tape,
-            DEFAULT_RECURSION_LIMIT,
-            self.allow_inf_nan,
+            self.options.recursion_limit_or_default(),
+            self.options.allow_inf_nan,
         )
         .map_err(Into::into)
     }
 
+    /// Like [Jiter::next_skip], but the raw bytes of the value are fed into `hasher` as it's skipped,
+    /// so changes to a value can be detected without building it.
+    ///
+    /// The bytes are hashed exactly as they appear in the JSON data, so values which only differ in
+    /// whitespace, key order, escapes or number formatting hash differently, see
+    /// [JsonValue::canonical_hash] to hash values by their content.
+    pub fn next_skip_hashed<H: Hasher>(&mut self, hasher: &mut H) -> JiterResult<()> {
+        let peek = self.peek()?;
+        let start = self.parser.index;
+        self.known_skip(peek)?;
+        hasher.write(&self.data[start..self.parser.index]);
+        Ok(())
+    }
+
+    /// Skip the next JSON value and return the bytes of the JSON data spanning it, without surrounding whitespace,
+    /// like `serde_json`'s `RawValue`, e.g. to store part of a document to parse later. Error if it is invalid JSON.
+    ///
+    /// *WARNING:* Like [Jiter::next_skip], this does not check that strings would be valid UTF-8.
+    ///
+    /// ```rust
+    /// use jiter::{Jiter, JsonValue};
+    ///
+    /// let mut jiter = Jiter::new(br#"{"config": {"a": [1, 2]} , "id": 1}"#);
+    /// assert_eq!(jiter.next_object().unwrap(), Some("config"));
+    /// let raw = jiter.next_raw_value().unwrap();
+    /// assert_eq!(raw, br#"{"a": [1, 2]}"#);
+    /// assert!(JsonValue::parse(raw, false).is_ok());
+    /// ```
+    pub fn next_raw_value(&mut self) -> JiterResult<&'j [u8]> {
+        let peek = self.peek()?;
+        let start = self.parser.index;
+        self.known_skip(peek)?;
+        Ok(&self.data[start..self.parser.index])
+    }
+
     /// Parse the next JSON value and return it as a [JsonValue] with static lifetime. Error if it is invalid JSON.
     pub fn next_value_owned(&mut self) -> JiterResult<JsonValue<'static>> {
         let peek = self.peek()?;
@@ -283,13 +1123,14 @@ impl<'j> Jiter<'j> {
     /// # Arguments
     /// - `peek`: The [Peek] of the next JSON value.
     pub fn known_value_owned(&mut self, peek: Peek) -> JiterResult<JsonValue<'static>> {
+        trace!(self, self.parser.index, "value");
         take_value_owned(
             peek,
             &mut self.parser,
             &mut self.tape,
-            DEFAULT_RECURSION_LIMIT,
-            self.allow_inf_nan,
-            PartialMode::Off,
+            self.options.recursion_limit_or_default(),
+            self.options.allow_inf_nan,
+            self.options.partial_mode,
         )
         .map_err(Into::into)
     }
@@ -309,12 +1150,260 @@ impl<'j> Jiter<'j> {
 
     /// Assuming the next value is an array, peat at the first value.
     pub fn known_array(&mut self) -> JiterResult<Option<Peek>> {
-        self.parser.array_first().map_err(Into::into)
+        trace!(self, self.parser.index, "[");
+        let peek = self.parser.array_first()?;
+        if peek.is_some() {
+            self.containers.push(Container::Array);
+        } else {
+            trace!(self, self.parser.index - 1, "]");
+        }
+        Ok(peek)
+    }
+
+    /// Like [Jiter::next_array], but error unless the array has exactly `len` elements.
+    ///
+    /// The length is checked as the array is read with [Jiter::array_step], so a
+    /// [WrongArrayLength](JiterErrorType::WrongArrayLength) error is raised at the first element after
+    /// the `len`th, or at the end of the array if it has fewer elements.
+    pub fn expect_array_len(&mut self, len: usize) -> JiterResult<Option<Peek>> {
+        let peek = self.next_array()?;
+        if peek.is_some() {
+            self.array_lengths.push(ArrayLength {
+                depth: self.containers.len(),
+                expected: len,
+                found: 0,
+            });
+            self.check_array_length(true)?;
+        } else if len != 0 {
+            return Err(JiterError::new(
+                JiterErrorType::WrongArrayLength {
+                    expected: len,
+                    found: 0,
+                },
+                self.parser.index - 1,
+            ));
+        }
+        Ok(peek)
     }
 
     /// Peek at the next value in an array.
     pub fn array_step(&mut self) -> JiterResult<Option<Peek>> {
-        self.parser.array_step().map_err(Into::into)
+        let peek = self.parser.array_step()?;
+        self.check_array_length(peek.is_some())?;
+        if peek.is_none() {
+            self.containers.pop();
+            trace!(self, self.parser.index - 1, "]");
+        }
+        Ok(peek)
+    }
+
+    /// If the current array was entered with [Jiter::expect_array_len], count the element just found, or check
+    /// the length at the end of the array if `more` is false.
+    fn check_array_length(&mut self, more: bool) -> JiterResult<()> {
+        let depth = self.containers.len();
+        let Some(length) = self.array_lengths.last_mut().filter(|length| length.depth == depth) else {
+            return Ok(());
+        };
+        let index = if more {
+            length.found += 1;
+            if length.found <= length.expected {
+                return Ok(());
+            }
+            self.parser.index
+        } else {
+            if length.found == length.expected {
+                self.array_lengths.pop();
+                return Ok(());
+            }
+            self.parser.index - 1
+        };
+        Err(JiterError::new(
+            JiterErrorType::WrongArrayLength {
+                expected: length.expected,
+                found: length.found,
+            },
+            index,
+        ))
+    }
+
+    /// Assuming the next value is an array of numbers, read them into `out`, returning the number of elements read.
+    ///
+    /// This avoids allocating when the maximum length of the array is known in advance.
+    /// Error if it is not an array, an element is not a number, or the array has more elements than `out`.
+    pub fn read_f64_array_into(&mut self, out: &mut [f64]) -> JiterResult<usize> {
+        self.read_array_into(out, Self::known_float)
+    }
+
+    /// Like [Jiter::read_f64_array_into], but for an array of `f32`s.
+    pub fn read_f32_array_into(&mut self, out: &mut [f32]) -> JiterResult<usize> {
+        self.read_array_into(out, |jiter, peek| jiter.known_float(peek).map(|f| f as f32))
+    }
+
+    /// Like [Jiter::read_f64_array_into], but for an array of `i64`s.
+    ///
+    /// Error if an element is a float, or is too large for an `i64`.
+    pub fn read_i64_array_into(&mut self, out: &mut [i64]) -> JiterResult<usize> {
+        self.read_array_into(out, Self::known_i64)
+    }
+
+    /// Assuming the next value is an array of exactly `N` numbers, read them into an array, e.g. `[x, y, z]`.
+    ///
+    /// Error if it is not an array, an element is not a number, or the array doesn't have `N` elements.
+    pub fn next_tuple_f64<const N: usize>(&mut self) -> JiterResult<[f64; N]> {
+        self.next_tuple(Self::known_float)
+    }
+
+    /// Like [Jiter::next_tuple_f64], but for an array of `i64`s.
+    pub fn next_tuple_i64<const N: usize>(&mut self) -> JiterResult<[i64; N]> {
+        self.next_tuple(Self::known_i64)
+    }
+
+    /// Assuming the next value is an array of exactly `N` elements, read them into an array using `read`.
+    ///
+    /// `read` is called with the [Peek] of each element, e.g. `jiter.next_tuple::<2, _>(Jiter::known_bool)`.
+    /// Error if it is not an array, `read` fails, or the array doesn't have `N` elements.
+    pub fn next_tuple<const N: usize, T: Default>(
+        &mut self,
+        read: impl Fn(&mut Self, Peek) -> JiterResult<T>,
+    ) -> JiterResult<[T; N]> {
+        let mut out: [T; N] = std::array::from_fn(|_| T::default());
+        // the length is checked as the array is read, so there's never more than `N` elements
+        let mut next = self.expect_array_len(N)?;
+        let mut count = 0;
+        while let Some(peek) = next {
+            out[count] = read(self, peek)?;
+            count += 1;
+            next = self.array_step()?;
+        }
+        Ok(out)
+    }
+
+    /// Assuming the next value is an array of strings, read all of them.
+    ///
+    /// Strings borrow from the JSON data unless they contain escapes.
+    /// Error if it is not an array, an element is not a string, or is invalid JSON.
+    pub fn read_str_array(&mut self) -> JiterResult<Vec<Cow<'j, str>>> {
+        self.read_array(|jiter, peek| match peek {
+            Peek::String => jiter.known_scalar_str(peek),
+            _ => Err(jiter.wrong_type(JsonType::String, peek)),
+        })
+    }
+
+    /// Assuming the next value is an array of integers, read all of them.
+    ///
+    /// Error if it is not an array, an element is not an integer or is too large for an `i64`, or is invalid JSON.
+    pub fn read_i64_array(&mut self) -> JiterResult<Vec<i64>> {
+        self.read_array(Self::known_i64)
+    }
+
+    /// Assuming the next value is an array of numbers, read all of them as floats.
+    ///
+    /// Error if it is not an array, an element is not a number, or is invalid JSON.
+    ///
+    /// This is faster than reading each element with [Jiter::array_step] and [Jiter::known_float], since
+    /// elements go straight from the parser to the float decoder.
+    pub fn read_f64_array(&mut self) -> JiterResult<Vec<f64>> {
+        if self.trace.is_some() {
+            return self.read_array(Self::known_float);
+        }
+        let Some(mut peek) = self.next_array()? else {
+            return Ok(Vec::new());
+        };
+        let mut out = Vec::new();
+        loop {
+            let first = peek.into_inner();
+            match self.decode_float(first) {
+                Ok((float, index)) => {
+                    self.parser.index = index;
+                    out.push(float);
+                }
+                Err(e) => return Err(self.maybe_number_error(e, JsonType::Float, peek)),
+            }
+            // `Jiter::array_step` only adds tracing and `expect_array_len` checks, neither apply here
+            match self.parser.array_step()? {
+                Some(next) => peek = next,
+                None => break,
+            }
+        }
+        self.containers.pop();
+        Ok(out)
+    }
+
+    /// Assuming the next value is an array of booleans, read all of them.
+    ///
+    /// Error if it is not an array, an element is not a boolean, or is invalid JSON.
+    pub fn read_bool_array(&mut self) -> JiterResult<Vec<bool>> {
+        self.read_array(Self::known_bool)
+    }
+
+    /// Assuming the next value is an array, fold over its elements, calling `f` with the accumulator and this
+    /// `Jiter` positioned at each element in turn.
+    ///
+    /// `f` reads the element however it likes, e.g. with [Jiter::next_float] or [Jiter::next_value], and anything
+    /// it leaves unread is skipped. Only one element is parsed at a time, so huge arrays can be counted, summed
+    /// or sampled in constant memory.
+    pub fn fold_array<B>(&mut self, init: B, mut f: impl FnMut(B, &mut Self) -> JiterResult<B>) -> JiterResult<B> {
+        let mut acc = init;
+        let depth = self.containers.len() + 1;
+        let mut next = self.next_array()?;
+        while next.is_some() {
+            acc = f(acc, self)?;
+            if self.containers.len() > depth {
+                self.skip_to_depth(depth)?;
+            } else {
+                self.skip_pending_value()?;
+            }
+            next = self.array_step()?;
+        }
+        Ok(acc)
+    }
+
+    /// Assuming the next value is an array, iterate over the [Peek] of each of its elements, see [ArrayIter].
+    ///
+    /// Unlike a loop over [Jiter::array_step], elements which aren't read are skipped.
+    pub fn array_iter(&mut self) -> JiterResult<ArrayIter<'_, 'j>> {
+        ArrayIter::new(self)
+    }
+
+    fn read_array<T>(&mut self, read: impl Fn(&mut Self, Peek) -> JiterResult<T>) -> JiterResult<Vec<T>> {
+        let mut out = Vec::new();
+        let mut next = self.next_array()?;
+        while let Some(peek) = next {
+            out.push(read(self, peek)?);
+            next = self.array_step()?;
+        }
+        Ok(out)
+    }
+
+    fn known_i64(&mut self, peek: Peek) -> JiterResult<i64> {
+        #[cfg(feature = "num-bigint")]
+        let start = self.parser.index;
+        match self.known_int(peek)? {
+            NumberInt::Int(i) => Ok(i),
+            #[cfg(feature = "num-bigint")]
+            NumberInt::BigInt(_) => Err(json_error!(NumberOutOfRange, start).into()),
+        }
+    }
+
+    fn read_array_into<T>(
+        &mut self,
+        out: &mut [T],
+        read: impl Fn(&mut Self, Peek) -> JiterResult<T>,
+    ) -> JiterResult<usize> {
+        let mut count = 0;
+        let mut next = self.next_array()?;
+        while let Some(peek) = next {
+            let Some(slot) = out.get_mut(count) else {
+                return Err(JiterError::new(
+                    JiterErrorType::ArrayTooLong { capacity: out.len() },
+                    self.parser.index,
+                ));
+            };
+            *slot = read(self, peek)?;
+            count += 1;
+            next = self.array_step()?;
+        }
+        Ok(count)
     }
 
     /// Assuming the next value is an object, consume the first key.
@@ -332,8 +1421,63 @@ impl<'j> Jiter<'j> {
 
     /// Assuming the next value is an object, conssume the first key and return bytes from the original JSON data.
     pub fn known_object(&mut self) -> JiterResult<Option<&str>> {
+        Ok(self.known_object_output()?.map(|s| s.as_str()))
+    }
+
+    /// Like [Jiter::known_object], but the key is returned as a `Cow` which borrows from the JSON data
+    /// where possible, so it can outlive the next call.
+    pub(crate) fn known_object_cow(&mut self) -> JiterResult<Option<Cow<'j, str>>> {
+        Ok(self.known_object_output()?.map(Into::into))
+    }
+
+    pub(crate) fn known_object_output(&mut self) -> JiterResult<Option<StringOutput<'_, 'j>>> {
+        trace!(self, self.parser.index, "{{");
         let op_str = self.parser.object_first::<StringDecoder>(&mut self.tape)?;
-        Ok(op_str.map(|s| s.as_str()))
+        if let Some(key) = &op_str {
+            self.containers.push(Container::Object);
+            trace!(self, self.parser.index, "key({})", key.as_str());
+        } else {
+            trace!(self, self.parser.index - 1, "}}");
+        }
+        Ok(op_str.map(|key| known_key(self.known_keys, key)))
+    }
+
+    /// Assuming the next value is an object, find the string value of `tag_key` without moving the parser.
+    ///
+    /// This is useful for discriminated unions, where a tag like `"type"` determines how the rest of the
+    /// object should be read, but the tag may not be the first key. The object is scanned on a clone of this
+    /// `Jiter`, so the whole object can still be read afterwards.
+    /// Returns `None` if the object has no such key, error if the tag's value is not a string or the object
+    /// is invalid JSON.
+    pub fn peek_object_tag(&self, tag_key: &str) -> JiterResult<Option<String>> {
+        let mut jiter = self.clone();
+        let mut is_tag = jiter.next_object()?.map(|key| key == tag_key);
+        while let Some(found) = is_tag {
+            if found {
+                return jiter.next_str().map(|tag| Some(tag.to_owned()));
+            }
+            jiter.next_skip()?;
+            is_tag = jiter.next_key()?.map(|key| key == tag_key);
+        }
+        Ok(None)
+    }
+
+    /// Assuming the next value is an object, check whether it contains `key` without moving the parser.
+    ///
+    /// Like [Jiter::peek_object_tag], the object is scanned on a clone of this `Jiter`, skipping values,
+    /// so the object can be read normally afterwards. Error if it is not an object, or is invalid JSON
+    /// up to the key.
+    pub fn object_contains_key(&self, key: &str) -> JiterResult<bool> {
+        let mut jiter = self.clone();
+        let mut found = jiter.next_object()?.map(|k| k == key);
+        while let Some(is_key) = found {
+            if is_key {
+                return Ok(true);
+            }
+            jiter.next_skip()?;
+            found = jiter.next_key()?.map(|k| k == key);
+        }
+        Ok(false)
     }
 
     /// Assuming the next value is an object, peek at the first key.
@@ -341,7 +1485,19 @@ impl<'j> Jiter<'j> {
         let peek = self.peek()?;
         match peek {
             Peek::Object => {
+                trace!(self, self.parser.index, "{{");
                 let op_range = self.parser.object_first::<StringDecoderRange>(&mut self.tape)?;
+                if let Some(range) = &op_range {
+                    self.containers.push(Container::Object);
+                    trace!(
+                        self,
+                        self.parser.index,
+                        "key({})",
+                        String::from_utf8_lossy(&self.data[range.clone()])
+                    );
+                } else {
+                    trace!(self, self.parser.index - 1, "}}");
+                }
                 Ok(op_range.map(|r| &self.data[r]))
             }
             _ => Err(self.wrong_type(JsonType::Object, peek)),
@@ -350,21 +1506,275 @@ impl<'j> Jiter<'j> {
 
     /// Get the next key in an object, or `None` if there are no more keys.
     pub fn next_key(&mut self) -> JiterResult<Option<&str>> {
+        Ok(self.next_key_output()?.map(|s| s.as_str()))
+    }
+
+    /// Like [Jiter::next_key], but the key is returned as a `Cow`, see [Jiter::known_object_cow].
+    pub(crate) fn next_key_cow(&mut self) -> JiterResult<Option<Cow<'j, str>>> {
+        Ok(self.next_key_output()?.map(Into::into))
+    }
+
+    pub(crate) fn next_key_output(&mut self) -> JiterResult<Option<StringOutput<'_, 'j>>> {
         let strs = self.parser.object_step::<StringDecoder>(&mut self.tape)?;
-        Ok(strs.map(|s| s.as_str()))
+        if let Some(key) = &strs {
+            trace!(self, self.parser.index, "key({})", key.as_str());
+        } else {
+            self.containers.pop();
+            trace!(self, self.parser.index - 1, "}}");
+        }
+        Ok(strs.map(|key| known_key(self.known_keys, key)))
+    }
+
+    /// Read the next key in an object, and error if it is not `expected`.
+    ///
+    /// On success the parser is positioned to read the key's value, otherwise an
+    /// [UnexpectedKey](crate::JiterErrorType::UnexpectedKey) error is returned, including if the object has ended.
+    pub fn expect_key(&mut self, expected: &str) -> JiterResult<()> {
+        let index = self.parser.index;
+        match self.next_key()? {
+            Some(key) if key == expected => Ok(()),
+            found => Err(JiterError::unexpected_key(expected, found, index)),
+        }
+    }
+
+    /// Assuming the next value is an object, consume the first key and parse it into `T`,
+    /// see [Jiter::next_key_as].
+    pub fn next_object_as<T: FromStr>(&mut self) -> JiterResult<Option<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        let key = parse_key(self.next_object()?);
+        key.map_err(|error_type| JiterError::new(error_type, self.parser.last_key_index()))
+    }
+
+    /// Get the next key in an object and parse it into `T`, or `None` if there are no more keys.
+    ///
+    /// JSON keys are always strings, this is for keys representing other types, e.g. `{"1": ..., "2": ...}`
+    /// read with `T = i32`. If parsing fails an [InvalidKey](crate::JiterErrorType::InvalidKey) error
+    /// is returned at the start of the key.
+    pub fn next_key_as<T: FromStr>(&mut self) -> JiterResult<Option<T>>
+    where
+        T::Err: fmt::Display,
+    {
+        let key = parse_key(self.next_key()?);
+        key.map_err(|error_type| JiterError::new(error_type, self.parser.last_key_index()))
+    }
+
+    /// Assuming the next value is an object, read it as a struct with the given `fields`, calling `read_field`
+    /// with the index of each field found to read its value.
+    ///
+    /// `fields` are `(name, required)` pairs, fields are expected in this order, but other orders are accepted.
+    /// Keys which aren't one of the `fields` are handled according to `unknown`, if they are collected they're
+    /// returned in the order they were found. If a required field is missing a
+    /// [MissingField](crate::JiterErrorType::MissingField) error is returned at the end of the object.
+    ///
+    /// ```rust
+    /// use jiter::{Jiter, NumberInt, UnknownFields};
+    ///
+    /// let mut jiter = Jiter::new(br#"{"id": 1, "extra": [true], "name": "Anne"}"#);
+    /// let (mut id, mut name) = (None, None);
+    /// let unknown = jiter
+    ///     .next_object_fields(&[("id", true), ("name", false)], UnknownFields::Collect, |jiter, field| {
+    ///         match field {
+    ///             0 => id = Some(jiter.next_int()?),
+    ///             _ => name = Some(jiter.next_str()?.to_string()),
+    ///         }
+    ///         Ok(())
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(id, Some(NumberInt::Int(1)));
+    /// assert_eq!(name.as_deref(), Some("Anne"));
+    /// assert_eq!(unknown.len(), 1);
+    /// assert_eq!(unknown[0].0, "extra");
+    /// ```
+    pub fn next_object_fields(
+        &mut self,
+        fields: &[(&str, bool)],
+        unknown: UnknownFields,
+        read_field: impl FnMut(&mut Self, usize) -> JiterResult<()>,
+    ) -> JiterResult<Vec<(Cow<'j, str>, JsonValue<'j>)>> {
+        let peek = self.peek()?;
+        match peek {
+            Peek::Object => self.known_object_fields(fields, unknown, read_field),
+            _ => Err(self.wrong_type(JsonType::Object, peek)),
+        }
+    }
+
+    /// Knowing the next value is an object, read it as a struct with the given `fields`,
+    /// see [Jiter::next_object_fields].
+    pub fn known_object_fields(
+        &mut self,
+        fields: &[(&str, bool)],
+        unknown: UnknownFields,
+        mut read_field: impl FnMut(&mut Self, usize) -> JiterResult<()>,
+    ) -> JiterResult<Vec<(Cow<'j, str>, JsonValue<'j>)>> {
+        let mut found: SmallVec<[bool; 16]> = smallvec![false; fields.len()];
+        let mut unknown_fields = Vec::new();
+        let mut expected = 0;
+        let mut next_key = self.known_object_cow()?;
+        while let Some(key) = next_key {
+            // fast path: fields are usually in the expected order
+            let field = match fields.get(expected) {
+                Some((name, _)) if key == *name => Some(expected),
+                _ => fields.iter().position(|(name, _)| key == *name),
+            };
+            match (field, unknown) {
+                (Some(field), _) => {
+                    found[field] = true;
+                    expected = field + 1;
+                    read_field(self, field)?;
+                }
+                (None, UnknownFields::Skip) => self.next_skip()?,
+                (None, UnknownFields::Collect) => {
+                    let value = self.next_value()?;
+                    unknown_fields.push((key, value));
+                }
+                (None, UnknownFields::Error) => {
+                    let error_type = JiterErrorType::UnknownField {
+                        field: key.into_owned(),
+                    };
+                    return Err(JiterError::new(error_type, self.parser.last_key_index()));
+                }
+            }
+            next_key = self.next_key_cow()?;
+        }
+        match fields
+            .iter()
+            .zip(found)
+            .find(|((_, required), found)| *required && !found)
+        {
+            Some(((name, _), _)) => {
+                let error_type = JiterErrorType::MissingField {
+                    field: (*name).to_owned(),
+                };
+                Err(JiterError::new(error_type, self.parser.index - 1))
+            }
+            None => Ok(unknown_fields),
+        }
     }
 
     /// Get the next key in an object as bytes, or `None` if there are no more keys.
     pub fn next_key_bytes(&mut self) -> JiterResult<Option<&[u8]>> {
         let op_range = self.parser.object_step::<StringDecoderRange>(&mut self.tape)?;
+        if let Some(range) = &op_range {
+            trace!(
+                self,
+                self.parser.index,
+                "key({})",
+                String::from_utf8_lossy(&self.data[range.clone()])
+            );
+        } else {
+            self.containers.pop();
+            trace!(self, self.parser.index - 1, "}}");
+        }
         Ok(op_range.map(|r| &self.data[r]))
     }
 
+    /// Skip the rest of the current arrays and objects until the parser is back at `target_depth`,
+    /// see [Jiter::current_depth].
+    ///
+    /// This can be called either before or after reading a value inside a container, any value
+    /// which hasn't been read yet is skipped along with all remaining siblings.
+    /// If the parser is already at or above `target_depth`, this does nothing.
+    pub fn skip_to_depth(&mut self, target_depth: usize) -> JiterResult<()> {
+        while self.containers.len() > target_depth {
+            let Some(&container) = self.containers.last() else {
+                break;
+            };
+            self.skip_rest_of_container(container)?;
+        }
+        Ok(())
+    }
+
+    /// Count the elements remaining in the current array, without moving the parser.
+    ///
+    /// Any element which has been peeked at but not read yet is included in the count. This scans forward
+    /// through the rest of the array, so it's roughly as expensive as skipping it.
+    /// Returns `None` if the parser is not currently inside an array, error if the rest of the array is invalid JSON.
+    pub fn remaining_array_count(&self) -> JiterResult<Option<usize>> {
+        self.remaining_count(Container::Array)
+    }
+
+    /// Count the entries remaining in the current object, without moving the parser.
+    ///
+    /// If a key has been read but its value hasn't, that entry is included in the count.
+    /// Returns `None` if the parser is not currently inside an object, error if the rest of the object is invalid JSON.
+    pub fn remaining_object_count(&self) -> JiterResult<Option<usize>> {
+        self.remaining_count(Container::Object)
+    }
+
+    fn remaining_count(&self, expected: Container) -> JiterResult<Option<usize>> {
+        if self.containers.last() != Some(&expected) {
+            return Ok(None);
+        }
+        let mut jiter = self.clone();
+        let depth = jiter.containers.len();
+        let mut count = usize::from(jiter.skip_pending_value()?);
+        while jiter.containers.len() == depth {
+            let more = match expected {
+                Container::Array => jiter.array_step()?.is_some(),
+                Container::Object => jiter.next_key_bytes()?.is_some(),
+            };
+            if more {
+                jiter.next_skip()?;
+                count += 1;
+            }
+        }
+        Ok(Some(count))
+    }
+
+    /// Skip the remaining values in `container`, including any value which is still pending.
+    fn skip_rest_of_container(&mut self, container: Container) -> JiterResult<()> {
+        self.skip_pending_value()?;
+        match container {
+            Container::Array => {
+                while self.array_step()?.is_some() {
+                    self.next_skip()?;
+                }
+            }
+            Container::Object => {
+                while self.next_key_bytes()?.is_some() {
+                    self.next_skip()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Skip the next value in the current container if it hasn't been read yet, returning whether there was one.
+    pub(crate) fn skip_pending_value(&mut self) -> JiterResult<bool> {
+        // if the next character isn't a separator or the end of the container, a value is still pending
+        match self.parser.peek() {
+            Ok(peek) if !matches!(peek.into_inner(), b',' | b']' | b'}') => {
+                self.known_skip(peek)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Iterate over the structural events of the next JSON value, see [JsonEvent](crate::JsonEvent).
+    ///
+    /// The iterator ends after the value, or after the first error, the parser is then positioned after the
+    /// value, so e.g. [Jiter::finish] can be called to check for trailing data.
+    pub fn events(&mut self) -> JiterEvents<'_, 'j> {
+        JiterEvents::new(self)
+    }
+
     /// Finish parsing the JSON data. Error if there is more data to be parsed.
     pub fn finish(&mut self) -> JiterResult<()> {
         self.parser.finish().map_err(Into::into)
     }
 
+    /// Finish parsing the JSON data, returning any data after the last value instead of erroring.
+    ///
+    /// Whitespace after the value is skipped, so the result is empty if the data is complete.
+    /// [Jiter::current_index] is left pointing at the start of the remaining data.
+    pub fn finish_and_remaining(&mut self) -> &'j [u8] {
+        self.parser.eat_whitespace();
+        &self.data[self.parser.index..]
+    }
+
     fn wrong_type(&self, expected: JsonType, peek: Peek) -> JiterError {
         match peek {
             Peek::True | Peek::False => JiterError::wrong_type(expected, JsonType::Bool, self.parser.index),
@@ -372,14 +1782,14 @@ impl<'j> Jiter<'j> {
             Peek::String => JiterError::wrong_type(expected, JsonType::String, self.parser.index),
             Peek::Array => JiterError::wrong_type(expected, JsonType::Array, self.parser.index),
             Peek::Object => JiterError::wrong_type(expected, JsonType::Object, self.parser.index),
-            _ if peek.is_num() => self.wrong_num(peek.into_inner(), expected),
+            _ if self.is_num(peek) => self.wrong_num(peek.into_inner(), expected),
             _ => json_error!(ExpectedSomeValue, self.parser.index).into(),
         }
     }
 
     fn wrong_num(&self, first: u8, expected: JsonType) -> JiterError {
         let mut parser2 = self.parser.clone();
-        let actual = match parser2.consume_number::<NumberAny>(first, self.allow_inf_nan) {
+        let actual = match parser2.consume_number::<NumberAny>(first, self.options.allow_inf_nan) {
             Ok(NumberAny::Int { .. }) => JsonType::Int,
             Ok(NumberAny::Float { .. }) => JsonType::Float,
             Err(e) => return e.into(),
@@ -387,11 +1797,67 @@ impl<'j> Jiter<'j> {
         JiterError::wrong_type(expected, actual, self.parser.index)
     }
 
+    /// Like [Peek::is_num], but also true for numbers like `.5` if they're allowed.
+    fn is_num(&self, peek: Peek) -> bool {
+        peek.is_num() || self.parser.at_leading_decimal_point(peek.into_inner())
+    }
+
+    /// Decode the float starting with `first` with the float decoder, without moving the parser.
+    fn decode_float(&self, first: u8) -> JsonResult<(f64, usize)> {
+        if self.parser.at_leading_decimal_point(first) {
+            (self.leading_decimal_point_decoder)(self.data, self.parser.index)
+        } else {
+            (self.float_decoder)(self.data, self.parser.index, first, self.options.allow_inf_nan)
+        }
+    }
+
     fn maybe_number_error(&self, e: JsonError, expected: JsonType, peek: Peek) -> JiterError {
-        if peek.is_num() {
+        if self.is_num(peek) {
             e.into()
         } else {
             self.wrong_type(expected, peek)
         }
     }
 }
+
+/// Fold over the elements of the JSON array in `data`, see [Jiter::fold_array].
+///
+/// ```rust
+/// let data = b"[1.5, 2, 3.5]";
+/// let (count, sum) =
+///     jiter::fold_array(data, (0, 0.0), |(count, sum), jiter| Ok((count + 1, sum + jiter.next_float()?))).unwrap();
+/// assert_eq!((count, sum), (3, 7.0));
+/// ```
+pub fn fold_array<'j, B>(
+    data: &'j [u8],
+    init: B,
+    f: impl FnMut(B, &mut Jiter<'j>) -> JiterResult<B>,
+) -> JiterResult<B> {
+    let mut jiter = Jiter::new(data);
+    let acc = jiter.fold_array(init, f)?;
+    jiter.finish()?;
+    Ok(acc)
+}
+
+/// If `key` is one of `known_keys`, return the known key instead, see [Jiter::with_known_keys].
+fn known_key<'t, 'j>(known_keys: &'static [&'static str], key: StringOutput<'t, 'j>) -> StringOutput<'t, 'j> {
+    match known_keys.iter().find(|known| **known == key.as_str()) {
+        Some(known) => StringOutput::Data(known, key.ascii_only()),
+        None => key,
+    }
+}
+
+/// Parse a key into `T`, callers add the index of the key's opening quote to the error, see
+/// [Parser::last_key_index].
+fn parse_key<T: FromStr>(key: Option<&str>) -> Result<Option<T>, JiterErrorType>
+where
+    T::Err: fmt::Display,
+{
+    let Some(key) = key else {
+        return Ok(None);
+    };
+    key.parse().map(Some).map_err(|e: T::Err| JiterErrorType::InvalidKey {
+        key: key.to_owned(),
+        message: e.to_string(),
+    })
+}
@@ -0,0 +1,51 @@
+use std::cell::RefCell;
+
+use ahash::AHashSet;
+
+/// A set of object keys which can be shared between many parses, see [JsonValue::parse_with_keyset](crate::JsonValue::parse_with_keyset).
+///
+/// Each distinct key is stored once, and parsed objects borrow their keys from the set, so parsing many
+/// documents with the same keys, e.g. records with a common schema, doesn't allocate a copy of each key
+/// for every document.
+///
+/// The set grows without limit as new keys are seen, call [KeySet::clear] to free the keys once no values
+/// borrowing from the set are left.
+#[derive(Debug, Default)]
+pub struct KeySet {
+    keys: RefCell<AHashSet<Box<str>>>,
+}
+
+impl KeySet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of distinct keys in the set.
+    pub fn len(&self) -> usize {
+        self.keys.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.borrow().is_empty()
+    }
+
+    /// Remove all keys from the set.
+    pub fn clear(&mut self) {
+        self.keys.get_mut().clear();
+    }
+
+    /// Get the copy of `key` in the set, adding it if it's not there yet.
+    pub(crate) fn intern(&self, key: &str) -> &str {
+        let mut keys = self.keys.borrow_mut();
+        let interned = if let Some(interned) = keys.get(key) {
+            interned.as_bytes()
+        } else {
+            keys.insert(key.into());
+            keys.get(key).map_or(b"".as_slice(), |interned| interned.as_bytes())
+        };
+        // SAFETY: each key is in its own allocation, which doesn't move when the set grows, and keys are only
+        // dropped by `clear` or dropping the set, which both need there to be no borrows of `self` left,
+        // the bytes came from a `str` so are valid UTF-8
+        unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(interned.as_ptr(), interned.len())) }
+    }
+}
@@ -1,7 +1,6 @@
 use std::borrow::{Borrow, Cow};
 use std::fmt;
 use std::hash::Hash;
-use std::slice::Iter as SliceIter;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::OnceLock;
 
@@ -9,10 +8,14 @@ use ahash::AHashMap;
 use smallvec::SmallVec;
 
 /// Like [IndexMap](https://docs.rs/indexmap/latest/indexmap/) but only builds the lookup map when it's needed.
+///
+/// Entries are always kept, and iterated, in insertion order - whether or not the lookup map has been built.
 pub struct LazyIndexMap<K, V> {
     vec: SmallVec<[(K, V); 8]>,
     map: OnceLock<AHashMap<K, usize>>,
     last_find: AtomicUsize,
+    /// whether any key appears more than once, found on the first lookup in the vec and reset by inserts
+    has_duplicates: OnceLock<bool>,
 }
 
 impl<K, V> Default for LazyIndexMap<K, V>
@@ -31,6 +34,7 @@ impl<K: Clone, V: Clone> Clone for LazyIndexMap<K, V> {
             vec: self.vec.clone(),
             map: self.map.clone(),
             last_find: AtomicUsize::new(0),
+            has_duplicates: self.has_duplicates.clone(),
         }
     }
 }
@@ -59,6 +63,7 @@ where
             vec: SmallVec::new(),
             map: OnceLock::new(),
             last_find: AtomicUsize::new(0),
+            has_duplicates: OnceLock::new(),
         }
     }
 
@@ -67,6 +72,7 @@ where
             map.insert(key.clone(), self.vec.len());
         }
         self.vec.push((key, value));
+        self.has_duplicates.take();
     }
 
     pub fn len(&self) -> usize {
@@ -77,7 +83,37 @@ where
         self.vec.is_empty()
     }
 
+    /// Get the value for `key`, if `key` appears more than once the value of its last entry is returned,
+    /// like JavaScript's `JSON.parse`. This is also the entry returned by [LazyIndexMap::iter_unique].
+    ///
+    /// Use [LazyIndexMap::get_all] to get the values of every entry for `key`.
     pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q> + PartialEq<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|index| &self.vec[index].1)
+    }
+
+    /// Iterate over the values of every entry for `key`, in insertion order.
+    pub fn get_all<'a, Q>(&'a self, key: &'a Q) -> impl Iterator<Item = &'a V>
+    where
+        K: PartialEq<Q>,
+        Q: ?Sized,
+    {
+        self.vec.iter().filter(move |(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Like [LazyIndexMap::get], but returns a mutable reference to the value.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q> + PartialEq<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|index| &mut self.vec[index].1)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
     where
         K: Borrow<Q> + PartialEq<Q>,
         Q: Hash + Eq + ?Sized,
@@ -85,29 +121,46 @@ where
         let vec_len = self.vec.len();
         // if the vec is longer than the threshold, we use the hashmap for lookups
         if vec_len > HASHMAP_THRESHOLD {
-            self.get_map().get(key).map(|&i| &self.vec[i].1)
+            self.get_map().get(key).copied()
         } else {
             // otherwise we find the value in the vec
             // we assume the most likely position for the match is at `last_find + 1`
             let first_try = self.last_find.load(Ordering::Relaxed) + 1;
             for i in first_try..first_try + vec_len {
-                let index = i % vec_len;
-                let (k, v) = &self.vec[index];
-                if k == key {
+                let mut index = i % vec_len;
+                if &self.vec[index].0 == key {
+                    // there may be later duplicates of the key, the last entry wins
+                    if self.has_duplicates() {
+                        if let Some(later) = self.vec[index + 1..].iter().rposition(|(k, _)| k == key) {
+                            index += 1 + later;
+                        }
+                    }
                     self.last_find.store(index, Ordering::Relaxed);
-                    return Some(v);
+                    return Some(index);
                 }
             }
             None
         }
     }
 
+    /// Iterate over keys in insertion order, including duplicates.
     pub fn keys(&self) -> impl Iterator<Item = &K> {
         self.vec.iter().map(|(k, _)| k)
     }
 
-    pub fn iter(&self) -> SliceIter<'_, (K, V)> {
-        self.vec.iter()
+    /// Iterate over values in insertion order, including values of duplicate keys.
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &V> + DoubleEndedIterator {
+        self.vec.iter().map(|(_, v)| v)
+    }
+
+    /// Iterate mutably over values in insertion order, including values of duplicate keys.
+    pub fn values_mut(&mut self) -> impl ExactSizeIterator<Item = &mut V> + DoubleEndedIterator {
+        self.vec.iter_mut().map(|(_, v)| v)
+    }
+
+    /// Iterate over `(key, value)` pairs in insertion order, including duplicate keys.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, &V)> + DoubleEndedIterator {
+        self.vec.iter().map(|(k, v)| (k, v))
     }
 
     pub fn iter_unique(&self) -> impl Iterator<Item = (&K, &V)> {
@@ -118,17 +171,54 @@ where
         }
     }
 
+    /// Keep only the entries for which `f` returns `true`, only entries which [LazyIndexMap::iter_unique]
+    /// would return are passed to `f`, the others are removed.
+    pub(crate) fn retain_unique(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let map = self.map.take().unwrap_or_else(|| build_map(&self.vec));
+        let mut index = 0;
+        self.vec.retain(|(key, value)| {
+            let unique = map.get(key) == Some(&index);
+            index += 1;
+            unique && f(key, value)
+        });
+        self.last_find.store(0, Ordering::Relaxed);
+        self.has_duplicates = OnceLock::from(false);
+    }
+
+    /// Remove and return all entries, keeping the memory allocated for them and for the lookup map
+    /// if it's been built, so the map can be refilled without allocating.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = (K, V)> + '_ {
+        if let Some(map) = self.map.get_mut() {
+            map.clear();
+        }
+        *self.last_find.get_mut() = 0;
+        self.has_duplicates.take();
+        self.vec.drain(..)
+    }
+
     fn get_map(&self) -> &AHashMap<K, usize> {
-        self.map.get_or_init(|| {
+        self.map.get_or_init(|| build_map(&self.vec))
+    }
+
+    /// Whether any key appears more than once, this is only needed for maps small enough to search the vec,
+    /// so comparing every pair of keys once is cheap.
+    fn has_duplicates(&self) -> bool {
+        *self.has_duplicates.get_or_init(|| {
             self.vec
                 .iter()
                 .enumerate()
-                .map(|(index, (key, _))| (key.clone(), index))
-                .collect()
+                .any(|(index, (key, _))| self.vec[index + 1..].iter().any(|(k, _)| k == key))
         })
     }
 }
 
+fn build_map<K: Clone + Eq + Hash, V>(vec: &[(K, V)]) -> AHashMap<K, usize> {
+    vec.iter()
+        .enumerate()
+        .map(|(index, (key, _))| (key.clone(), index))
+        .collect()
+}
+
 impl<'j> LazyIndexMap<Cow<'j, str>, crate::JsonValue<'j>> {
     pub(crate) fn to_static(&self) -> LazyIndexMap<Cow<'static, str>, crate::JsonValue<'static>> {
         LazyIndexMap {
@@ -139,6 +229,7 @@ impl<'j> LazyIndexMap<Cow<'j, str>, crate::JsonValue<'j>> {
                 .collect(),
             map: OnceLock::new(),
             last_find: AtomicUsize::new(0),
+            has_duplicates: OnceLock::new(),
         }
     }
 }
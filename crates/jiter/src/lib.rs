@@ -148,10 +148,18 @@
 //! test x100_serde_value                  ... bench:          83 ns/iter (+/- 3)
 //! ```
 
+mod array_iter;
+mod cst;
 mod errors;
+mod events;
+mod incremental;
 mod jiter;
+mod key_set;
 mod lazy_index_map;
+mod macros;
+pub mod number;
 mod number_decoder;
+mod options;
 mod parse;
 #[cfg(feature = "python")]
 mod py_lossless_float;
@@ -159,17 +167,43 @@ mod py_lossless_float;
 mod py_string_cache;
 #[cfg(feature = "python")]
 mod python;
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "serde")]
+mod serde_de;
 #[cfg(target_arch = "aarch64")]
 mod simd_aarch64;
 mod string_decoder;
 mod value;
+mod value_pool;
+mod value_rc;
 
-pub use errors::{JiterError, JiterErrorType, JsonError, JsonErrorType, JsonResult, JsonType, LinePosition};
-pub use jiter::{Jiter, JiterResult};
+pub use array_iter::ArrayIter;
+pub use cst::{parse_cst, Comment, CstDocument, CstKind, CstMember, CstNode};
+pub use errors::{
+    ErrorDetail, JiterError, JiterErrorType, JsonError, JsonErrorType, JsonResult, JsonType, LinePosition,
+    ValueConversionError,
+};
+pub use events::{JiterEvents, JsonEvent};
+pub use incremental::IncrementalParser;
+#[cfg(feature = "base64")]
+pub use jiter::Base64Alphabet;
+pub use jiter::{fold_array, Jiter, JiterResult, JiterSnapshot, UnknownFields};
+pub use key_set::KeySet;
 pub use lazy_index_map::LazyIndexMap;
-pub use number_decoder::{NumberAny, NumberInt};
+pub use number_decoder::{FloatSource, NumberAny, NumberInt};
+pub use options::ParseOptions;
 pub use parse::Peek;
-pub use value::{JsonArray, JsonObject, JsonValue};
+#[cfg(feature = "schema")]
+pub use schema::{Schema, SchemaError, SchemaErrorType};
+#[cfg(feature = "serde")]
+pub use serde_de::{from_slice, from_slice_borrowed};
+pub use string_decoder::{EscapeAction, EscapeHandler};
+pub use value::{
+    parse_prefix, parse_prefix_with_options, JsonArray, JsonObject, JsonValue, NumberCoercion, PathSegment, ValueStats,
+};
+pub use value_pool::{JsonValuePool, PooledJsonValue};
+pub use value_rc::{JsonArrayRc, JsonObjectRc, JsonValueRc};
 
 #[cfg(feature = "python")]
 pub use py_lossless_float::{FloatMode, LosslessFloat};
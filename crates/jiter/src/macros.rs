@@ -0,0 +1,186 @@
+/// Construct a [JsonValue](crate::JsonValue) from a JSON literal, e.g. for tests and fixtures.
+///
+/// Leaf values can be any expression with a `From` conversion into `JsonValue`, object keys can be
+/// string literals or parenthesised expressions which convert into `Cow<str>`.
+///
+/// The macro is only available as `jiter::json!` (or via an explicit `use`), so it can be used in the
+/// same module as `serde_json::json!` by referring to one of them by path.
+///
+/// ```rust
+/// use jiter::JsonValue;
+///
+/// let value = jiter::json!({"a": [1, 2.5, {"b": true}], "c": null});
+/// assert_eq!(value, JsonValue::parse(br#"{"a": [1, 2.5, {"b": true}], "c": null}"#, false).unwrap());
+/// ```
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+// This is a simplified version of the TT muncher used by `serde_json::json!`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    // array: done with a trailing comma
+    (@array [$($elems:expr,)*]) => {
+        $crate::JsonValue::Array(::std::sync::Arc::new(
+            ::std::iter::IntoIterator::into_iter([$($elems,)*]).collect(),
+        ))
+    };
+
+    // array: done without a trailing comma
+    (@array [$($elems:expr),*]) => {
+        $crate::json_internal!(@array [$($elems,)*])
+    };
+
+    // array: next element is `null`, `true`, `false`, an array or an object
+    (@array [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(null)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(true)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!(false)] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!({$($map)*})] $($rest)*)
+    };
+
+    // array: next element is an expression followed by a comma, or is the last element
+    (@array [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($next),] $($rest)*)
+    };
+    (@array [$($elems:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elems,)* $crate::json_internal!($last)])
+    };
+
+    // array: comma after the most recent element
+    (@array [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elems,)*] $($rest)*)
+    };
+
+    // array: unexpected token after the most recent element
+    (@array [$($elems:expr),*] $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected)
+    };
+
+    // object: done
+    (@object $object:ident () () ()) => {};
+
+    // object: insert the current entry followed by a trailing comma
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        $object.insert(::std::borrow::Cow::from($($key)+), $value);
+        $crate::json_internal!(@object $object () ($($rest)*) ($($rest)*));
+    };
+
+    // object: unexpected token after the current entry
+    (@object $object:ident [$($key:tt)+] ($value:expr) $unexpected:tt $($rest:tt)*) => {
+        $crate::json_unexpected!($unexpected);
+    };
+
+    // object: insert the last entry without a trailing comma
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        $object.insert(::std::borrow::Cow::from($($key)+), $value);
+    };
+
+    // object: next value is `null`, `true`, `false`, an array or an object
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!({$($map)*})) $($rest)*);
+    };
+
+    // object: next value is an expression followed by a comma, or is the last value
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)));
+    };
+
+    // object: missing value for the last entry, trigger a "missing tokens" error
+    (@object $object:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::json_internal!();
+    };
+
+    // object: missing colon and value for the last entry
+    (@object $object:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::json_internal!();
+    };
+
+    // object: misplaced colon or comma, with no key
+    (@object $object:ident () (: $($rest:tt)*) ($colon:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($colon);
+    };
+    (@object $object:ident ($($key:tt)*) (, $($rest:tt)*) ($comma:tt $($copy:tt)*)) => {
+        $crate::json_unexpected!($comma);
+    };
+
+    // object: the key is a parenthesised expression
+    (@object $object:ident () (($key:expr) : $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($key) (: $($rest)*) (: $($rest)*));
+    };
+
+    // object: munch a token into the current key
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::json_internal!(@object $object ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::JsonValue::Null
+    };
+
+    (true) => {
+        $crate::JsonValue::Bool(true)
+    };
+
+    (false) => {
+        $crate::JsonValue::Bool(false)
+    };
+
+    ([]) => {
+        $crate::json_internal!(@array [])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::json_internal!(@array [] $($tt)+)
+    };
+
+    ({}) => {
+        $crate::JsonValue::Object(::std::sync::Arc::new($crate::LazyIndexMap::new()))
+    };
+
+    ({ $($tt:tt)+ }) => {{
+        let mut object = $crate::LazyIndexMap::new();
+        $crate::json_internal!(@object object () ($($tt)+) ($($tt)+));
+        $crate::JsonValue::Object(::std::sync::Arc::new(object))
+    }};
+
+    // any other expression is converted with `From`
+    ($other:expr) => {
+        $crate::JsonValue::from($other)
+    };
+}
+
+// Used to produce a "no rules expected the token" error pointing at the unexpected token.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_unexpected {
+    () => {};
+}
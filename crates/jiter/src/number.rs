@@ -0,0 +1,17 @@
+//! Low level number scanning, for building custom tokenizers on top of jiter's handling of
+//! signs, exponents, `Infinity` and `NaN`.
+//!
+//! ```rust
+//! use jiter::number::{AbstractNumberDecoder, NumberRange};
+//!
+//! let data = b"-1.5e3, 2";
+//! let (number, end) = NumberRange::decode(data, 0, data[0], false).unwrap();
+//! assert_eq!(number.range, 0..6);
+//! assert!(!number.is_int);
+//! assert_eq!(end, 6);
+//! ```
+
+pub use crate::number_decoder::{
+    AbstractNumberDecoder, NumberAny, NumberFloat, NumberInt, NumberIntLenient, NumberRange,
+};
+pub use crate::parse::{consume_infinity, consume_nan};
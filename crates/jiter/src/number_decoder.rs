@@ -11,10 +11,22 @@ use lexical_parse_float::{format as lexical_format, FromLexicalWithOptions, Opti
 
 use crate::errors::{json_err, json_error, JsonError, JsonResult};
 
+/// A decoder for a JSON number, implemented by each of the number types jiter can produce.
 pub trait AbstractNumberDecoder {
     type Output;
 
+    /// Decode the number starting at `index`, where `first` is the byte at `index`.
+    ///
+    /// Returns the decoded number and the index after it.
     fn decode(data: &[u8], index: usize, first: u8, allow_inf_nan: bool) -> JsonResult<(Self::Output, usize)>;
+
+    /// Decode a number starting at `index` with no digits before its decimal point, like `.5` or `-.5`,
+    /// see [ParseOptions::allow_leading_decimal_point](crate::ParseOptions::allow_leading_decimal_point).
+    ///
+    /// By default these numbers are rejected as invalid.
+    fn decode_leading_decimal_point(_data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        json_err!(InvalidNumber, index)
+    }
 }
 
 /// A number that can be either an [i64] or a [BigInt](num_bigint::BigInt)
@@ -65,8 +77,48 @@ impl AbstractNumberDecoder for NumberInt {
             _ => json_err!(FloatExpectingInt, index),
         }
     }
+
+    fn decode_leading_decimal_point(data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        let end = consume_leading_decimal_point(data, index)?;
+        json_err!(FloatExpectingInt, end)
+    }
+}
+
+/// Like [NumberInt], but also accepts integers with leading zeros like `007`, which aren't valid JSON.
+///
+/// The output is the integer and whether it had leading zeros, so callers can warn about
+/// numbers which are technically invalid but recoverable, e.g. `-007` is decoded as `(-7, true)`.
+pub struct NumberIntLenient;
+
+impl AbstractNumberDecoder for NumberIntLenient {
+    type Output = (NumberInt, bool);
+
+    fn decode(data: &[u8], index: usize, first: u8, allow_inf_nan: bool) -> JsonResult<(Self::Output, usize)> {
+        let digits_start = if first == b'-' { index + 1 } else { index };
+        let mut digits = digits_start;
+        // skip every zero which is followed by another digit, leaving the last digit if they're all zeros
+        while data.get(digits) == Some(&b'0') && data.get(digits + 1).is_some_and(u8::is_ascii_digit) {
+            digits += 1;
+        }
+        if digits == digits_start {
+            return NumberInt::decode(data, index, first, allow_inf_nan).map(|(int, index)| ((int, false), index));
+        }
+        let (int, index) = NumberInt::decode(data, digits, data[digits], allow_inf_nan)?;
+        let int = match int {
+            _ if first != b'-' => int,
+            NumberInt::Int(int) => NumberInt::Int(-int),
+            #[cfg(feature = "num-bigint")]
+            NumberInt::BigInt(big_int) => NumberInt::BigInt(-big_int),
+        };
+        Ok(((int, true), index))
+    }
+
+    fn decode_leading_decimal_point(data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        NumberInt::decode_leading_decimal_point(data, index).map(|(int, index)| ((int, false), index))
+    }
 }
 
+/// Decodes any JSON number as an [f64].
 pub struct NumberFloat;
 
 impl AbstractNumberDecoder for NumberFloat {
@@ -112,6 +164,40 @@ impl AbstractNumberDecoder for NumberFloat {
             json_err!(EofWhileParsingValue, index)
         }
     }
+
+    fn decode_leading_decimal_point(data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        let end = consume_leading_decimal_point(data, index)?;
+        // lexical's JSON format requires digits before the decimal point, but std's parser doesn't
+        let float = std::str::from_utf8(&data[index..end])
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| json_error!(InvalidNumber, index))?;
+        Ok((float, end))
+    }
+}
+
+/// Where a float came from in the JSON data, see [Jiter::next_float_with_source](crate::Jiter::next_float_with_source).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatSource {
+    /// A finite number.
+    Finite,
+    /// A number which was too large to represent as an [f64], so it overflowed to `inf` or `-inf`.
+    Overflow,
+    /// A literal `Infinity`, `-Infinity` or `NaN`, only valid when `allow_inf_nan` is enabled.
+    Literal,
+}
+
+impl FloatSource {
+    /// Classify `float`, where `number_bytes` are the bytes it was parsed from.
+    pub(crate) fn classify(float: f64, number_bytes: &[u8]) -> Self {
+        if float.is_finite() {
+            Self::Finite
+        } else if matches!(number_bytes, [b'I' | b'N', ..] | [b'-', b'I', ..]) {
+            Self::Literal
+        } else {
+            Self::Overflow
+        }
+    }
 }
 
 /// A number that can be either a [NumberInt] or an [f64]
@@ -161,6 +247,10 @@ impl AbstractNumberDecoder for NumberAny {
             IntParse::FloatNaN => consume_nan(data, index, allow_inf_nan).map(|(f, index)| (Self::Float(f), index)),
         }
     }
+
+    fn decode_leading_decimal_point(data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        NumberFloat::decode_leading_decimal_point(data, index).map(|(f, index)| (Self::Float(f), index))
+    }
 }
 
 fn consume_inf(data: &[u8], index: usize, positive: bool, allow_inf_nan: bool) -> JsonResult<usize> {
@@ -385,6 +475,7 @@ pub(crate) static INT_CHAR_MAP: [bool; 256] = {
     ]
 };
 
+/// Decodes a JSON number into the range of `data` it occupies, without converting it.
 pub struct NumberRange {
     pub range: Range<usize>,
     // in some cfg configurations, this field is never read.
@@ -501,6 +592,11 @@ impl AbstractNumberDecoder for NumberRange {
             }
         }
     }
+
+    fn decode_leading_decimal_point(data: &[u8], index: usize) -> JsonResult<(Self::Output, usize)> {
+        let end = consume_leading_decimal_point(data, index)?;
+        Ok((Self::float(index..end), end))
+    }
 }
 
 fn consume_exponential(data: &[u8], mut index: usize) -> JsonResult<usize> {
@@ -531,6 +627,18 @@ fn consume_exponential(data: &[u8], mut index: usize) -> JsonResult<usize> {
     Ok(index)
 }
 
+/// The end of a number with no digits before its decimal point, like `.5` or `-.5`, starting at `index`.
+fn consume_leading_decimal_point(data: &[u8], mut index: usize) -> JsonResult<usize> {
+    if data.get(index) == Some(&b'-') {
+        index += 1;
+    }
+    match data.get(index) {
+        Some(b'.') => consume_decimal(data, index + 1),
+        Some(_) => json_err!(InvalidNumber, index),
+        None => json_err!(EofWhileParsingValue, index),
+    }
+}
+
 fn consume_decimal(data: &[u8], mut index: usize) -> JsonResult<usize> {
     match data.get(index) {
         Some(v) if v.is_ascii_digit() => (),
@@ -0,0 +1,206 @@
+use crate::errors::DEFAULT_RECURSION_LIMIT;
+use crate::string_decoder::EscapeHandler;
+use crate::PartialMode;
+
+/// Options controlling how JSON data is parsed.
+///
+/// The same options are accepted by [Jiter::with_options](crate::Jiter::with_options) and
+/// [JsonValue::parse_with_options](crate::JsonValue::parse_with_options), the simpler entry points
+/// like [JsonValue::parse](crate::JsonValue::parse) are thin wrappers which build a `ParseOptions`.
+///
+/// ```rust
+/// use jiter::{JsonValue, ParseOptions, PartialMode};
+///
+/// let options = ParseOptions::new()
+///     .with_allow_inf_nan()
+///     .with_partial_mode(PartialMode::On);
+/// let value = JsonValue::parse_with_options(b"[NaN, 1", options).unwrap();
+/// assert!(matches!(value, JsonValue::Array(_)));
+/// ```
+///
+/// New options may be added in any release, so build `ParseOptions` with [ParseOptions::new] and the
+/// `with_*` methods, or by setting fields on a default value, rather than with a struct literal.
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_excessive_bools)] // these are independent flags, not a state machine
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Whether to allow `NaN`, `Infinity` and `-Infinity` as numbers.
+    pub allow_inf_nan: bool,
+    /// Whether to allow partial JSON data, see [PartialMode].
+    pub partial_mode: PartialMode,
+    /// Whether to allow control characters (`\x00` to `\x1F`), like tabs and newlines, unescaped in strings and keys.
+    pub allow_raw_control_chars: bool,
+    /// Whether to allow JSON5 whitespace between tokens, e.g. form feed, vertical tab and Unicode spaces
+    /// like U+00A0 and U+2028, as well as the JSON whitespace characters.
+    pub extended_whitespace: bool,
+    /// Whether to allow `// line` and `/* block */` comments anywhere whitespace is allowed, they're skipped.
+    ///
+    /// An unterminated block comment runs to the end of the data.
+    pub allow_comments: bool,
+    /// The maximum number of elements allowed in any single array, `None` for no limit.
+    pub max_array_elements: Option<usize>,
+    /// The maximum number of entries allowed in any single object, `None` for no limit.
+    ///
+    /// Entries with duplicate keys are each counted.
+    pub max_object_entries: Option<usize>,
+    /// The maximum total length in bytes of all strings and keys decoded while parsing, `None` for no limit.
+    ///
+    /// Unlike a limit on each string, this bounds the memory used by documents with very many small strings.
+    /// Strings which are skipped rather than decoded aren't counted.
+    pub max_total_string_bytes: Option<usize>,
+    /// Called for unknown escapes in strings and keys, e.g. `\x`, to decide what to do with them,
+    /// `None` (the default) means unknown escapes are an error.
+    pub escape_handler: Option<EscapeHandler>,
+    /// How deeply arrays and objects may be nested, `None` (the default) means a limit of 200,
+    /// deeper data fails with [RecursionLimitExceeded](crate::JsonErrorType::RecursionLimitExceeded).
+    ///
+    /// This applies both when reading values and when skipping them.
+    pub recursion_limit: Option<u8>,
+    /// Whether arrays and objects nested beyond the recursion limit are skipped and replaced with
+    /// [JsonValue::Null](crate::JsonValue::Null), rather than parsing failing with
+    /// [RecursionLimitExceeded](crate::JsonErrorType::RecursionLimitExceeded).
+    ///
+    /// This applies when reading [JsonValue](crate::JsonValue)s, so the structure of pathologically nested
+    /// data can still be read down to the limit. The skipped data must still be valid JSON.
+    pub max_depth_error_recovery: bool,
+    /// Whether `NaN`, `Infinity` and `-Infinity` are read as [JsonValue::Null](crate::JsonValue::Null) when
+    /// reading values, like JavaScript's `JSON.stringify` does, rather than as floats or an error.
+    ///
+    /// This applies whether or not [ParseOptions::allow_inf_nan] is set.
+    pub nan_inf_as_null: bool,
+    /// Whether the JavaScript literal `undefined` is allowed and read as `null`, for data serialized by lenient
+    /// JavaScript code, it's otherwise an error.
+    pub undefined_as_null: bool,
+    /// Whether numbers with no digits before the decimal point, like `.5` and `-.5`, are allowed and read as
+    /// floats, as in JSON5, without allowing JSON5's other number syntax.
+    pub allow_leading_decimal_point: bool,
+    /// Whether every object key must sort after the key before it, comparing decoded keys byte by byte,
+    /// otherwise parsing fails with [KeysNotSorted](crate::JsonErrorType::KeysNotSorted).
+    ///
+    /// Use this to check data is already in a canonical form, duplicate keys are also rejected.
+    pub require_sorted_keys: bool,
+    /// Whether numbers are read as [JsonValue::RawNumber](crate::JsonValue::RawNumber), keeping their text
+    /// exactly as it appears in the data, rather than as ints and floats, when reading values.
+    ///
+    /// Numbers are still validated, so e.g. `NaN` is only allowed with [ParseOptions::allow_inf_nan].
+    #[cfg(feature = "raw-number")]
+    pub raw_numbers: bool,
+    /// Whether an object with the same key more than once is an error when reading values, rather than all
+    /// entries being kept, see [LazyIndexMap](crate::LazyIndexMap).
+    ///
+    /// Parsing fails with [DuplicateKey](crate::JsonErrorType::DuplicateKey) at the second occurrence of the key.
+    pub catch_duplicate_keys: bool,
+}
+
+impl ParseOptions {
+    /// Create a new `ParseOptions` with strict JSON defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The recursion limit set by [ParseOptions::recursion_limit], or the default.
+    pub(crate) fn recursion_limit_or_default(&self) -> u8 {
+        self.recursion_limit.unwrap_or(DEFAULT_RECURSION_LIMIT)
+    }
+
+    pub fn with_allow_inf_nan(mut self) -> Self {
+        self.allow_inf_nan = true;
+        self
+    }
+
+    pub fn with_partial_mode(mut self, partial_mode: PartialMode) -> Self {
+        self.partial_mode = partial_mode;
+        self
+    }
+
+    pub fn with_allow_raw_control_chars(mut self) -> Self {
+        self.allow_raw_control_chars = true;
+        self
+    }
+
+    pub fn with_extended_whitespace(mut self) -> Self {
+        self.extended_whitespace = true;
+        self
+    }
+
+    /// Skip `//` and `/* */` comments, see [ParseOptions::allow_comments].
+    pub fn with_allow_comments(mut self) -> Self {
+        self.allow_comments = true;
+        self
+    }
+
+    /// Error with [TooManyElements](crate::JsonErrorType::TooManyElements) if any array has more than `max` elements.
+    pub fn with_max_array_elements(mut self, max: usize) -> Self {
+        self.max_array_elements = Some(max);
+        self
+    }
+
+    /// Error with [TooManyElements](crate::JsonErrorType::TooManyElements) if any object has more than `max` entries.
+    pub fn with_max_object_entries(mut self, max: usize) -> Self {
+        self.max_object_entries = Some(max);
+        self
+    }
+
+    /// Error with [TooManyStringBytes](crate::JsonErrorType::TooManyStringBytes) if the total length of decoded
+    /// strings and keys exceeds `max` bytes.
+    pub fn with_max_total_string_bytes(mut self, max: usize) -> Self {
+        self.max_total_string_bytes = Some(max);
+        self
+    }
+
+    /// Use `handler` to decide what to do with unknown escapes, see [EscapeAction](crate::EscapeAction).
+    pub fn with_escape_handler(mut self, handler: EscapeHandler) -> Self {
+        self.escape_handler = Some(handler);
+        self
+    }
+
+    /// Error if arrays and objects are nested more than `limit` deep, see [ParseOptions::recursion_limit].
+    pub fn with_recursion_limit(mut self, limit: u8) -> Self {
+        self.recursion_limit = Some(limit);
+        self
+    }
+
+    /// Replace arrays and objects nested beyond the recursion limit with `null` rather than erroring,
+    /// see [ParseOptions::max_depth_error_recovery].
+    pub fn with_max_depth_error_recovery(mut self) -> Self {
+        self.max_depth_error_recovery = true;
+        self
+    }
+
+    /// Read `NaN`, `Infinity` and `-Infinity` as `null`, see [ParseOptions::nan_inf_as_null].
+    pub fn with_nan_inf_as_null(mut self) -> Self {
+        self.nan_inf_as_null = true;
+        self
+    }
+
+    /// Read `undefined` as `null`, see [ParseOptions::undefined_as_null].
+    pub fn with_undefined_as_null(mut self) -> Self {
+        self.undefined_as_null = true;
+        self
+    }
+
+    /// Allow numbers like `.5`, see [ParseOptions::allow_leading_decimal_point].
+    pub fn with_allow_leading_decimal_point(mut self) -> Self {
+        self.allow_leading_decimal_point = true;
+        self
+    }
+
+    /// Error if object keys aren't in sorted order, see [ParseOptions::require_sorted_keys].
+    pub fn with_require_sorted_keys(mut self) -> Self {
+        self.require_sorted_keys = true;
+        self
+    }
+
+    /// Error if an object has duplicate keys, see [ParseOptions::catch_duplicate_keys].
+    pub fn with_catch_duplicate_keys(mut self) -> Self {
+        self.catch_duplicate_keys = true;
+        self
+    }
+
+    /// Read numbers as their original text, see [ParseOptions::raw_numbers].
+    #[cfg(feature = "raw-number")]
+    pub fn with_raw_numbers(mut self) -> Self {
+        self.raw_numbers = true;
+        self
+    }
+}
@@ -1,9 +1,10 @@
 use std::fmt;
 use std::ops::Range;
 
-use crate::errors::{json_err, JsonResult, LinePosition};
+use crate::errors::{json_err, JsonError, JsonErrorType, JsonResult, LinePosition};
 use crate::number_decoder::AbstractNumberDecoder;
-use crate::string_decoder::{AbstractStringDecoder, Tape};
+use crate::string_decoder::{AbstractStringDecoder, EscapeHandler, StringDecoder, StringOptions, Tape};
+use crate::ParseOptions;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct Peek(u8);
@@ -55,18 +56,210 @@ impl Peek {
 static TRUE_REST: [u8; 3] = [b'r', b'u', b'e'];
 static FALSE_REST: [u8; 4] = [b'a', b'l', b's', b'e'];
 static NULL_REST: [u8; 3] = [b'u', b'l', b'l'];
+static UNDEFINED_REST: [u8; 8] = [b'n', b'd', b'e', b'f', b'i', b'n', b'e', b'd'];
 static NAN_REST: [u8; 2] = [b'a', b'N'];
 static INFINITY_REST: [u8; 7] = [b'n', b'f', b'i', b'n', b'i', b't', b'y'];
 
+/// A callback called with the parser's index roughly every `every` bytes, see `Jiter::with_progress`.
+///
+/// Clones of the parser don't have the callback, since they're used to look ahead and their index runs
+/// ahead of the real parser's.
+struct Progress<'j> {
+    every: usize,
+    callback: Box<dyn FnMut(usize) + Send + Sync + 'j>,
+}
+
+impl fmt::Debug for Progress<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Progress")
+            .field("every", &self.every)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The length and last item of a stack, which is enough to restore it as long as it isn't popped
+/// below that item in the meantime.
+#[derive(Debug, Clone)]
+pub(crate) struct StackTop<T> {
+    len: usize,
+    top: Option<T>,
+}
+
+impl<T: Clone> StackTop<T> {
+    pub fn new(stack: &[T]) -> Self {
+        Self {
+            len: stack.len(),
+            top: stack.last().cloned(),
+        }
+    }
+
+    pub fn restore(&self, stack: &mut Vec<T>) {
+        debug_assert!(stack.len() + 1 >= self.len, "stack popped below the snapshot");
+        stack.truncate(self.len.saturating_sub(1));
+        stack.extend(self.top.clone());
+    }
+}
+
+/// The state of a [Parser] which changes as it advances, see [Parser::snapshot].
 #[derive(Debug, Clone)]
+pub(crate) struct ParserSnapshot {
+    index: usize,
+    total_string_bytes: usize,
+    comments_len: usize,
+    element_counts: Option<StackTop<usize>>,
+    previous_keys: Option<StackTop<Vec<u8>>>,
+}
+
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)] // these are independent flags copied from `ParseOptions`
 pub(crate) struct Parser<'j> {
     data: &'j [u8],
     pub index: usize,
+    /// whether `data` is known to be valid UTF-8, so decoded strings needn't be validated, see [Parser::from_str]
+    input_is_utf8: bool,
+    /// whether control characters are allowed unescaped in strings, including keys
+    pub allow_raw_control_chars: bool,
+    /// whether JSON5 whitespace is allowed between tokens, see [extended_whitespace_len]
+    pub extended_whitespace: bool,
+    /// whether `//` and `/* */` comments are skipped as whitespace, see [comment_len]
+    pub allow_comments: bool,
+    /// the ranges of comments skipped so far, only recorded if set, see `Jiter::with_comment_sink`
+    pub comments: Option<Vec<Range<usize>>>,
+    /// called for unknown escapes in strings and keys
+    pub escape_handler: Option<EscapeHandler>,
+    /// whether arrays and objects beyond the recursion limit are replaced with `null` rather than an error,
+    /// this is handled by callers
+    pub max_depth_error_recovery: bool,
+    /// whether `NaN`, `Infinity` and `-Infinity` are read as `null`, see [Parser::at_nan_inf_as_null]
+    pub nan_inf_as_null: bool,
+    /// whether `undefined` is read as `null`, see [Parser::peek_of]
+    pub undefined_as_null: bool,
+    /// whether numbers like `.5` are allowed, see [Parser::at_leading_decimal_point]
+    pub allow_leading_decimal_point: bool,
+    /// whether numbers are read as their text, see [Parser::consume_raw_number], this is handled by callers
+    #[cfg(feature = "raw-number")]
+    pub raw_numbers: bool,
+    /// whether objects with duplicate keys are an error, this is handled by callers, see [Parser::last_key_index]
+    pub catch_duplicate_keys: bool,
+    /// the index of the opening quote of the last object key read
+    last_key_index: usize,
+    max_array_elements: usize,
+    max_object_entries: usize,
+    /// the number of elements read so far in each non-empty array and object the parser is inside, innermost last,
+    /// only tracked if `max_array_elements` or `max_object_entries` is set
+    element_counts: Option<Vec<usize>>,
+    max_total_string_bytes: usize,
+    /// the total length of strings and keys decoded so far
+    total_string_bytes: usize,
+    /// the previous key of each non-empty object the parser is inside, innermost last,
+    /// only tracked if `require_sorted_keys` is set
+    previous_keys: Option<Vec<Vec<u8>>>,
+    progress: Option<Progress<'j>>,
+    /// the index at which to next call the progress callback, `usize::MAX` if there isn't one so that
+    /// checking costs a single comparison
+    next_progress: usize,
+}
+
+impl Clone for Parser<'_> {
+    /// Clone a `Parser`. Like the default implementation, but the clone doesn't call the progress callback,
+    /// clones are used to look ahead, so their progress isn't the real parser's.
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            index: self.index,
+            input_is_utf8: self.input_is_utf8,
+            allow_raw_control_chars: self.allow_raw_control_chars,
+            extended_whitespace: self.extended_whitespace,
+            allow_comments: self.allow_comments,
+            comments: self.comments.clone(),
+            escape_handler: self.escape_handler,
+            max_depth_error_recovery: self.max_depth_error_recovery,
+            nan_inf_as_null: self.nan_inf_as_null,
+            undefined_as_null: self.undefined_as_null,
+            allow_leading_decimal_point: self.allow_leading_decimal_point,
+            #[cfg(feature = "raw-number")]
+            raw_numbers: self.raw_numbers,
+            catch_duplicate_keys: self.catch_duplicate_keys,
+            last_key_index: self.last_key_index,
+            max_array_elements: self.max_array_elements,
+            max_object_entries: self.max_object_entries,
+            element_counts: self.element_counts.clone(),
+            max_total_string_bytes: self.max_total_string_bytes,
+            total_string_bytes: self.total_string_bytes,
+            previous_keys: self.previous_keys.clone(),
+            progress: None,
+            next_progress: usize::MAX,
+        }
+    }
 }
 
 impl<'j> Parser<'j> {
     pub fn new(data: &'j [u8]) -> Self {
-        Self { data, index: 0 }
+        Self {
+            data,
+            index: 0,
+            input_is_utf8: false,
+            allow_raw_control_chars: false,
+            extended_whitespace: false,
+            allow_comments: false,
+            comments: None,
+            escape_handler: None,
+            max_depth_error_recovery: false,
+            nan_inf_as_null: false,
+            undefined_as_null: false,
+            allow_leading_decimal_point: false,
+            #[cfg(feature = "raw-number")]
+            raw_numbers: false,
+            catch_duplicate_keys: false,
+            last_key_index: 0,
+            max_array_elements: usize::MAX,
+            max_object_entries: usize::MAX,
+            element_counts: None,
+            max_total_string_bytes: usize::MAX,
+            total_string_bytes: 0,
+            previous_keys: None,
+            progress: None,
+            next_progress: usize::MAX,
+        }
+    }
+
+    /// Like [Parser::new], but since `data` is a `str` it's known to be valid UTF-8, so strings don't
+    /// need validating after decoding.
+    pub fn from_str(data: &'j str) -> Self {
+        Self {
+            input_is_utf8: true,
+            ..Self::new(data.as_bytes())
+        }
+    }
+
+    /// Apply the options which affect tokenizing, the rest are handled by callers.
+    pub fn set_options(&mut self, options: ParseOptions) {
+        self.allow_raw_control_chars = options.allow_raw_control_chars;
+        self.extended_whitespace = options.extended_whitespace;
+        self.allow_comments = options.allow_comments;
+        self.escape_handler = options.escape_handler;
+        self.max_depth_error_recovery = options.max_depth_error_recovery;
+        self.nan_inf_as_null = options.nan_inf_as_null;
+        self.undefined_as_null = options.undefined_as_null;
+        self.allow_leading_decimal_point = options.allow_leading_decimal_point;
+        #[cfg(feature = "raw-number")]
+        {
+            self.raw_numbers = options.raw_numbers;
+        }
+        self.catch_duplicate_keys = options.catch_duplicate_keys;
+        self.max_array_elements = options.max_array_elements.unwrap_or(usize::MAX);
+        self.max_object_entries = options.max_object_entries.unwrap_or(usize::MAX);
+        self.max_total_string_bytes = options.max_total_string_bytes.unwrap_or(usize::MAX);
+        self.previous_keys = if options.require_sorted_keys {
+            Some(Vec::new())
+        } else {
+            None
+        };
+        self.element_counts = if options.max_array_elements.is_some() || options.max_object_entries.is_some() {
+            Some(Vec::new())
+        } else {
+            None
+        };
     }
 
     #[allow(dead_code)]
@@ -74,13 +267,54 @@ impl<'j> Parser<'j> {
         self.data.get(range)
     }
 
+    /// Record the parser's position and the state of the innermost array or object, to go back to with
+    /// [Parser::restore]. The previous key is only copied if `require_sorted_keys` is set.
+    pub fn snapshot(&self) -> ParserSnapshot {
+        ParserSnapshot {
+            index: self.index,
+            total_string_bytes: self.total_string_bytes,
+            comments_len: self.comments.as_ref().map_or(0, Vec::len),
+            element_counts: self.element_counts.as_deref().map(StackTop::new),
+            previous_keys: self.previous_keys.as_deref().map(StackTop::new),
+        }
+    }
+
+    pub fn restore(&mut self, snapshot: &ParserSnapshot) {
+        self.index = snapshot.index;
+        self.total_string_bytes = snapshot.total_string_bytes;
+        if let Some(comments) = &mut self.comments {
+            comments.truncate(snapshot.comments_len);
+        }
+        if let (Some(counts), Some(top)) = (&mut self.element_counts, &snapshot.element_counts) {
+            top.restore(counts);
+        }
+        if let (Some(keys), Some(top)) = (&mut self.previous_keys, &snapshot.previous_keys) {
+            top.restore(keys);
+        }
+    }
+
+    /// The index of the last object key read, for errors about the key found after reading it.
+    pub fn last_key_index(&self) -> usize {
+        self.last_key_index
+    }
+
     pub fn current_position(&self) -> LinePosition {
         LinePosition::find(self.data, self.index)
     }
 
+    /// The [Peek] of a value starting with `next`, `undefined` peeks as [Peek::Null] if `undefined_as_null`
+    /// is set, so callers read it with [Parser::consume_null].
+    pub fn peek_of(&self, next: u8) -> Peek {
+        if next == b'u' && self.undefined_as_null {
+            Peek::Null
+        } else {
+            Peek::new(next)
+        }
+    }
+
     pub fn peek(&mut self) -> JsonResult<Peek> {
         if let Some(next) = self.eat_whitespace() {
-            Ok(Peek::new(next))
+            Ok(self.peek_of(next))
         } else {
             json_err!(EofWhileParsingValue, self.index)
         }
@@ -93,7 +327,8 @@ impl<'j> Parser<'j> {
                 self.index += 1;
                 Ok(None)
             } else {
-                Ok(Some(Peek::new(next)))
+                self.first_element(self.max_array_elements)?;
+                Ok(Some(self.peek_of(next)))
             }
         } else {
             json_err!(EofWhileParsingList, self.index)
@@ -109,11 +344,13 @@ impl<'j> Parser<'j> {
                     if next.is_none() {
                         json_err!(TrailingComma, self.index)
                     } else {
+                        self.next_element(self.max_array_elements)?;
                         Ok(next)
                     }
                 }
                 b']' => {
                     self.index += 1;
+                    self.end_container();
                     Ok(None)
                 }
                 _ => {
@@ -135,7 +372,10 @@ impl<'j> Parser<'j> {
         self.index += 1;
         if let Some(next) = self.eat_whitespace() {
             match next {
-                b'"' => self.object_key::<D>(tape).map(Some),
+                b'"' => {
+                    self.first_element(self.max_object_entries)?;
+                    self.object_key::<D>(tape, true).map(Some)
+                }
                 b'}' => {
                     self.index += 1;
                     Ok(None)
@@ -159,7 +399,10 @@ impl<'j> Parser<'j> {
                 b',' => {
                     self.index += 1;
                     match self.eat_whitespace() {
-                        Some(b'"') => self.object_key::<D>(tape).map(Some),
+                        Some(b'"') => {
+                            self.next_element(self.max_object_entries)?;
+                            self.object_key::<D>(tape, false).map(Some)
+                        }
                         Some(b'}') => json_err!(TrailingComma, self.index),
                         Some(_) => json_err!(KeyMustBeAString, self.index),
                         None => json_err!(EofWhileParsingValue, self.index),
@@ -167,6 +410,10 @@ impl<'j> Parser<'j> {
                 }
                 b'}' => {
                     self.index += 1;
+                    self.end_container();
+                    if let Some(previous_keys) = &mut self.previous_keys {
+                        previous_keys.pop();
+                    }
                     Ok(None)
                 }
                 _ => json_err!(ExpectedObjectCommaOrEnd, self.index),
@@ -193,7 +440,11 @@ impl<'j> Parser<'j> {
     }
 
     pub fn consume_null(&mut self) -> JsonResult<()> {
-        self.consume_ident(NULL_REST)
+        if self.undefined_as_null && self.data.get(self.index) == Some(&b'u') {
+            self.consume_ident(UNDEFINED_REST)
+        } else {
+            self.consume_ident(NULL_REST)
+        }
     }
 
     pub fn consume_string<'t, D: AbstractStringDecoder<'t, 'j>>(
@@ -204,27 +455,85 @@ impl<'j> Parser<'j> {
     where
         'j: 't,
     {
-        let (output, index) = D::decode(self.data, self.index, tape, allow_partial)?;
+        let options = StringOptions {
+            allow_partial,
+            allow_raw_control_chars: self.allow_raw_control_chars,
+            escape_handler: self.escape_handler,
+            input_is_utf8: self.input_is_utf8,
+        };
+        let (output, index) = D::decode(self.data, self.index, tape, options)?;
+        self.count_string_bytes::<D>(&output)?;
         self.index = index;
         Ok(output)
     }
 
+    /// Whether the number starting with `first` is `NaN`, `Infinity` or `-Infinity` and should be read as `null`,
+    /// callers should then consume it with `allow_inf_nan` set.
+    pub fn at_nan_inf_as_null(&self, first: u8) -> bool {
+        self.nan_inf_as_null
+            && match first {
+                b'N' | b'I' => true,
+                b'-' => self.data.get(self.index + 1) == Some(&b'I'),
+                _ => false,
+            }
+    }
+
+    /// Whether the number starting with `first` has no digits before its decimal point, like `.5` or `-.5`,
+    /// and `allow_leading_decimal_point` is set, so it should be read with
+    /// [AbstractNumberDecoder::decode_leading_decimal_point].
+    pub fn at_leading_decimal_point(&self, first: u8) -> bool {
+        self.allow_leading_decimal_point
+            && match first {
+                b'.' => true,
+                b'-' => self.data.get(self.index + 1) == Some(&b'.'),
+                _ => false,
+            }
+    }
+
     pub fn consume_number<D: AbstractNumberDecoder>(
         &mut self,
         first: u8,
         allow_inf_nan: bool,
     ) -> JsonResult<D::Output> {
-        let (output, index) = D::decode(self.data, self.index, first, allow_inf_nan)?;
+        let (output, index) = if self.at_leading_decimal_point(first) {
+            D::decode_leading_decimal_point(self.data, self.index)?
+        } else {
+            D::decode(self.data, self.index, first, allow_inf_nan)?
+        };
         self.index = index;
         Ok(output)
     }
 
-    /// private method to get an object key, then consume the colon which should follow
-    fn object_key<'t, D: AbstractStringDecoder<'t, 'j>>(&mut self, tape: &'t mut Tape) -> JsonResult<D::Output>
+    /// Consume a number, returning its text exactly as it appears in the data.
+    #[cfg(feature = "raw-number")]
+    pub fn consume_raw_number(&mut self, first: u8, allow_inf_nan: bool) -> JsonResult<&'j str> {
+        let start = self.index;
+        self.consume_number::<crate::number_decoder::NumberRange>(first, allow_inf_nan)?;
+        let bytes = &self.data[start..self.index];
+        // safety: numbers, including `NaN` and `Infinity`, are only made of ASCII characters
+        Ok(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+
+    /// private method to get an object key, then consume the colon which should follow,
+    /// `first` is whether this is the first key in the object
+    fn object_key<'t, D: AbstractStringDecoder<'t, 'j>>(
+        &mut self,
+        tape: &'t mut Tape,
+        first: bool,
+    ) -> JsonResult<D::Output>
     where
         'j: 't,
     {
-        let (output, index) = D::decode(self.data, self.index, tape, false)?;
+        let options = StringOptions {
+            allow_partial: false,
+            allow_raw_control_chars: self.allow_raw_control_chars,
+            escape_handler: self.escape_handler,
+            input_is_utf8: self.input_is_utf8,
+        };
+        self.last_key_index = self.index;
+        let (output, index) = D::decode(self.data, self.index, tape, options)?;
+        self.count_string_bytes::<D>(&output)?;
+        self.check_key_order(index, first, options)?;
         self.index = index;
         if let Some(next) = self.eat_whitespace() {
             if next == b':' {
@@ -238,6 +547,80 @@ impl<'j> Parser<'j> {
         }
     }
 
+    /// Add the length of a decoded string to the total, erroring at the start of the string if that exceeds
+    /// `max_total_string_bytes`. Decoders which don't decode strings, e.g. when skipping, aren't counted.
+    fn count_string_bytes<'t, D: AbstractStringDecoder<'t, 'j>>(&mut self, output: &D::Output) -> JsonResult<()>
+    where
+        'j: 't,
+    {
+        if let Some(len) = D::decoded_len(output) {
+            self.total_string_bytes += len;
+            if self.total_string_bytes > self.max_total_string_bytes {
+                return json_err!(TooManyStringBytes, self.index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the key from `self.index` to `end` sorts after the previous key in the current object,
+    /// if `require_sorted_keys` is set. Keys are compared by their decoded bytes.
+    fn check_key_order(&mut self, end: usize, first: bool, options: StringOptions) -> JsonResult<()> {
+        let Some(previous_keys) = &mut self.previous_keys else {
+            return Ok(());
+        };
+        let start = self.index;
+        // the key without its quotes, which is already decoded unless it contains escapes
+        let raw = &self.data[start + 1..end - 1];
+        let mut tape = Tape::new();
+        let key = if raw.contains(&b'\\') {
+            let (output, _) = StringDecoder::decode(self.data, start, &mut tape, options)?;
+            output.as_str().as_bytes()
+        } else {
+            raw
+        };
+
+        if first {
+            previous_keys.push(key.to_vec());
+        } else if let Some(previous) = previous_keys.last_mut() {
+            if key <= previous.as_slice() {
+                let key = String::from_utf8_lossy(key).into_owned();
+                return Err(JsonError::new(JsonErrorType::KeysNotSorted(key), start));
+            }
+            previous.clear();
+            previous.extend_from_slice(key);
+        }
+        Ok(())
+    }
+
+    /// Start counting the elements of a non-empty array or object, if element limits are set.
+    fn first_element(&mut self, max: usize) -> JsonResult<()> {
+        if let Some(counts) = &mut self.element_counts {
+            if max == 0 {
+                return json_err!(TooManyElements, self.index);
+            }
+            counts.push(1);
+        }
+        Ok(())
+    }
+
+    /// Count another element of the current array or object, if element limits are set.
+    fn next_element(&mut self, max: usize) -> JsonResult<()> {
+        if let Some(count) = self.element_counts.as_mut().and_then(|counts| counts.last_mut()) {
+            *count += 1;
+            if *count > max {
+                return json_err!(TooManyElements, self.index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop counting the elements of the current array or object, if element limits are set.
+    fn end_container(&mut self) {
+        if let Some(counts) = &mut self.element_counts {
+            counts.pop();
+        }
+    }
+
     fn consume_ident<const SIZE: usize>(&mut self, expected: [u8; SIZE]) -> JsonResult<()> {
         self.index = consume_ident(self.data, self.index, expected)?;
         Ok(())
@@ -247,17 +630,53 @@ impl<'j> Parser<'j> {
         if let Some(next) = self.eat_whitespace() {
             match next {
                 b']' => Ok(None),
-                _ => Ok(Some(Peek::new(next))),
+                _ => Ok(Some(self.peek_of(next))),
             }
         } else {
             json_err!(EofWhileParsingValue, self.index)
         }
     }
 
-    fn eat_whitespace(&mut self) -> Option<u8> {
+    /// Call `callback` with the parser's index each time it has advanced at least `every` bytes,
+    /// checked before each token, so long strings delay the call until after them.
+    pub fn set_progress(&mut self, every: usize, callback: impl FnMut(usize) + Send + Sync + 'j) {
+        let every = every.max(1);
+        self.progress = Some(Progress {
+            every,
+            callback: Box::new(callback),
+        });
+        self.next_progress = self.index.saturating_add(every);
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn report_progress(&mut self) {
+        if let Some(progress) = &mut self.progress {
+            (progress.callback)(self.index);
+            self.next_progress = self.index.saturating_add(progress.every);
+        }
+    }
+
+    pub(crate) fn eat_whitespace(&mut self) -> Option<u8> {
+        if self.index >= self.next_progress {
+            self.report_progress();
+        }
         while let Some(next) = self.data.get(self.index) {
             match next {
                 b' ' | b'\r' | b'\t' | b'\n' => self.index += 1,
+                b'/' if self.allow_comments => match comment_len(&self.data[self.index..]) {
+                    Some(len) => {
+                        if let Some(comments) = &mut self.comments {
+                            comments.push(self.index..self.index + len);
+                        }
+                        self.index += len;
+                    }
+                    None => return Some(*next),
+                },
+                _ if self.extended_whitespace => match extended_whitespace_len(&self.data[self.index..]) {
+                    Some(len) => self.index += len,
+                    None => return Some(*next),
+                },
                 _ => return Some(*next),
             }
         }
@@ -265,11 +684,51 @@ impl<'j> Parser<'j> {
     }
 }
 
-pub(crate) fn consume_infinity(data: &[u8], index: usize) -> JsonResult<usize> {
+/// If `data` starts with a `// line` or `/* block */` comment, return its length in bytes, not including
+/// the newline which ends a line comment. An unterminated block comment runs to the end of `data`.
+pub(crate) fn comment_len(data: &[u8]) -> Option<usize> {
+    match data {
+        [b'/', b'/', rest @ ..] => Some(2 + rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len())),
+        [b'/', b'*', rest @ ..] => Some(match rest.windows(2).position(|w| w == b"*/") {
+            Some(position) => 2 + position + 2,
+            None => data.len(),
+        }),
+        _ => None,
+    }
+}
+
+/// If `data` starts with whitespace allowed by JSON5 other than the JSON whitespace characters,
+/// return its length in bytes.
+///
+/// This covers vertical tab, form feed, and Unicode space separators, line and paragraph separators
+/// and the byte order mark.
+fn extended_whitespace_len(data: &[u8]) -> Option<usize> {
+    match data {
+        // vertical tab and form feed
+        [b'\x0B' | b'\x0C', ..] => Some(1),
+        // U+00A0 no-break space
+        [0xC2, 0xA0, ..] => Some(2),
+        // U+1680 ogham space mark
+        [0xE1, 0x9A, 0x80, ..]
+        // U+2000 to U+200A, U+2028 line separator, U+2029 paragraph separator, U+202F narrow no-break space
+        | [0xE2, 0x80, 0x80..=0x8A | 0xA8 | 0xA9 | 0xAF, ..]
+        // U+205F medium mathematical space
+        | [0xE2, 0x81, 0x9F, ..]
+        // U+3000 ideographic space
+        | [0xE3, 0x80, 0x80, ..]
+        // U+FEFF byte order mark
+        | [0xEF, 0xBB, 0xBF, ..] => Some(3),
+        _ => None,
+    }
+}
+
+/// Consume the literal `Infinity`, where `index` is the position of the `I`, returning the index after it.
+pub fn consume_infinity(data: &[u8], index: usize) -> JsonResult<usize> {
     consume_ident(data, index, INFINITY_REST)
 }
 
-pub(crate) fn consume_nan(data: &[u8], index: usize) -> JsonResult<usize> {
+/// Consume the literal `NaN`, where `index` is the position of the `N`, returning the index after it.
+pub fn consume_nan(data: &[u8], index: usize) -> JsonResult<usize> {
     consume_ident(data, index, NAN_REST)
 }
 
@@ -0,0 +1,258 @@
+use std::fmt;
+
+#[cfg(feature = "num-bigint")]
+use num_traits::cast::ToPrimitive;
+
+use crate::errors::JsonType;
+use crate::value::{JsonValue, PathSegment};
+
+/// A minimal subset of [JSON Schema](https://json-schema.org/) for checking the structure of a [JsonValue],
+/// see [JsonValue::validate_schema].
+///
+/// Supports `type`, `required`, `properties`, `items`, `minimum`, `maximum` and `enum`, constraints which
+/// aren't set always pass.
+///
+/// ```rust
+/// use jiter::{JsonType, JsonValue, Schema};
+///
+/// let schema = Schema::new()
+///     .with_type(JsonType::Object)
+///     .with_required(&["id"])
+///     .with_property("id", Schema::new().with_type(JsonType::Int).with_minimum(1.0));
+///
+/// let value = JsonValue::parse(br#"{"id": 0}"#, false).unwrap();
+/// let errors = value.validate_schema(&schema).unwrap_err();
+/// assert_eq!(errors[0].to_string(), "/id: less than the minimum of 1");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    json_type: Option<JsonType>,
+    required: Vec<String>,
+    properties: Vec<(String, Schema)>,
+    items: Option<Box<Schema>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    enum_values: Option<Vec<JsonValue<'static>>>,
+}
+
+impl Schema {
+    /// A schema which accepts any value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require values to be of type `json_type`, [JsonType::Float] accepts ints too, like JSON Schema's `"number"`.
+    pub fn with_type(mut self, json_type: JsonType) -> Self {
+        self.json_type = Some(json_type);
+        self
+    }
+
+    /// Require objects to have all of `keys`.
+    pub fn with_required(mut self, keys: &[&str]) -> Self {
+        self.required.extend(keys.iter().map(|key| (*key).to_owned()));
+        self
+    }
+
+    /// Check the value of `key` in objects against `schema`, if it's present.
+    pub fn with_property(mut self, key: &str, schema: Schema) -> Self {
+        self.properties.push((key.to_owned(), schema));
+        self
+    }
+
+    /// Check every element of arrays against `schema`.
+    pub fn with_items(mut self, schema: Schema) -> Self {
+        self.items = Some(Box::new(schema));
+        self
+    }
+
+    /// Require numbers to be greater than or equal to `minimum`.
+    pub fn with_minimum(mut self, minimum: f64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    /// Require numbers to be less than or equal to `maximum`.
+    pub fn with_maximum(mut self, maximum: f64) -> Self {
+        self.maximum = Some(maximum);
+        self
+    }
+
+    /// Require values to equal one of `values`.
+    pub fn with_enum(mut self, values: Vec<JsonValue<'static>>) -> Self {
+        self.enum_values = Some(values);
+        self
+    }
+
+    fn validate<'a>(
+        &'a self,
+        value: &'a JsonValue,
+        path: &mut Vec<PathSegment<'a>>,
+        errors: &mut Vec<SchemaError<'a>>,
+    ) {
+        let mut error = |error_type| {
+            errors.push(SchemaError {
+                path: path.clone(),
+                error_type,
+            });
+        };
+
+        let actual = value.json_type();
+        if let Some(expected) = &self.json_type {
+            let number_as_float = *expected == JsonType::Float && actual == JsonType::Int;
+            if *expected != actual && !number_as_float {
+                error(SchemaErrorType::WrongType {
+                    expected: expected.clone(),
+                    actual,
+                });
+                // other constraints would just repeat the type error
+                return;
+            }
+        }
+
+        if let Some(enum_values) = &self.enum_values {
+            if !enum_values.iter().any(|v| values_equal(v, value)) {
+                error(SchemaErrorType::NotInEnum);
+            }
+        }
+
+        if let Some(number) = as_f64(value) {
+            if let Some(minimum) = self.minimum.filter(|minimum| number < *minimum) {
+                error(SchemaErrorType::BelowMinimum { minimum });
+            }
+            if let Some(maximum) = self.maximum.filter(|maximum| number > *maximum) {
+                error(SchemaErrorType::AboveMaximum { maximum });
+            }
+        }
+
+        match value {
+            JsonValue::Object(object) => {
+                for key in &self.required {
+                    if object.get(key.as_str()).is_none() {
+                        error(SchemaErrorType::MissingKey { key: key.clone() });
+                    }
+                }
+                for (key, schema) in &self.properties {
+                    if let Some(value) = object.get(key.as_str()) {
+                        path.push(PathSegment::Key(key));
+                        schema.validate(value, path, errors);
+                        path.pop();
+                    }
+                }
+            }
+            JsonValue::Array(array) => {
+                if let Some(items) = &self.items {
+                    for (index, item) in array.iter().enumerate() {
+                        path.push(PathSegment::Index(index));
+                        items.validate(item, path, errors);
+                        path.pop();
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Like `==`, but `JsonValue` is invariant over its lifetime so we can't compare `'static` values with others.
+fn values_equal(a: &JsonValue, b: &JsonValue) -> bool {
+    match (a, b) {
+        (JsonValue::Null, JsonValue::Null) => true,
+        (JsonValue::Bool(a), JsonValue::Bool(b)) => a == b,
+        (JsonValue::Int(a), JsonValue::Int(b)) => a == b,
+        #[cfg(feature = "num-bigint")]
+        (JsonValue::BigInt(a), JsonValue::BigInt(b)) => a == b,
+        // exact equality, as with the derived `PartialEq`
+        #[allow(clippy::float_cmp)]
+        (JsonValue::Float(a), JsonValue::Float(b)) => a == b,
+        (JsonValue::Str(a), JsonValue::Str(b)) => a == b,
+        (JsonValue::Array(a), JsonValue::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| values_equal(a, b))
+        }
+        (JsonValue::Object(a), JsonValue::Object(b)) => {
+            a.iter().len() == b.iter().len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|((ak, av), (bk, bv))| ak == bk && values_equal(av, bv))
+        }
+        _ => false,
+    }
+}
+
+fn as_f64(value: &JsonValue) -> Option<f64> {
+    match value {
+        JsonValue::Int(int) => Some(*int as f64),
+        #[cfg(feature = "num-bigint")]
+        JsonValue::BigInt(big_int) => big_int.to_f64(),
+        JsonValue::Float(float) => Some(*float),
+        _ => None,
+    }
+}
+
+/// A value which didn't match a [Schema], see [JsonValue::validate_schema].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaError<'a> {
+    /// Where the value is, as used by [JsonValue::get_path], empty for the root value.
+    pub path: Vec<PathSegment<'a>>,
+    pub error_type: SchemaErrorType,
+}
+
+impl fmt::Display for SchemaError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str("/")?;
+        }
+        for segment in &self.path {
+            match segment {
+                PathSegment::Key(key) => write!(f, "/{key}")?,
+                PathSegment::Index(index) => write!(f, "/{index}")?,
+            }
+        }
+        write!(f, ": {}", self.error_type)
+    }
+}
+
+/// The constraint a value broke, see [SchemaError].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaErrorType {
+    WrongType {
+        expected: JsonType,
+        actual: JsonType,
+    },
+    /// An object was missing a key from [Schema::with_required].
+    MissingKey {
+        key: String,
+    },
+    BelowMinimum {
+        minimum: f64,
+    },
+    AboveMaximum {
+        maximum: f64,
+    },
+    /// The value wasn't one of the values from [Schema::with_enum].
+    NotInEnum,
+}
+
+impl fmt::Display for SchemaErrorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongType { expected, actual } => write!(f, "expected {expected} but found {actual}"),
+            Self::MissingKey { key } => write!(f, "missing required key \"{key}\""),
+            Self::BelowMinimum { minimum } => write!(f, "less than the minimum of {minimum}"),
+            Self::AboveMaximum { maximum } => write!(f, "greater than the maximum of {maximum}"),
+            Self::NotInEnum => f.write_str("not one of the allowed values"),
+        }
+    }
+}
+
+impl JsonValue<'_> {
+    /// Check this value against `schema`, returning every violation found rather than stopping at the first.
+    pub fn validate_schema<'a>(&'a self, schema: &'a Schema) -> Result<(), Vec<SchemaError<'a>>> {
+        let mut errors = Vec::new();
+        schema.validate(self, &mut Vec::new(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
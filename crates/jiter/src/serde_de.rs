@@ -0,0 +1,427 @@
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::errors::{json_error, JiterError, JiterErrorType, DEFAULT_RECURSION_LIMIT};
+use crate::jiter::{Jiter, JiterResult};
+use crate::number_decoder::{NumberAny, NumberInt};
+use crate::parse::Peek;
+use crate::string_decoder::StringOutput;
+
+/// Errors from `serde::de::Error::custom` don't know where they happened, this placeholder index is
+/// replaced with the start of the value being deserialized when the error reaches the [Deserializer].
+const UNKNOWN_INDEX: usize = usize::MAX;
+
+/// Deserialize a `T` from JSON data, reading it directly with a [Jiter] rather than via a
+/// [JsonValue](crate::JsonValue).
+///
+/// Errors raised by `T`'s `Deserialize` implementation, e.g. a missing field, are returned as
+/// [JiterErrorType::Custom] at the index of the value being deserialized,
+/// use [JiterError::get_position] with a [Jiter] over the same data to find the line and column.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// let data = br#"{"a": [1, 2], "b": [3]}"#;
+/// let map: HashMap<String, Vec<u8>> = jiter::from_slice(data).unwrap();
+/// assert_eq!(map["a"], vec![1, 2]);
+/// ```
+pub fn from_slice<T: DeserializeOwned>(data: &[u8]) -> JiterResult<T> {
+    from_slice_borrowed(data)
+}
+
+/// Like [from_slice], but `T` may borrow from `data`: strings without escapes are handed to `T` as `&'j str`.
+///
+/// Strings with escapes have to be decoded so can't be borrowed, use `Cow<'j, str>` with `#[serde(borrow)]`
+/// for fields which may contain them.
+pub fn from_slice_borrowed<'j, T: de::Deserialize<'j>>(data: &'j [u8]) -> JiterResult<T> {
+    let mut deserializer = Deserializer {
+        jiter: Jiter::new(data),
+        remaining_depth: DEFAULT_RECURSION_LIMIT,
+    };
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.jiter.finish()?;
+    Ok(value)
+}
+
+impl de::Error for JiterError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::new(JiterErrorType::Custom(msg.to_string()), UNKNOWN_INDEX)
+    }
+}
+
+/// Set the index of an error raised by a visitor, which doesn't know where it is, to `start`.
+fn at_index<T>(result: JiterResult<T>, start: usize) -> JiterResult<T> {
+    result.map_err(|mut e| {
+        if e.index == UNKNOWN_INDEX {
+            e.index = start;
+        }
+        e
+    })
+}
+
+struct Deserializer<'j> {
+    jiter: Jiter<'j>,
+    remaining_depth: u8,
+}
+
+impl Deserializer<'_> {
+    fn enter(&mut self, start: usize) -> JiterResult<()> {
+        match self.remaining_depth.checked_sub(1) {
+            Some(depth) => {
+                self.remaining_depth = depth;
+                Ok(())
+            }
+            None => Err(json_error!(RecursionLimitExceeded, start).into()),
+        }
+    }
+
+    fn exit(&mut self) {
+        self.remaining_depth += 1;
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = JiterError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> JiterResult<V::Value> {
+        let peek = self.jiter.peek()?;
+        let start = self.jiter.current_index();
+        let result = match peek {
+            Peek::Null => {
+                self.jiter.known_null()?;
+                visitor.visit_unit()
+            }
+            Peek::True | Peek::False => {
+                let b = self.jiter.known_bool(peek)?;
+                visitor.visit_bool(b)
+            }
+            Peek::String => match self.jiter.known_str_output()? {
+                StringOutput::Data(s, _) => visitor.visit_borrowed_str(s),
+                StringOutput::Tape(s, _) => visitor.visit_str(s),
+            },
+            Peek::Array => {
+                self.enter(start)?;
+                let mut seq = SeqAccess::new(&mut *self);
+                let value = visitor.visit_seq(&mut seq);
+                let value = value.and_then(|value| seq.end().map(|()| value));
+                self.exit();
+                value
+            }
+            Peek::Object => {
+                self.enter(start)?;
+                let mut map = MapAccess::new(&mut *self);
+                let value = visitor.visit_map(&mut map);
+                let value = value.and_then(|value| map.end().map(|()| value));
+                self.exit();
+                value
+            }
+            _ => match self.jiter.known_number(peek)? {
+                NumberAny::Int(NumberInt::Int(i)) => visitor.visit_i64(i),
+                #[cfg(feature = "num-bigint")]
+                NumberAny::Int(NumberInt::BigInt(big_int)) => {
+                    use num_traits::ToPrimitive;
+
+                    if let Some(u) = big_int.to_u64() {
+                        visitor.visit_u64(u)
+                    } else if let Some(i) = big_int.to_i128() {
+                        visitor.visit_i128(i)
+                    } else if let Some(u) = big_int.to_u128() {
+                        visitor.visit_u128(u)
+                    } else {
+                        Err(json_error!(NumberOutOfRange, start).into())
+                    }
+                }
+                NumberAny::Float(f) => visitor.visit_f64(f),
+            },
+        };
+        at_index(result, start)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> JiterResult<V::Value> {
+        let peek = self.jiter.peek()?;
+        let start = self.jiter.current_index();
+        if peek == Peek::Null {
+            self.jiter.known_null()?;
+            at_index(visitor.visit_none(), start)
+        } else {
+            at_index(visitor.visit_some(self), start)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> JiterResult<V::Value> {
+        let start = self.jiter.current_index();
+        at_index(visitor.visit_newtype_struct(self), start)
+    }
+
+    /// Enums are either a string naming a unit variant, or an object with a single key naming the variant
+    /// and its value as the variant's content, like `serde_json`.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> JiterResult<V::Value> {
+        let peek = self.jiter.peek()?;
+        let start = self.jiter.current_index();
+        let result = match peek {
+            Peek::String => match self.jiter.known_str_output()? {
+                StringOutput::Data(s, _) => visitor.visit_enum(de::value::BorrowedStrDeserializer::new(s)),
+                StringOutput::Tape(s, _) => visitor.visit_enum(s.into_deserializer()),
+            },
+            Peek::Object => {
+                self.enter(start)?;
+                let value = visitor.visit_enum(EnumAccess { de: &mut *self, start });
+                let value = value.and_then(|value| match self.jiter.next_key()? {
+                    None => Ok(value),
+                    Some(_) => Err(de::Error::custom("expected an object with a single key for an enum")),
+                });
+                self.exit();
+                value
+            }
+            _ => Err(de::Error::custom(
+                "expected a string or an object with a single key for an enum",
+            )),
+        };
+        at_index(result, start)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> JiterResult<V::Value> {
+        self.jiter.next_skip()?;
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+struct SeqAccess<'a, 'j> {
+    de: &'a mut Deserializer<'j>,
+    first: bool,
+    done: bool,
+    len: usize,
+}
+
+impl<'a, 'j> SeqAccess<'a, 'j> {
+    fn new(de: &'a mut Deserializer<'j>) -> Self {
+        Self {
+            de,
+            first: true,
+            done: false,
+            len: 0,
+        }
+    }
+
+    /// Move to the next element, returns `false` at the end of the array.
+    fn step(&mut self) -> JiterResult<bool> {
+        let peek = if std::mem::take(&mut self.first) {
+            self.de.jiter.known_array()?
+        } else {
+            self.de.jiter.array_step()?
+        };
+        self.done = peek.is_none();
+        Ok(!self.done)
+    }
+
+    /// Error if the visitor stopped before the end of the array.
+    fn end(&mut self) -> JiterResult<()> {
+        if !self.done && self.step()? {
+            Err(JiterError::new(
+                JiterErrorType::ArrayTooLong { capacity: self.len },
+                self.de.jiter.current_index(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for &mut SeqAccess<'_, 'de> {
+    type Error = JiterError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> JiterResult<Option<T::Value>> {
+        if !self.done && self.step()? {
+            self.len += 1;
+            seed.deserialize(&mut *self.de).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+struct MapAccess<'a, 'j> {
+    de: &'a mut Deserializer<'j>,
+    first: bool,
+    done: bool,
+}
+
+impl<'a, 'j> MapAccess<'a, 'j> {
+    fn new(de: &'a mut Deserializer<'j>) -> Self {
+        Self {
+            de,
+            first: true,
+            done: false,
+        }
+    }
+
+    /// Error if the visitor stopped before the end of the object.
+    fn end(&mut self) -> JiterResult<()> {
+        if self.done {
+            Ok(())
+        } else {
+            let start = self.de.jiter.current_index();
+            Err(JiterError::new(
+                JiterErrorType::Custom("object has more entries than expected".to_string()),
+                start,
+            ))
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for &mut MapAccess<'_, 'de> {
+    type Error = JiterError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> JiterResult<Option<K::Value>> {
+        if self.done {
+            return Ok(None);
+        }
+        let start = self.de.jiter.current_index();
+        let key = if std::mem::take(&mut self.first) {
+            self.de.jiter.known_object_output()?
+        } else {
+            self.de.jiter.next_key_output()?
+        };
+        if let Some(key) = key {
+            at_index(seed.deserialize(KeyDeserializer { key, start }), start).map(Some)
+        } else {
+            self.done = true;
+            Ok(None)
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> JiterResult<V::Value> {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct EnumAccess<'a, 'j> {
+    de: &'a mut Deserializer<'j>,
+    start: usize,
+}
+
+impl<'a, 'de> de::EnumAccess<'de> for EnumAccess<'a, 'de> {
+    type Error = JiterError;
+    type Variant = &'a mut Deserializer<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> JiterResult<(V::Value, Self::Variant)> {
+        let Some(key) = self.de.jiter.known_object_output()? else {
+            return Err(de::Error::custom("expected an object with a single key for an enum"));
+        };
+        let variant = seed.deserialize(KeyDeserializer { key, start: self.start })?;
+        Ok((variant, self.de))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut Deserializer<'de> {
+    type Error = JiterError;
+
+    fn unit_variant(self) -> JiterResult<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> JiterResult<T::Value> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> JiterResult<V::Value> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> JiterResult<V::Value> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+/// Deserializes object keys, which are always strings in JSON, but may be parsed into numbers,
+/// e.g. for `HashMap<u32, _>`.
+struct KeyDeserializer<'t, 'j> {
+    key: StringOutput<'t, 'j>,
+    start: usize,
+}
+
+impl KeyDeserializer<'_, '_> {
+    fn parse<T: std::str::FromStr>(&self) -> JiterResult<T>
+    where
+        T::Err: std::fmt::Display,
+    {
+        let key = self.key.as_str();
+        key.parse().map_err(|e: T::Err| {
+            JiterError::new(
+                JiterErrorType::InvalidKey {
+                    key: key.to_owned(),
+                    message: e.to_string(),
+                },
+                self.start,
+            )
+        })
+    }
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($deserialize:ident => $visit:ident,)*) => {
+        $(
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> JiterResult<V::Value> {
+                visitor.$visit(self.parse()?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'_, 'de> {
+    type Error = JiterError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> JiterResult<V::Value> {
+        match self.key {
+            StringOutput::Data(s, _) => visitor.visit_borrowed_str(s),
+            StringOutput::Tape(s, _) => visitor.visit_str(s),
+        }
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> JiterResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> JiterResult<V::Value> {
+        match self.key {
+            StringOutput::Data(s, _) => visitor.visit_enum(de::value::BorrowedStrDeserializer::new(s)),
+            StringOutput::Tape(s, _) => visitor.visit_enum(s.into_deserializer()),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
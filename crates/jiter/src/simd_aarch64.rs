@@ -37,7 +37,7 @@ use std::arch::aarch64::{
 use crate::JsonResult;
 
 use crate::number_decoder::{decode_int_chunk_fallback, IntChunk};
-use crate::string_decoder::StringChunk;
+use crate::string_decoder::{StringChunk, StringOptions};
 
 type SimdVecu8_16 = uint8x16_t;
 type SimdVecu16_8 = uint16x8_t;
@@ -196,7 +196,7 @@ pub(crate) fn decode_string_chunk(
     data: &[u8],
     mut index: usize,
     mut ascii_only: bool,
-    allow_partial: bool,
+    options: StringOptions,
 ) -> JsonResult<(StringChunk, bool, usize)> {
     while let Some(byte_chunk) = data.get(index..index + SIMD_STEP) {
         let byte_vec = load_slice(byte_chunk);
@@ -209,7 +209,7 @@ pub(crate) fn decode_string_chunk(
             // this chunk contains either a stop character or a non-ascii character
             let a: [u8; 16] = unsafe { transmute(byte_vec) };
             #[allow(clippy::redundant_else)]
-            if let Some(r) = StringChunk::decode_array(a, &mut index, ascii_only) {
+            if let Some(r) = StringChunk::decode_array(a, &mut index, ascii_only, options) {
                 return r;
             } else {
                 ascii_only = false;
@@ -217,7 +217,7 @@ pub(crate) fn decode_string_chunk(
         }
     }
     // we got near the end of the string, fall back to the slow path
-    StringChunk::decode_fallback(data, index, ascii_only, allow_partial)
+    StringChunk::decode_fallback(data, index, ascii_only, options)
 }
 
 #[rustfmt::skip]
@@ -2,7 +2,7 @@ use std::borrow::Cow;
 use std::ops::Range;
 use std::str::{from_utf8, from_utf8_unchecked};
 
-use crate::errors::{json_err, json_error, JsonResult};
+use crate::errors::{json_err, json_error, JsonErrorType, JsonResult};
 
 pub type Tape = Vec<u8>;
 
@@ -19,8 +19,56 @@ where
         data: &'j [u8],
         index: usize,
         tape: &'t mut Tape,
-        allow_partial: bool,
+        options: StringOptions,
     ) -> JsonResult<(Self::Output, usize)>;
+
+    /// The length of the decoded string, `None` if this decoder doesn't decode strings.
+    fn decoded_len(_output: &Self::Output) -> Option<usize> {
+        None
+    }
+}
+
+/// Options controlling how strings are decoded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringOptions {
+    /// Whether a string may be truncated by the end of the data.
+    pub allow_partial: bool,
+    /// Whether control characters (`\x00` to `\x1F`) are allowed unescaped in strings.
+    pub allow_raw_control_chars: bool,
+    /// Called for unknown escapes, if `None` they're an error.
+    pub escape_handler: Option<EscapeHandler>,
+    /// Whether the data is known to be valid UTF-8, e.g. it came from a `str`, so decoded strings aren't validated.
+    ///
+    /// The data between two quotes, or between a quote and an escape, is then valid UTF-8 too since those
+    /// are ASCII characters, so it can't split a multi-byte character.
+    pub input_is_utf8: bool,
+}
+
+/// What to do with an unknown escape sequence, returned by an [EscapeHandler].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeAction {
+    /// Error with [InvalidEscape](crate::JsonErrorType::InvalidEscape), the default behaviour.
+    Error,
+    /// Keep the escape as it is, including the backslash, e.g. `\x` stays as `\x`.
+    Literal,
+    /// Replace the backslash and the escaped character with this character.
+    Replace(char),
+}
+
+/// Decides what to do with an unknown escape sequence, called with the byte after the backslash.
+///
+/// Only called for ASCII bytes, a backslash followed by a non-ASCII character is always an error.
+pub type EscapeHandler = fn(u8) -> EscapeAction;
+
+/// Get the action for the unknown escape at `index`, the byte after the backslash.
+fn unknown_escape(data: &[u8], index: usize, options: StringOptions) -> JsonResult<EscapeAction> {
+    match (options.escape_handler, data[index]) {
+        (Some(handler), next) if next.is_ascii() => match handler(next) {
+            EscapeAction::Error => json_err!(InvalidEscape, index),
+            action => Ok(action),
+        },
+        _ => json_err!(InvalidEscape, index),
+    }
 }
 
 pub struct StringDecoder;
@@ -78,20 +126,24 @@ where
         data: &'j [u8],
         index: usize,
         tape: &'t mut Tape,
-        allow_partial: bool,
+        options: StringOptions,
     ) -> JsonResult<(Self::Output, usize)> {
         let start = index + 1;
 
-        match decode_chunk(data, start, true, allow_partial)? {
+        match decode_chunk(data, start, true, options)? {
             (StringChunk::StringEnd, ascii_only, index) => {
-                let s = to_str(&data[start..index], ascii_only, start)?;
+                let s = to_str(&data[start..index], ascii_only, start, options)?;
                 Ok((StringOutput::Data(s, ascii_only), index + 1))
             }
             (StringChunk::Backslash, ascii_only, index) => {
-                decode_to_tape(data, index, tape, start, ascii_only, allow_partial)
+                decode_to_tape(data, index, tape, start, ascii_only, options)
             }
         }
     }
+
+    fn decoded_len(output: &Self::Output) -> Option<usize> {
+        Some(output.as_str().len())
+    }
 }
 
 fn decode_to_tape<'t, 'j>(
@@ -100,7 +152,7 @@ fn decode_to_tape<'t, 'j>(
     tape: &'t mut Tape,
     start: usize,
     mut ascii_only: bool,
-    allow_partial: bool,
+    options: StringOptions,
 ) -> JsonResult<(StringOutput<'t, 'j>, usize)> {
     tape.clear();
     let mut chunk_start = start;
@@ -116,24 +168,41 @@ fn decode_to_tape<'t, 'j>(
                 b'n' => tape.push(b'\n'),
                 b'r' => tape.push(b'\r'),
                 b't' => tape.push(b'\t'),
-                b'u' => {
-                    let (c, new_index) = parse_escape(data, index)?;
-                    ascii_only = false;
-                    index = new_index;
-                    tape.extend_from_slice(c.encode_utf8(&mut [0_u8; 4]).as_bytes());
-                }
-                _ => return json_err!(InvalidEscape, index),
+                b'u' => match parse_escape(data, index) {
+                    Ok((c, new_index)) => {
+                        ascii_only = false;
+                        index = new_index;
+                        tape.extend_from_slice(c.encode_utf8(&mut [0_u8; 4]).as_bytes());
+                    }
+                    // the escape (or surrogate pair) was cut off by the end of the data, drop it entirely
+                    // rather than emitting part of it, e.g. a lone surrogate
+                    Err(e) if options.allow_partial && e.error_type == JsonErrorType::EofWhileParsingString => {
+                        let s = to_str(tape, ascii_only, start, options)?;
+                        return Ok((StringOutput::Tape(s, ascii_only), data.len() + 1));
+                    }
+                    Err(e) => return Err(e),
+                },
+                _ => match unknown_escape(data, index, options)? {
+                    EscapeAction::Replace(c) => {
+                        ascii_only &= c.is_ascii();
+                        tape.extend_from_slice(c.encode_utf8(&mut [0_u8; 4]).as_bytes());
+                    }
+                    _ => tape.extend_from_slice(&data[index - 1..=index]),
+                },
             }
             index += 1;
+        } else if options.allow_partial {
+            let s = to_str(tape, ascii_only, start, options)?;
+            return Ok((StringOutput::Tape(s, ascii_only), index + 1));
         } else {
             return json_err!(EofWhileParsingString, index);
         }
 
-        match decode_chunk(data, index, ascii_only, allow_partial)? {
+        match decode_chunk(data, index, ascii_only, options)? {
             (StringChunk::StringEnd, ascii_only, new_index) => {
                 tape.extend_from_slice(&data[index..new_index]);
                 index = new_index + 1;
-                let s = to_str(tape, ascii_only, start)?;
+                let s = to_str(tape, ascii_only, start, options)?;
                 return Ok((StringOutput::Tape(s, ascii_only), index));
             }
             (StringChunk::Backslash, ascii_only_new, index_new) => {
@@ -150,17 +219,17 @@ pub(crate) fn decode_chunk(
     data: &[u8],
     index: usize,
     ascii_only: bool,
-    allow_partial: bool,
+    options: StringOptions,
 ) -> JsonResult<(StringChunk, bool, usize)> {
     // TODO x86_64: use simd
 
     #[cfg(target_arch = "aarch64")]
     {
-        crate::simd_aarch64::decode_string_chunk(data, index, ascii_only, allow_partial)
+        crate::simd_aarch64::decode_string_chunk(data, index, ascii_only, options)
     }
     #[cfg(not(target_arch = "aarch64"))]
     {
-        StringChunk::decode_fallback(data, index, ascii_only, allow_partial)
+        StringChunk::decode_fallback(data, index, ascii_only, options)
     }
 }
 
@@ -175,13 +244,14 @@ impl StringChunk {
         data: &[u8],
         mut index: usize,
         mut ascii_only: bool,
-        allow_partial: bool,
+        options: StringOptions,
     ) -> JsonResult<(Self, bool, usize)> {
         while let Some(next) = data.get(index) {
             if !JSON_ASCII[*next as usize] {
                 match &CHAR_TYPE[*next as usize] {
                     CharType::Quote => return Ok((Self::StringEnd, ascii_only, index)),
                     CharType::Backslash => return Ok((Self::Backslash, ascii_only, index)),
+                    CharType::ControlChar if options.allow_raw_control_chars => (),
                     CharType::ControlChar => return json_err!(ControlCharacterWhileParsingString, index),
                     CharType::Other => {
                         ascii_only = false;
@@ -190,7 +260,7 @@ impl StringChunk {
             }
             index += 1;
         }
-        if allow_partial {
+        if options.allow_partial {
             Ok((Self::StringEnd, ascii_only, index))
         } else {
             json_err!(EofWhileParsingString, index)
@@ -205,14 +275,18 @@ impl StringChunk {
         data: [u8; T],
         index: &mut usize,
         ascii_only: bool,
+        options: StringOptions,
     ) -> Option<JsonResult<(Self, bool, usize)>> {
         for u8_char in data {
             if !JSON_ASCII[u8_char as usize] {
                 return match &CHAR_TYPE[u8_char as usize] {
                     CharType::Quote => Some(Ok((Self::StringEnd, ascii_only, *index))),
                     CharType::Backslash => Some(Ok((Self::Backslash, ascii_only, *index))),
-                    CharType::ControlChar => Some(json_err!(ControlCharacterWhileParsingString, *index)),
-                    CharType::Other => {
+                    CharType::ControlChar if !options.allow_raw_control_chars => {
+                        Some(json_err!(ControlCharacterWhileParsingString, *index))
+                    }
+                    // this marks the string as not ascii-only, which is conservative for a raw control character
+                    CharType::ControlChar | CharType::Other => {
                         *index += 1;
                         None
                     }
@@ -294,10 +368,10 @@ static CHAR_TYPE: [CharType; 256] = {
     ]
 };
 
-fn to_str(bytes: &[u8], ascii_only: bool, start: usize) -> JsonResult<&str> {
-    if ascii_only {
-        // safety: in this case we've already confirmed that all characters are ascii, we can safely
-        // transmute from bytes to str
+fn to_str(bytes: &[u8], ascii_only: bool, start: usize, options: StringOptions) -> JsonResult<&str> {
+    if ascii_only || options.input_is_utf8 {
+        // safety: in this case we've already confirmed that all characters are ascii, or the input was a `str`
+        // and escapes are only ever replaced with whole characters, we can safely transmute from bytes to str
         Ok(unsafe { from_utf8_unchecked(bytes) })
     } else {
         from_utf8(bytes).map_err(|e| json_error!(InvalidUnicodeCodePoint, start + e.valid_up_to() + 1))
@@ -368,36 +442,76 @@ where
 
     fn decode(
         data: &'j [u8],
-        mut index: usize,
+        index: usize,
         _tape: &'t mut Tape,
-        allow_partial: bool,
+        options: StringOptions,
     ) -> JsonResult<(Self::Output, usize)> {
-        index += 1;
-        let start = index;
+        let (range, _, index) = decode_range(data, index, options)?;
+        Ok((range, index))
+    }
+}
 
-        loop {
-            index = match decode_chunk(data, index, true, allow_partial)? {
-                (StringChunk::StringEnd, _, index) => {
-                    let r = start..index;
-                    return Ok((r, index + 1));
-                }
-                (StringChunk::Backslash, _, index) => index,
-            };
-            index += 1;
-            if let Some(next_inner) = data.get(index) {
-                match next_inner {
-                    // these escapes are easy to validate
-                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => (),
-                    b'u' => {
-                        let (_, new_index) = parse_escape(data, index)?;
-                        index = new_index;
+/// A string decoder that returns the range of the string and whether it contains any escape sequences.
+///
+/// *WARNING:* as with [StringDecoderRange], this decoder does not check that the string would be valid UTF-8.
+pub struct StringDecoderRawRange;
+
+impl<'t, 'j> AbstractStringDecoder<'t, 'j> for StringDecoderRawRange
+where
+    'j: 't,
+{
+    type Output = (Range<usize>, bool);
+
+    fn decode(
+        data: &'j [u8],
+        index: usize,
+        _tape: &'t mut Tape,
+        options: StringOptions,
+    ) -> JsonResult<(Self::Output, usize)> {
+        let (range, has_escapes, index) = decode_range(data, index, options)?;
+        Ok(((range, has_escapes), index))
+    }
+}
+
+/// Find the range of a string, checking escapes are valid but not decoding them,
+/// also returns whether any escapes were found.
+fn decode_range(data: &[u8], mut index: usize, options: StringOptions) -> JsonResult<(Range<usize>, bool, usize)> {
+    index += 1;
+    let start = index;
+    let mut has_escapes = false;
+
+    loop {
+        index = match decode_chunk(data, index, true, options)? {
+            (StringChunk::StringEnd, _, index) => {
+                let r = start..index;
+                return Ok((r, has_escapes, index + 1));
+            }
+            (StringChunk::Backslash, _, index) => index,
+        };
+        let backslash_index = index;
+        index += 1;
+        if let Some(next_inner) = data.get(index) {
+            match next_inner {
+                // these escapes are easy to validate
+                b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => (),
+                b'u' => match parse_escape(data, index) {
+                    Ok((_, new_index)) => index = new_index,
+                    // as with `StringDecoder`, drop an escape which was cut off by the end of the data
+                    Err(e) if options.allow_partial && e.error_type == JsonErrorType::EofWhileParsingString => {
+                        return Ok((start..backslash_index, has_escapes, data.len() + 1));
                     }
-                    _ => return json_err!(InvalidEscape, index),
+                    Err(e) => return Err(e),
+                },
+                _ => {
+                    unknown_escape(data, index, options)?;
                 }
-                index += 1;
-            } else {
-                return json_err!(EofWhileParsingString, index);
             }
+            has_escapes = true;
+            index += 1;
+        } else if options.allow_partial {
+            return Ok((start..backslash_index, has_escapes, index + 1));
+        } else {
+            return json_err!(EofWhileParsingString, index);
         }
     }
 }
@@ -1,16 +1,23 @@
 use std::borrow::Cow;
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
 use std::sync::Arc;
 
 #[cfg(feature = "num-bigint")]
 use num_bigint::BigInt;
 use smallvec::SmallVec;
 
-use crate::errors::{json_error, JsonError, JsonResult, DEFAULT_RECURSION_LIMIT};
+use crate::errors::{
+    json_error, JsonError, JsonErrorType, JsonResult, JsonType, ValueConversionError, DEFAULT_RECURSION_LIMIT,
+};
+use crate::key_set::KeySet;
 use crate::lazy_index_map::LazyIndexMap;
 use crate::number_decoder::{NumberAny, NumberInt, NumberRange};
 use crate::parse::{Parser, Peek};
 use crate::string_decoder::{StringDecoder, StringDecoderRange, StringOutput, Tape};
-use crate::PartialMode;
+use crate::value_pool::JsonValuePool;
+use crate::{ParseOptions, PartialMode};
 
 /// Enum representing a JSON value.
 #[derive(Clone, Debug, PartialEq)]
@@ -21,11 +28,54 @@ pub enum JsonValue<'s> {
     #[cfg(feature = "num-bigint")]
     BigInt(BigInt),
     Float(f64),
+    /// A number kept as its text exactly as it appears in the JSON data, e.g. to convert it with a decimal
+    /// library without losing precision, only produced if [ParseOptions::raw_numbers] is set.
+    ///
+    /// This variant only exists with the `raw-number` feature, so exhaustive matches on `JsonValue`
+    /// aren't affected by default.
+    #[cfg(feature = "raw-number")]
+    RawNumber(Cow<'s, str>),
     Str(Cow<'s, str>),
     Array(JsonArray<'s>),
     Object(JsonObject<'s>),
 }
 
+/// Statistics about a [JsonValue], see [JsonValue::stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValueStats {
+    /// Total number of values, including the value itself and every array element and object value.
+    pub nodes: usize,
+    /// Depth of the most deeply nested array or object, `0` for a scalar value.
+    pub max_depth: usize,
+    pub nulls: usize,
+    pub bools: usize,
+    /// Number of integers, including big integers.
+    pub ints: usize,
+    pub floats: usize,
+    pub strings: usize,
+    pub arrays: usize,
+    pub objects: usize,
+}
+
+/// How [JsonValue::coerce_numbers] should convert numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberCoercion {
+    /// Convert every number to a float, big integers which are too large for a float become infinite.
+    AllFloat,
+    /// Convert floats with no fractional part which fit in an [i64] to ints, and big integers which fit in
+    /// an [i64] to ints, other numbers are unchanged - in particular, floats are never converted to big integers.
+    IntsWherePossible,
+}
+
+/// One step of a path into a [JsonValue], see [JsonValue::get_path].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// A key in an object.
+    Key(&'a str),
+    /// An index in an array.
+    Index(usize),
+}
+
 pub type JsonArray<'s> = Arc<SmallVec<[JsonValue<'s>; 8]>>;
 pub type JsonObject<'s> = Arc<LazyIndexMap<Cow<'s, str>, JsonValue<'s>>>;
 
@@ -41,6 +91,11 @@ impl pyo3::ToPyObject for JsonValue<'_> {
             #[cfg(feature = "num-bigint")]
             Self::BigInt(b) => b.to_object(py),
             Self::Float(f) => f.to_object(py),
+            #[cfg(feature = "raw-number")]
+            Self::RawNumber(s) => crate::py_lossless_float::get_decimal_type(py)
+                .and_then(|decimal| decimal.call1((s.as_ref(),)))
+                .unwrap()
+                .to_object(py),
             Self::Str(s) => s.to_object(py),
             Self::Array(v) => pyo3::types::PyList::new_bound(py, v.iter().map(|v| v.to_object(py))).to_object(py),
             Self::Object(o) => {
@@ -69,6 +124,8 @@ impl<'py> pyo3::IntoPyObject<'py> for JsonValue<'_> {
             #[cfg(feature = "num-bigint")]
             Self::BigInt(b) => Ok(b.into_pyobject(py)?.into_any()),
             Self::Float(f) => Ok(f.into_pyobject(py)?.into_any()),
+            #[cfg(feature = "raw-number")]
+            Self::RawNumber(s) => crate::py_lossless_float::get_decimal_type(py)?.call1((s.as_ref(),)),
             Self::Str(s) => Ok(s.into_pyobject(py)?.into_any()),
             Self::Array(v) => Ok(pyo3::types::PyList::new(py, v.iter())?.into_any()),
             Self::Object(o) => {
@@ -97,6 +154,8 @@ impl<'py> pyo3::IntoPyObject<'py> for &'_ JsonValue<'_> {
             #[cfg(feature = "num-bigint")]
             JsonValue::BigInt(b) => Ok(b.into_pyobject(py)?.into_any()),
             JsonValue::Float(f) => Ok(f.into_pyobject(py)?.into_any()),
+            #[cfg(feature = "raw-number")]
+            JsonValue::RawNumber(s) => crate::py_lossless_float::get_decimal_type(py)?.call1((s.as_ref(),)),
             JsonValue::Str(s) => Ok(s.into_pyobject(py)?.into_any()),
             JsonValue::Array(v) => Ok(pyo3::types::PyList::new(py, v.iter())?.into_any()),
             JsonValue::Object(o) => {
@@ -122,21 +181,137 @@ impl<'j> JsonValue<'j> {
         allow_inf_nan: bool,
         allow_partial: PartialMode,
     ) -> Result<Self, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            partial_mode: allow_partial,
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options(data, options)
+    }
+
+    /// Like [JsonValue::parse], but arrays and objects may only be nested `recursion_limit` deep,
+    /// see [ParseOptions::recursion_limit].
+    pub fn parse_with_recursion_limit(
+        data: &'j [u8],
+        allow_inf_nan: bool,
+        recursion_limit: u8,
+    ) -> Result<Self, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            recursion_limit: Some(recursion_limit),
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options(data, options)
+    }
+
+    /// Parse a JSON enum from a byte slice using [ParseOptions], returning a borrowed version of the enum.
+    pub fn parse_with_options(data: &'j [u8], options: ParseOptions) -> Result<Self, JsonError> {
+        Self::parse_from_parser(Parser::new(data), options)
+    }
+
+    /// Like [JsonValue::parse], but since `data` is a `str` it's known to be valid UTF-8, so strings and keys
+    /// which aren't ASCII aren't validated again, which makes parsing them faster.
+    pub fn parse_str(data: &'j str, allow_inf_nan: bool) -> Result<Self, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            ..ParseOptions::default()
+        };
+        Self::parse_from_parser(Parser::from_str(data), options)
+    }
+
+    fn parse_from_parser(mut parser: Parser<'j>, options: ParseOptions) -> Result<Self, JsonError> {
+        let v = Self::take_with_options(&mut parser, options)?;
+        if !options.partial_mode.is_active() {
+            parser.finish()?;
+        }
+        Ok(v)
+    }
+
+    /// Read one value with `parser` using `options`, without checking what follows it.
+    fn take_with_options(parser: &mut Parser<'j>, options: ParseOptions) -> Result<Self, JsonError> {
+        parser.set_options(options);
+
+        let mut tape = Tape::default();
+        let peek = parser.peek()?;
+        take_value_borrowed(
+            peek,
+            parser,
+            &mut tape,
+            options.recursion_limit_or_default(),
+            options.allow_inf_nan,
+            options.partial_mode,
+        )
+    }
+
+    /// Like [JsonValue::parse], but empty or whitespace-only data returns [JsonValue::Null] rather than an error,
+    /// e.g. for HTTP responses where an empty body means "no content".
+    ///
+    /// A literal `null` also returns [JsonValue::Null], use [Jiter](crate::Jiter) if the two need distinguishing.
+    pub fn parse_or_null(data: &'j [u8], allow_inf_nan: bool) -> Result<Self, JsonError> {
+        if data.iter().all(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r')) {
+            Ok(JsonValue::Null)
+        } else {
+            Self::parse(data, allow_inf_nan)
+        }
+    }
+
+    /// Parse a single JSON value from the start of a byte slice, returning it along with the index
+    /// just past the value.
+    ///
+    /// Unlike [JsonValue::parse], data after the value is not checked, so this can be used to read one value
+    /// from a buffer which contains more data. See also [parse_prefix].
+    pub fn parse_with_length(data: &'j [u8], allow_inf_nan: bool) -> Result<(Self, usize), JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options_and_length(data, options)
+    }
+
+    /// Like [JsonValue::parse_with_length], but using [ParseOptions].
+    pub fn parse_with_options_and_length(data: &'j [u8], options: ParseOptions) -> Result<(Self, usize), JsonError> {
+        parse_prefix_with_options(data, false, options)
+    }
+
+    /// Parse a JSON array where a malformed element doesn't stop the rest being parsed, e.g. to drop bad
+    /// records from a large file.
+    ///
+    /// Returns the result of parsing each element, and an error if the array itself is malformed: if the data
+    /// isn't an array, is truncated or has trailing characters. Elements before that error are still returned.
+    ///
+    /// After a bad element, parsing resumes at the next `,` or `]` which isn't inside a nested array, object or
+    /// string in that element, so an unterminated string in a bad element swallows the rest of the array.
+    pub fn parse_array_best_effort(
+        data: &'j [u8],
+        allow_inf_nan: bool,
+    ) -> (Vec<Result<Self, JsonError>>, Option<JsonError>) {
+        let mut elements = Vec::new();
+        let error = parse_array_elements(data, allow_inf_nan, &mut elements).err();
+        (elements, error)
+    }
+
+    /// Like [JsonValue::parse], but object keys are interned in `keyset`, which can be shared between
+    /// many parses so documents with the same keys share a single copy of each key.
+    ///
+    /// Keys borrow from `keyset` rather than `data`, so even keys with escapes aren't allocated once
+    /// they're in the set.
+    pub fn parse_with_keyset(data: &'j [u8], allow_inf_nan: bool, keyset: &'j KeySet) -> Result<Self, JsonError> {
         let mut parser = Parser::new(data);
 
         let mut tape = Tape::default();
         let peek = parser.peek()?;
-        let v = take_value_borrowed(
+        let v = take_value(
             peek,
             &mut parser,
             &mut tape,
             DEFAULT_RECURSION_LIMIT,
             allow_inf_nan,
-            allow_partial,
+            PartialMode::Off,
+            &|s: StringOutput<'_, 'j>| s.into(),
+            &|s: StringOutput<'_, 'j>| Cow::Borrowed(keyset.intern(s.as_str())),
+            None,
         )?;
-        if !allow_partial.is_active() {
-            parser.finish()?;
-        }
+        parser.finish()?;
         Ok(v)
     }
 
@@ -149,6 +324,536 @@ impl<'j> JsonValue<'j> {
     pub fn to_static(&self) -> JsonValue<'static> {
         value_static(self.clone())
     }
+
+    /// Move the object out of this value, or return the value unchanged if it is not an object.
+    ///
+    /// Like the other `into_*` methods, this only matches the corresponding variant, see the `TryFrom` impls
+    /// for conversions which widen ints to floats or copy shared data.
+    pub fn into_object(self) -> Result<JsonObject<'j>, Self> {
+        match self {
+            JsonValue::Object(object) => Ok(object),
+            _ => Err(self),
+        }
+    }
+
+    /// Move the array out of this value, or return the value unchanged if it is not an array.
+    pub fn into_array(self) -> Result<JsonArray<'j>, Self> {
+        match self {
+            JsonValue::Array(array) => Ok(array),
+            _ => Err(self),
+        }
+    }
+
+    /// Move the string out of this value, or return the value unchanged if it is not a string.
+    pub fn into_string(self) -> Result<Cow<'j, str>, Self> {
+        match self {
+            JsonValue::Str(s) => Ok(s),
+            _ => Err(self),
+        }
+    }
+
+    /// Get the integer out of this value, or return the value unchanged if it is not an [i64] int.
+    pub fn into_i64(self) -> Result<i64, Self> {
+        match self {
+            JsonValue::Int(i) => Ok(i),
+            _ => Err(self),
+        }
+    }
+
+    /// Get the float out of this value, or return the value unchanged if it is not a float.
+    pub fn into_f64(self) -> Result<f64, Self> {
+        match self {
+            JsonValue::Float(f) => Ok(f),
+            _ => Err(self),
+        }
+    }
+
+    /// Get the boolean out of this value, or return the value unchanged if it is not a boolean.
+    pub fn into_bool(self) -> Result<bool, Self> {
+        match self {
+            JsonValue::Bool(b) => Ok(b),
+            _ => Err(self),
+        }
+    }
+
+    /// Get the [JsonType] of this value.
+    pub fn json_type(&self) -> JsonType {
+        match self {
+            JsonValue::Null => JsonType::Null,
+            JsonValue::Bool(_) => JsonType::Bool,
+            JsonValue::Int(_) => JsonType::Int,
+            #[cfg(feature = "num-bigint")]
+            JsonValue::BigInt(_) => JsonType::Int,
+            JsonValue::Float(_) => JsonType::Float,
+            #[cfg(feature = "raw-number")]
+            JsonValue::RawNumber(s) => raw_number_type(s),
+            JsonValue::Str(_) => JsonType::String,
+            JsonValue::Array(_) => JsonType::Array,
+            JsonValue::Object(_) => JsonType::Object,
+        }
+    }
+
+    /// Count the nodes in this value by type, and find how deeply nested it is, see [ValueStats].
+    pub fn stats(&self) -> ValueStats {
+        let mut stats = ValueStats::default();
+        let mut stack: Vec<(&JsonValue<'j>, usize)> = vec![(self, 0)];
+        while let Some((value, depth)) = stack.pop() {
+            stats.nodes += 1;
+            match value {
+                JsonValue::Null => stats.nulls += 1,
+                JsonValue::Bool(_) => stats.bools += 1,
+                JsonValue::Int(_) => stats.ints += 1,
+                #[cfg(feature = "num-bigint")]
+                JsonValue::BigInt(_) => stats.ints += 1,
+                JsonValue::Float(_) => stats.floats += 1,
+                #[cfg(feature = "raw-number")]
+                JsonValue::RawNumber(s) => match raw_number_type(s) {
+                    JsonType::Int => stats.ints += 1,
+                    _ => stats.floats += 1,
+                },
+                JsonValue::Str(_) => stats.strings += 1,
+                JsonValue::Array(array) => {
+                    stats.arrays += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stack.extend(array.iter().map(|v| (v, depth + 1)));
+                }
+                JsonValue::Object(object) => {
+                    stats.objects += 1;
+                    stats.max_depth = stats.max_depth.max(depth + 1);
+                    stack.extend(object.iter().map(|(_, v)| (v, depth + 1)));
+                }
+            }
+        }
+        stats
+    }
+
+    /// Get the value for `key` if this is an object, `None` if it isn't or doesn't have the key.
+    ///
+    /// If the object has duplicate keys, the value of the last is returned, see [LazyIndexMap::get].
+    pub fn get(&self, key: &str) -> Option<&JsonValue<'j>> {
+        match self {
+            JsonValue::Object(object) => object.get(key),
+            _ => None,
+        }
+    }
+
+    /// Get the element at `index` if this is an array, `None` if it isn't or `index` is out of range.
+    pub fn get_index(&self, index: usize) -> Option<&JsonValue<'j>> {
+        match self {
+            JsonValue::Array(array) => array.get(index),
+            _ => None,
+        }
+    }
+
+    /// Get the value at `path`, or `None` if any segment doesn't match, e.g. a key is missing, an index is
+    /// out of range, or the value isn't an array or object as the segment expects.
+    ///
+    /// ```rust
+    /// use jiter::{JsonValue, PathSegment};
+    ///
+    /// let value = JsonValue::parse(br#"{"users": [{"name": "Anne"}]}"#, false).unwrap();
+    /// let path = [PathSegment::Key("users"), PathSegment::Index(0), PathSegment::Key("name")];
+    /// assert_eq!(value.get_path(&path), Some(&JsonValue::Str("Anne".into())));
+    /// ```
+    pub fn get_path(&self, path: &[PathSegment]) -> Option<&JsonValue<'j>> {
+        path.iter().try_fold(self, |value, segment| match (value, segment) {
+            (JsonValue::Object(object), PathSegment::Key(key)) => object.get(*key),
+            (JsonValue::Array(array), PathSegment::Index(index)) => array.get(*index),
+            _ => None,
+        })
+    }
+
+    /// Like [JsonValue::get_path], but returns a mutable reference to the value.
+    ///
+    /// Arrays and objects along the path are only cloned (via [Arc::make_mut]) if they're shared.
+    pub fn get_path_mut(&mut self, path: &[PathSegment]) -> Option<&mut JsonValue<'j>> {
+        path.iter().try_fold(self, |value, segment| match (value, segment) {
+            (JsonValue::Object(object), PathSegment::Key(key)) => Arc::make_mut(object).get_mut(*key),
+            (JsonValue::Array(array), PathSegment::Index(index)) => Arc::make_mut(array).get_mut(*index),
+            _ => None,
+        })
+    }
+
+    /// Get the value at the [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON Pointer `pointer`,
+    /// e.g. `/users/0/name`, like [JsonValue::get_path] with the path written as a string.
+    ///
+    /// `~1` and `~0` in tokens are unescaped to `/` and `~`, tokens are used as indexes into arrays if they're
+    /// decimal integers without leading zeros. The empty pointer returns this value, and a pointer which doesn't
+    /// start with `/` returns `None`.
+    ///
+    /// ```rust
+    /// use jiter::JsonValue;
+    ///
+    /// let value = JsonValue::parse(br#"{"users": [{"name": "Anne"}], "a/b": 1}"#, false).unwrap();
+    /// assert_eq!(value.pointer("/users/0/name"), Some(&JsonValue::Str("Anne".into())));
+    /// assert_eq!(value.pointer("/a~1b"), Some(&JsonValue::Int(1)));
+    /// assert_eq!(value.pointer("/users/1"), None);
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&JsonValue<'j>> {
+        pointer_tokens(pointer)?.try_fold(self, |value, token| match value {
+            JsonValue::Object(object) => object.get(token.as_ref()),
+            JsonValue::Array(array) => array.get(pointer_index(&token)?),
+            _ => None,
+        })
+    }
+
+    /// Like [JsonValue::pointer], but returns a mutable reference to the value.
+    ///
+    /// As with [JsonValue::get_path_mut], arrays and objects along the pointer are only cloned
+    /// (via [Arc::make_mut]) if they're shared, so they don't need to be unique.
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut JsonValue<'j>> {
+        pointer_tokens(pointer)?.try_fold(self, |value, token| match value {
+            JsonValue::Object(object) => Arc::make_mut(object).get_mut(token.as_ref()),
+            JsonValue::Array(array) => Arc::make_mut(array).get_mut(pointer_index(&token)?),
+            _ => None,
+        })
+    }
+
+    /// Convert every number in this value, including inside arrays and objects, according to `mode`.
+    ///
+    /// Arrays and objects are only cloned (via [Arc::make_mut]) if they're shared.
+    pub fn coerce_numbers(&mut self, mode: NumberCoercion) {
+        let mut stack: Vec<&mut JsonValue<'j>> = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonValue::Array(array) => stack.extend(Arc::make_mut(array).iter_mut()),
+                JsonValue::Object(object) => stack.extend(Arc::make_mut(object).values_mut()),
+                number => coerce_number(number, mode),
+            }
+        }
+    }
+
+    /// Normalize every float in this value, including inside arrays and objects, so it serializes to
+    /// a single canonical form.
+    ///
+    /// Every finite `f64` already round-trips exactly through its shortest decimal representation, e.g.
+    /// `Float(0.1)` is left unchanged, so only values with more than one form are rewritten:
+    /// `-0.0` becomes `0.0` (as in [RFC 8785](https://www.rfc-editor.org/rfc/rfc8785) canonical JSON)
+    /// and any NaN becomes [f64::NAN].
+    ///
+    /// Arrays and objects are only cloned (via [Arc::make_mut]) if they're shared.
+    pub fn normalize_floats(&mut self) {
+        let mut stack: Vec<&mut JsonValue<'j>> = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                JsonValue::Array(array) => stack.extend(Arc::make_mut(array).iter_mut()),
+                JsonValue::Object(object) => stack.extend(Arc::make_mut(object).values_mut()),
+                JsonValue::Float(f) => *f = normalize_float(*f),
+                _ => (),
+            }
+        }
+    }
+
+    /// Feed a canonical representation of this value into `hasher`, so values which are logically equal
+    /// hash equally.
+    ///
+    /// Object keys are hashed in sorted order, with only the last entry of duplicate keys included,
+    /// and floats are normalized as by [JsonValue::normalize_floats]. Ints and floats are hashed
+    /// differently, matching `JsonValue::Int(1) != JsonValue::Float(1.0)`.
+    pub fn canonical_hash<H: Hasher>(&self, hasher: &mut H) {
+        match self {
+            JsonValue::Null => hasher.write_u8(0),
+            JsonValue::Bool(b) => {
+                hasher.write_u8(1);
+                b.hash(hasher);
+            }
+            JsonValue::Int(i) => {
+                hasher.write_u8(2);
+                i.hash(hasher);
+            }
+            #[cfg(feature = "num-bigint")]
+            JsonValue::BigInt(b) => {
+                hasher.write_u8(3);
+                b.hash(hasher);
+            }
+            JsonValue::Float(f) => {
+                hasher.write_u8(4);
+                normalize_float(*f).to_bits().hash(hasher);
+            }
+            #[cfg(feature = "raw-number")]
+            JsonValue::RawNumber(s) => {
+                hasher.write_u8(8);
+                s.hash(hasher);
+            }
+            JsonValue::Str(s) => {
+                hasher.write_u8(5);
+                s.hash(hasher);
+            }
+            JsonValue::Array(array) => {
+                hasher.write_u8(6);
+                hasher.write_usize(array.len());
+                array.iter().for_each(|v| v.canonical_hash(hasher));
+            }
+            JsonValue::Object(object) => {
+                hasher.write_u8(7);
+                let mut entries: Vec<_> = object.iter_unique().collect();
+                entries.sort_unstable_by_key(|&(k, _)| k);
+                hasher.write_usize(entries.len());
+                for (k, v) in entries {
+                    k.hash(hasher);
+                    v.canonical_hash(hasher);
+                }
+            }
+        }
+    }
+
+    /// Serialize this value as compact JSON, with no whitespace.
+    ///
+    /// Strings are escaped like `serde_json`, floats are written in their shortest form which parses back to
+    /// the same value, and big ints are written exactly. `NaN`, `Infinity` and `-Infinity` aren't valid JSON
+    /// so they're written as `null`, like JavaScript's `JSON.stringify`. Every entry of objects is written,
+    /// in order, including duplicate keys.
+    ///
+    /// ```rust
+    /// use jiter::JsonValue;
+    ///
+    /// let value = JsonValue::parse(br#"{"a": [1, 2.5, "x\ny"], "b": {}}"#, false).unwrap();
+    /// assert_eq!(value.to_json_string(), r#"{"a":[1,2.5,"x\ny"],"b":{}}"#);
+    /// ```
+    pub fn to_json_string(&self) -> String {
+        let mut out = String::new();
+        write_json(&mut out, self, None, 0);
+        out
+    }
+
+    /// Like [JsonValue::to_json_string], but pretty-printed with each array element and object entry on its own
+    /// line, indented by `indent` spaces per level, the same layout as `serde_json::to_string_pretty`
+    /// (which indents by 2). Empty arrays and objects are written as `[]` and `{}`.
+    ///
+    /// ```rust
+    /// use jiter::JsonValue;
+    ///
+    /// let value = JsonValue::parse(br#"{"a": [1, []], "b": null}"#, false).unwrap();
+    /// assert_eq!(
+    ///     value.to_json_string_pretty(2),
+    ///     "{\n  \"a\": [\n    1,\n    []\n  ],\n  \"b\": null\n}"
+    /// );
+    /// ```
+    pub fn to_json_string_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        write_json(&mut out, self, Some(indent), 0);
+        out
+    }
+
+    /// Apply an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386) merge patch to this value.
+    ///
+    /// If `patch` is an object, each of its keys is merged into this value recursively, with `null` removing
+    /// the key, otherwise `patch` replaces this value. New keys are added after existing keys.
+    ///
+    /// Objects are updated in place, they're only cloned (via [Arc::make_mut]) if they're shared.
+    pub fn merge(&mut self, patch: &JsonValue<'j>) {
+        let (JsonValue::Object(object), JsonValue::Object(patch)) = (&mut *self, patch) else {
+            *self = merge_patch(patch);
+            return;
+        };
+        let object = Arc::make_mut(object);
+        object.retain_unique(|key, value| match patch.get(key.as_ref()) {
+            Some(JsonValue::Null) => false,
+            Some(patch_value) => {
+                value.merge(patch_value);
+                true
+            }
+            None => true,
+        });
+        for (key, patch_value) in patch.iter_unique() {
+            if !matches!(patch_value, JsonValue::Null) && object.get(key.as_ref()).is_none() {
+                object.insert(key.clone(), merge_patch(patch_value));
+            }
+        }
+    }
+
+    /// Compute an [RFC 7386](https://www.rfc-editor.org/rfc/rfc7386) merge patch which turns `old` into `new`
+    /// when applied with [JsonValue::merge].
+    ///
+    /// Objects are compared recursively: removed keys become `null`, changed keys are included and unchanged
+    /// keys are left out. Arrays and scalars which differ are replaced whole.
+    /// As merge patches use `null` to remove keys, `merge(old, diff(old, new)) == new` only holds if
+    /// no object in `new` has a `null` value.
+    pub fn diff(old: &JsonValue<'j>, new: &JsonValue<'j>) -> JsonValue<'j> {
+        let (JsonValue::Object(old), JsonValue::Object(new)) = (old, new) else {
+            return new.clone();
+        };
+        let mut patch = LazyIndexMap::new();
+        for (key, old_value) in old.iter_unique() {
+            match new.get(key.as_ref()) {
+                Some(new_value) if new_value == old_value => (),
+                Some(new_value) => patch.insert(key.clone(), JsonValue::diff(old_value, new_value)),
+                None => patch.insert(key.clone(), JsonValue::Null),
+            }
+        }
+        for (key, new_value) in new.iter_unique() {
+            if old.get(key.as_ref()).is_none() {
+                patch.insert(key.clone(), new_value.clone());
+            }
+        }
+        JsonValue::Object(Arc::new(patch))
+    }
+
+    /// Copy a JSON enum into a fully independent owned JSON enum.
+    ///
+    /// Unlike [JsonValue::to_static] and [JsonValue::into_static], which are free to share existing
+    /// arrays and objects where that's possible, this always allocates new containers at every level,
+    /// so the copy can be mutated via [Arc::make_mut] without ever cloning again.
+    pub fn deep_clone(&self) -> JsonValue<'static> {
+        match self {
+            JsonValue::Array(v) => JsonValue::Array(Arc::new(v.iter().map(JsonValue::deep_clone).collect())),
+            JsonValue::Object(o) => {
+                let mut object = LazyIndexMap::new();
+                for (k, v) in o.iter() {
+                    object.insert(Cow::Owned(k.to_string()), v.deep_clone());
+                }
+                JsonValue::Object(Arc::new(object))
+            }
+            scalar => value_static(scalar.clone()),
+        }
+    }
+}
+
+/// Apply `patch` to a key which doesn't exist yet, i.e. copy it with any `null` object values removed.
+fn merge_patch<'s>(patch: &JsonValue<'s>) -> JsonValue<'s> {
+    let JsonValue::Object(patch) = patch else {
+        return patch.clone();
+    };
+    let mut merged = LazyIndexMap::new();
+    for (key, patch_value) in patch.iter_unique() {
+        if !matches!(patch_value, JsonValue::Null) {
+            merged.insert(key.clone(), merge_patch(patch_value));
+        }
+    }
+    JsonValue::Object(Arc::new(merged))
+}
+
+/// Replace `-0.0` with `0.0` and any NaN with [f64::NAN], see [JsonValue::normalize_floats].
+fn normalize_float(f: f64) -> f64 {
+    if f.is_nan() {
+        f64::NAN
+    } else if f == 0.0 {
+        // `-0.0 == 0.0`, so this also replaces `-0.0`
+        0.0
+    } else {
+        f
+    }
+}
+
+fn coerce_number(value: &mut JsonValue<'_>, mode: NumberCoercion) {
+    match (mode, &*value) {
+        (NumberCoercion::AllFloat, JsonValue::Int(int)) => *value = JsonValue::Float(*int as f64),
+        #[cfg(feature = "num-bigint")]
+        (NumberCoercion::AllFloat, JsonValue::BigInt(big_int)) => {
+            use num_traits::cast::ToPrimitive;
+            *value = JsonValue::Float(big_int.to_f64().unwrap_or(f64::NAN));
+        }
+        // `i64::MAX as f64` rounds up to 2^63, hence the exclusive upper bound
+        (NumberCoercion::IntsWherePossible, JsonValue::Float(float))
+            if float.fract() == 0.0 && *float >= i64::MIN as f64 && *float < i64::MAX as f64 =>
+        {
+            *value = JsonValue::Int(*float as i64);
+        }
+        #[cfg(feature = "num-bigint")]
+        (NumberCoercion::IntsWherePossible, JsonValue::BigInt(big_int)) => {
+            if let Ok(int) = i64::try_from(big_int) {
+                *value = JsonValue::Int(int);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Parse exactly one JSON value from the start of `data`, returning the value and the number of bytes consumed.
+///
+/// Unlike [JsonValue::parse], any data after the value is ignored, so the caller can continue parsing
+/// from `&data[consumed..]`. Whitespace after the value is only counted in the consumed length if
+/// `include_trailing_whitespace` is `true`.
+pub fn parse_prefix(data: &[u8], include_trailing_whitespace: bool) -> Result<(JsonValue<'_>, usize), JsonError> {
+    parse_prefix_with_options(data, include_trailing_whitespace, ParseOptions::default())
+}
+
+/// Like [parse_prefix], but using [ParseOptions], trailing whitespace includes comments and JSON5 whitespace
+/// if they're allowed.
+pub fn parse_prefix_with_options(
+    data: &[u8],
+    include_trailing_whitespace: bool,
+    options: ParseOptions,
+) -> Result<(JsonValue<'_>, usize), JsonError> {
+    let mut parser = Parser::new(data);
+    let v = JsonValue::take_with_options(&mut parser, options)?;
+    if include_trailing_whitespace {
+        parser.eat_whitespace();
+    }
+    Ok((v, parser.index))
+}
+
+/// Parse each element of the array in `data` into `elements`, see [JsonValue::parse_array_best_effort].
+fn parse_array_elements<'j>(
+    data: &'j [u8],
+    allow_inf_nan: bool,
+    elements: &mut Vec<JsonResult<JsonValue<'j>>>,
+) -> JsonResult<()> {
+    let mut parser = Parser::new(data);
+    let mut tape = Tape::default();
+    if parser.peek()? != Peek::Array {
+        return Err(json_error!(ExpectedArray, parser.index));
+    }
+    let mut next = parser.array_first()?;
+    while let Some(peek) = next {
+        let start = parser.index;
+        let element = take_value_borrowed(
+            peek,
+            &mut parser,
+            &mut tape,
+            DEFAULT_RECURSION_LIMIT,
+            allow_inf_nan,
+            PartialMode::Off,
+        )
+        .and_then(|value| match parser.eat_whitespace() {
+            // EOF is an error with the array rather than this element, raised by `array_step`
+            Some(b',' | b']') | None => Ok(value),
+            Some(_) => Err(json_error!(ExpectedListCommaOrEnd, parser.index)),
+        });
+        if element.is_err() {
+            parser.index = array_element_end(data, start);
+        }
+        elements.push(element);
+        next = parser.array_step()?;
+    }
+    parser.finish()
+}
+
+/// Find the `,` or `]` which ends the array element starting at `index`, skipping over nested arrays,
+/// objects and strings, or `data.len()` if there isn't one.
+fn array_element_end(data: &[u8], mut index: usize) -> usize {
+    let mut depth = 0_usize;
+    while let Some(&byte) = data.get(index) {
+        match byte {
+            b'"' => {
+                index += 1;
+                while let Some(&byte) = data.get(index) {
+                    match byte {
+                        b'\\' => index += 1,
+                        b'"' => break,
+                        _ => (),
+                    }
+                    index += 1;
+                }
+            }
+            b',' | b']' if depth == 0 => return index,
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        index += 1;
+    }
+    data.len()
+}
+
+/// The type of a [JsonValue::RawNumber], numbers with a fraction or exponent, `NaN` and `Infinity` are floats.
+#[cfg(feature = "raw-number")]
+pub(crate) fn raw_number_type(s: &str) -> JsonType {
+    if s.bytes().any(|b| matches!(b, b'.' | b'e' | b'E' | b'N' | b'I')) {
+        JsonType::Float
+    } else {
+        JsonType::Int
+    }
 }
 
 fn value_static(v: JsonValue<'_>) -> JsonValue<'static> {
@@ -159,16 +864,228 @@ fn value_static(v: JsonValue<'_>) -> JsonValue<'static> {
         #[cfg(feature = "num-bigint")]
         JsonValue::BigInt(b) => JsonValue::BigInt(b),
         JsonValue::Float(f) => JsonValue::Float(f),
+        #[cfg(feature = "raw-number")]
+        JsonValue::RawNumber(s) => JsonValue::RawNumber(s.into_owned().into()),
         JsonValue::Str(s) => JsonValue::Str(s.into_owned().into()),
         JsonValue::Array(v) => JsonValue::Array(Arc::new(v.iter().map(JsonValue::to_static).collect::<SmallVec<_>>())),
         JsonValue::Object(o) => JsonValue::Object(Arc::new(o.to_static())),
     }
 }
 
+impl From<bool> for JsonValue<'_> {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),+) => {
+        $(
+            impl From<$t> for JsonValue<'_> {
+                fn from(i: $t) -> Self {
+                    Self::Int(i.into())
+                }
+            }
+        )+
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f64> for JsonValue<'_> {
+    fn from(f: f64) -> Self {
+        Self::Float(f)
+    }
+}
+
+impl<'s> From<&'s str> for JsonValue<'s> {
+    fn from(s: &'s str) -> Self {
+        Self::Str(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for JsonValue<'_> {
+    fn from(s: String) -> Self {
+        Self::Str(Cow::Owned(s))
+    }
+}
+
+impl<'s> From<Cow<'s, str>> for JsonValue<'s> {
+    fn from(s: Cow<'s, str>) -> Self {
+        Self::Str(s)
+    }
+}
+
+/// Collect values into a [JsonValue::Array].
+impl<'s> FromIterator<JsonValue<'s>> for JsonValue<'s> {
+    fn from_iter<I: IntoIterator<Item = JsonValue<'s>>>(iter: I) -> Self {
+        Self::Array(Arc::new(iter.into_iter().collect()))
+    }
+}
+
+/// Collect `(key, value)` pairs into a [JsonValue::Object].
+///
+/// As when parsing JSON, entries with duplicate keys are all kept, in order.
+impl<'s, K: Into<Cow<'s, str>>> FromIterator<(K, JsonValue<'s>)> for JsonValue<'s> {
+    fn from_iter<I: IntoIterator<Item = (K, JsonValue<'s>)>>(iter: I) -> Self {
+        let mut object = LazyIndexMap::new();
+        for (key, value) in iter {
+            object.insert(key.into(), value);
+        }
+        Self::Object(Arc::new(object))
+    }
+}
+
+impl JsonValue<'_> {
+    fn wrong_type(&self, expected: JsonType) -> ValueConversionError {
+        ValueConversionError::WrongType {
+            expected,
+            actual: self.json_type(),
+        }
+    }
+}
+
+impl TryFrom<JsonValue<'_>> for String {
+    type Error = ValueConversionError;
+
+    fn try_from(value: JsonValue<'_>) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Str(s) => Ok(s.into_owned()),
+            _ => Err(value.wrong_type(JsonType::String)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue<'_>> for i64 {
+    type Error = ValueConversionError;
+
+    fn try_from(value: JsonValue<'_>) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Int(i) => Ok(i),
+            #[cfg(feature = "num-bigint")]
+            JsonValue::BigInt(_) => Err(ValueConversionError::NumberOutOfRange),
+            _ => Err(value.wrong_type(JsonType::Int)),
+        }
+    }
+}
+
+/// Integers are widened to floats, which may lose precision.
+impl TryFrom<JsonValue<'_>> for f64 {
+    type Error = ValueConversionError;
+
+    fn try_from(value: JsonValue<'_>) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Float(f) => Ok(f),
+            JsonValue::Int(i) => Ok(i as f64),
+            #[cfg(feature = "num-bigint")]
+            JsonValue::BigInt(b) => Ok(NumberInt::BigInt(b).into()),
+            _ => Err(value.wrong_type(JsonType::Float)),
+        }
+    }
+}
+
+impl TryFrom<JsonValue<'_>> for bool {
+    type Error = ValueConversionError;
+
+    fn try_from(value: JsonValue<'_>) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Bool(b) => Ok(b),
+            _ => Err(value.wrong_type(JsonType::Bool)),
+        }
+    }
+}
+
+/// The array is only copied if it's shared with another value.
+impl<'s> TryFrom<JsonValue<'s>> for Vec<JsonValue<'s>> {
+    type Error = ValueConversionError;
+
+    fn try_from(value: JsonValue<'s>) -> Result<Self, Self::Error> {
+        match value {
+            JsonValue::Array(array) => Ok(Arc::try_unwrap(array).unwrap_or_else(|a| (*a).clone()).into_vec()),
+            _ => Err(value.wrong_type(JsonType::Array)),
+        }
+    }
+}
+
+// comparisons with Rust values only match the corresponding variant, e.g. `JsonValue::Int(1) != 1.0`
+
+impl PartialEq<str> for JsonValue<'_> {
+    fn eq(&self, other: &str) -> bool {
+        matches!(self, JsonValue::Str(s) if s == other)
+    }
+}
+
+impl PartialEq<&str> for JsonValue<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<String> for JsonValue<'_> {
+    fn eq(&self, other: &String) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<i64> for JsonValue<'_> {
+    fn eq(&self, other: &i64) -> bool {
+        matches!(self, JsonValue::Int(i) if i == other)
+    }
+}
+
+impl PartialEq<bool> for JsonValue<'_> {
+    fn eq(&self, other: &bool) -> bool {
+        matches!(self, JsonValue::Bool(b) if b == other)
+    }
+}
+
+impl PartialEq<f64> for JsonValue<'_> {
+    fn eq(&self, other: &f64) -> bool {
+        matches!(self, JsonValue::Float(f) if f == other)
+    }
+}
+
+/// Like [JsonValue::get], but panics if this isn't an object or doesn't have `key`.
+impl<'j> Index<&str> for JsonValue<'j> {
+    type Output = JsonValue<'j>;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            JsonValue::Object(object) => object
+                .get(key)
+                .unwrap_or_else(|| panic!("key {key:?} not found in object")),
+            _ => panic!("cannot index {} with key {key:?}", self.json_type()),
+        }
+    }
+}
+
+/// Like [JsonValue::get_index], but panics if this isn't an array or `index` is out of range.
+impl<'j> Index<usize> for JsonValue<'j> {
+    type Output = JsonValue<'j>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            JsonValue::Array(array) => &array[index],
+            _ => panic!("cannot index {} with {index}", self.json_type()),
+        }
+    }
+}
+
 impl JsonValue<'static> {
     /// Parse a JSON enum from a byte slice, returning an owned version of the enum.
     pub fn parse_owned(data: &[u8], allow_inf_nan: bool, allow_partial: PartialMode) -> Result<Self, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            partial_mode: allow_partial,
+            ..ParseOptions::default()
+        };
+        Self::parse_owned_with_options(data, options)
+    }
+
+    /// Parse a JSON enum from a byte slice using [ParseOptions], returning an owned version of the enum.
+    pub fn parse_owned_with_options(data: &[u8], options: ParseOptions) -> Result<Self, JsonError> {
         let mut parser = Parser::new(data);
+        parser.set_options(options);
 
         let mut tape = Tape::default();
         let peek = parser.peek()?;
@@ -176,9 +1093,9 @@ impl JsonValue<'static> {
             peek,
             &mut parser,
             &mut tape,
-            DEFAULT_RECURSION_LIMIT,
-            allow_inf_nan,
-            allow_partial,
+            options.recursion_limit_or_default(),
+            options.allow_inf_nan,
+            options.partial_mode,
         )?;
         parser.finish()?;
         Ok(v)
@@ -201,6 +1118,31 @@ pub(crate) fn take_value_borrowed<'j>(
         allow_inf_nan,
         allow_partial,
         &|s: StringOutput<'_, 'j>| s.into(),
+        &|s: StringOutput<'_, 'j>| s.into(),
+        None,
+    )
+}
+
+/// Like [take_value_borrowed], but arrays and objects are taken from `pool`.
+pub(crate) fn take_value_pooled<'j>(
+    peek: Peek,
+    parser: &mut Parser<'j>,
+    tape: &mut Tape,
+    recursion_limit: u8,
+    allow_inf_nan: bool,
+    allow_partial: PartialMode,
+    pool: &JsonValuePool,
+) -> JsonResult<JsonValue<'j>> {
+    take_value(
+        peek,
+        parser,
+        tape,
+        recursion_limit,
+        allow_inf_nan,
+        allow_partial,
+        &|s: StringOutput<'_, 'j>| s.into(),
+        &|s: StringOutput<'_, 'j>| s.into(),
+        Some(pool),
     )
 }
 
@@ -220,9 +1162,163 @@ pub(crate) fn take_value_owned<'j>(
         allow_inf_nan,
         allow_partial,
         &|s: StringOutput<'_, 'j>| Into::<String>::into(s).into(),
+        &|s: StringOutput<'_, 'j>| Into::<String>::into(s).into(),
+        None,
     )
 }
 
+/// Write `value` as JSON to `out`, pretty-printed if `indent` is set, `depth` is the current nesting level.
+fn write_json(out: &mut String, value: &JsonValue, indent: Option<usize>, depth: usize) {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Int(i) => {
+            let _ = write!(out, "{i}");
+        }
+        #[cfg(feature = "num-bigint")]
+        JsonValue::BigInt(b) => {
+            let _ = write!(out, "{b}");
+        }
+        JsonValue::Float(f) if f.is_finite() => {
+            // `Debug` gives the shortest representation which round-trips, always with a `.` or exponent,
+            // a `+` is added to positive exponents to match serde_json
+            let start = out.len();
+            let _ = write!(out, "{f:?}");
+            if let Some(e) = out[start..].find('e') {
+                if !out[start + e + 1..].starts_with('-') {
+                    out.insert(start + e + 1, '+');
+                }
+            }
+        }
+        JsonValue::Float(_) => out.push_str("null"),
+        #[cfg(feature = "raw-number")]
+        JsonValue::RawNumber(s) => match s.trim_start_matches('-') {
+            "NaN" | "Infinity" => out.push_str("null"),
+            _ => out.push_str(s),
+        },
+        JsonValue::Str(s) => write_json_str(out, s),
+        JsonValue::Array(array) => {
+            write_json_container(out, '[', ']', array.iter(), indent, depth, |out, v, depth| {
+                write_json(out, v, indent, depth);
+            });
+        }
+        JsonValue::Object(object) => {
+            write_json_container(out, '{', '}', object.iter(), indent, depth, |out, (k, v), depth| {
+                write_json_str(out, k);
+                out.push_str(if indent.is_some() { ": " } else { ":" });
+                write_json(out, v, indent, depth);
+            });
+        }
+    }
+}
+
+/// Write the items of an array or object between `open` and `close`, each item on its own line if `indent` is set.
+fn write_json_container<T>(
+    out: &mut String,
+    open: char,
+    close: char,
+    items: impl ExactSizeIterator<Item = T>,
+    indent: Option<usize>,
+    depth: usize,
+    write_item: impl Fn(&mut String, T, usize),
+) {
+    out.push(open);
+    if items.len() == 0 {
+        out.push(close);
+        return;
+    }
+    let newline = |out: &mut String, depth: usize| {
+        if let Some(indent) = indent {
+            out.push('\n');
+            for _ in 0..indent * depth {
+                out.push(' ');
+            }
+        }
+    };
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        newline(out, depth + 1);
+        write_item(out, item, depth + 1);
+    }
+    newline(out, depth);
+    out.push(close);
+}
+
+/// Write `s` as a JSON string, escaping quotes, backslashes and control characters like `serde_json`.
+fn write_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    let mut start = 0;
+    for (i, byte) in s.bytes().enumerate() {
+        let escape = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0x08 => "\\b",
+            0x0c => "\\f",
+            0x00..=0x1f => "",
+            _ => continue,
+        };
+        out.push_str(&s[start..i]);
+        if escape.is_empty() {
+            let _ = write!(out, "\\u{byte:04x}");
+        } else {
+            out.push_str(escape);
+        }
+        start = i + 1;
+    }
+    out.push_str(&s[start..]);
+    out.push('"');
+}
+
+/// The unescaped reference tokens of a JSON Pointer, or `None` if it's not empty and doesn't start with `/`.
+fn pointer_tokens(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    let tokens = if pointer.is_empty() {
+        None
+    } else {
+        Some(pointer.strip_prefix('/')?.split('/'))
+    };
+    Some(tokens.into_iter().flatten().map(|token| {
+        if token.contains('~') {
+            // `~1` first, so `~01` becomes `~1` rather than `/`
+            Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+        } else {
+            Cow::Borrowed(token)
+        }
+    }))
+}
+
+/// The array index a JSON Pointer token refers to, RFC 6901 doesn't allow leading zeros or a sign.
+fn pointer_index(token: &str) -> Option<usize> {
+    if token.starts_with('+') || (token.len() > 1 && token.starts_with('0')) {
+        None
+    } else {
+        token.parse().ok()
+    }
+}
+
+/// A new empty array, from `pool` if there is one.
+fn new_array<'s>(pool: Option<&JsonValuePool>) -> JsonArray<'s> {
+    match pool {
+        Some(pool) => pool.array(),
+        None => Arc::new(SmallVec::new()),
+    }
+}
+
+/// A new empty object, from `pool` if there is one.
+fn new_object<'s>(pool: Option<&JsonValuePool>) -> JsonObject<'s> {
+    match pool {
+        Some(pool) => pool.object(),
+        None => Arc::new(LazyIndexMap::new()),
+    }
+}
+
+/// `create_cow` converts strings and `create_key` converts object keys, e.g. to intern them in a [KeySet],
+/// arrays and objects are taken from `pool` if it's set.
+#[allow(clippy::too_many_arguments)]
 fn take_value<'j, 's>(
     peek: Peek,
     parser: &mut Parser<'j>,
@@ -231,6 +1327,8 @@ fn take_value<'j, 's>(
     allow_inf_nan: bool,
     allow_partial: PartialMode,
     create_cow: &impl Fn(StringOutput<'_, 'j>) -> Cow<'s, str>,
+    create_key: &impl Fn(StringOutput<'_, 'j>) -> Cow<'s, str>,
+    pool: Option<&JsonValuePool>,
 ) -> JsonResult<JsonValue<'s>> {
     let partial_active = allow_partial.is_active();
     match peek {
@@ -252,7 +1350,7 @@ fn take_value<'j, 's>(
             Ok(JsonValue::Str(create_cow(s)))
         }
         Peek::Array => {
-            let array = Arc::new(SmallVec::new());
+            let array = new_array(pool);
             let peek_first = match parser.array_first() {
                 Ok(Some(peek)) => peek,
                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
@@ -267,17 +1365,19 @@ fn take_value<'j, 's>(
                 allow_inf_nan,
                 allow_partial,
                 create_cow,
+                create_key,
+                pool,
             )
         }
         Peek::Object => {
             // same for objects
-            let object = Arc::new(LazyIndexMap::new());
+            let object = new_object(pool);
             let first_key = match parser.object_first::<StringDecoder>(tape) {
                 Ok(Some(first_key)) => first_key,
                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
                 _ => return Ok(JsonValue::Object(object)),
             };
-            let first_key = create_cow(first_key);
+            let first_key = create_key(first_key);
             match parser.peek() {
                 Ok(peek) => take_value_recursive(
                     peek,
@@ -291,11 +1391,18 @@ fn take_value<'j, 's>(
                     allow_inf_nan,
                     allow_partial,
                     create_cow,
+                    create_key,
+                    pool,
                 ),
                 Err(e) if !(partial_active && e.allowed_if_partial()) => Err(e),
                 _ => Ok(JsonValue::Object(object)),
             }
         }
+        _ if parser.at_nan_inf_as_null(peek.into_inner()) => parser
+            .consume_number::<NumberRange>(peek.into_inner(), true)
+            .map(|_| JsonValue::Null),
+        #[cfg(feature = "raw-number")]
+        _ if parser.raw_numbers => take_raw_number(peek, parser, allow_inf_nan, create_cow),
         _ => {
             let n = parser.consume_number::<NumberAny>(peek.into_inner(), allow_inf_nan);
             match n {
@@ -315,6 +1422,37 @@ fn take_value<'j, 's>(
     }
 }
 
+/// Read a number as [JsonValue::RawNumber], see [ParseOptions::raw_numbers].
+#[cfg(feature = "raw-number")]
+fn take_raw_number<'j, 's>(
+    peek: Peek,
+    parser: &mut Parser<'j>,
+    allow_inf_nan: bool,
+    create_cow: &impl Fn(StringOutput<'_, 'j>) -> Cow<'s, str>,
+) -> JsonResult<JsonValue<'s>> {
+    match parser.consume_raw_number(peek.into_inner(), allow_inf_nan) {
+        Ok(s) => Ok(JsonValue::RawNumber(create_cow(StringOutput::Data(s, true)))),
+        Err(_) if !peek.is_num() => Err(json_error!(ExpectedSomeValue, parser.index)),
+        Err(e) => Err(e),
+    }
+}
+
+/// Error if `key`, the last key read, is already in `object` and [ParseOptions::catch_duplicate_keys] is set.
+pub(crate) fn check_duplicate_key<V: std::fmt::Debug>(
+    parser: &Parser,
+    object: &LazyIndexMap<Cow<'_, str>, V>,
+    key: &str,
+) -> JsonResult<()> {
+    if parser.catch_duplicate_keys && object.get(key).is_some() {
+        Err(JsonError::new(
+            JsonErrorType::DuplicateKey(key.to_owned()),
+            parser.last_key_index(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 enum RecursedValue<'s> {
     Array(JsonArray<'s>),
     Object {
@@ -335,6 +1473,8 @@ fn take_value_recursive<'j, 's>(
     allow_inf_nan: bool,
     allow_partial: PartialMode,
     create_cow: &impl Fn(StringOutput<'_, 'j>) -> Cow<'s, str>,
+    create_key: &impl Fn(StringOutput<'_, 'j>) -> Cow<'s, str>,
+    pool: Option<&JsonValuePool>,
 ) -> JsonResult<JsonValue<'s>> {
     let recursion_limit: usize = recursion_limit.into();
 
@@ -342,11 +1482,18 @@ fn take_value_recursive<'j, 's>(
     let partial_active = allow_partial.is_active();
 
     macro_rules! push_recursion {
-        ($next_peek:expr, $value:expr) => {
-            peek = $next_peek;
-            recursion_stack.push(std::mem::replace(&mut current_recursion, $value));
-            if recursion_stack.len() >= recursion_limit {
-                return Err(json_error!(RecursionLimitExceeded, parser.index));
+        ($label:lifetime, $next_peek:expr, $in_object:expr, $value:expr) => {
+            if parser.max_depth_error_recovery && recursion_stack.len() + 1 >= recursion_limit {
+                // too deep, skip the rest of the array or object and use `null` in its place
+                skip_nested($next_peek, $in_object, parser, tape, allow_inf_nan).map(|()| JsonValue::Null)
+            } else {
+                peek = $next_peek;
+                recursion_stack.push(std::mem::replace(&mut current_recursion, $value));
+                if recursion_stack.len() >= recursion_limit {
+                    return Err(json_error!(RecursionLimitExceeded, parser.index));
+                }
+                // immediately jump to process the first value in the array or object
+                continue $label;
             }
         };
     }
@@ -364,40 +1511,40 @@ fn take_value_recursive<'j, 's>(
                             .consume_string::<StringDecoder>(tape, allow_partial.allow_trailing_str())
                             .map(|s| JsonValue::Str(create_cow(s))),
                         Peek::Array => {
-                            let array = Arc::new(SmallVec::new());
+                            let array = new_array(pool);
                             match parser.array_first() {
                                 Ok(Some(first_peek)) => {
-                                    push_recursion!(first_peek, RecursedValue::Array(array));
-                                    // immediately jump to process the first value in the array
-                                    continue 'recursion;
+                                    push_recursion!('recursion, first_peek, ARRAY, RecursedValue::Array(array))
                                 }
                                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                _ => (),
-                            };
-                            Ok(JsonValue::Array(array))
+                                _ => Ok(JsonValue::Array(array)),
+                            }
                         }
                         Peek::Object => {
-                            let object = Arc::new(LazyIndexMap::new());
+                            let object = new_object(pool);
                             match parser.object_first::<StringDecoder>(tape) {
                                 Ok(Some(first_key)) => match parser.peek() {
-                                    Ok(peek) => {
-                                        push_recursion!(
-                                            peek,
-                                            RecursedValue::Object {
-                                                partial: object,
-                                                next_key: create_cow(first_key)
-                                            }
-                                        );
-                                        continue 'recursion;
-                                    }
+                                    Ok(peek) => push_recursion!(
+                                        'recursion,
+                                        peek,
+                                        OBJECT,
+                                        RecursedValue::Object {
+                                            partial: object,
+                                            next_key: create_key(first_key)
+                                        }
+                                    ),
                                     Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                    _ => (),
+                                    _ => Ok(JsonValue::Object(object)),
                                 },
                                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                _ => (),
-                            };
-                            Ok(JsonValue::Object(object))
+                                _ => Ok(JsonValue::Object(object)),
+                            }
                         }
+                        _ if parser.at_nan_inf_as_null(peek.into_inner()) => parser
+                            .consume_number::<NumberRange>(peek.into_inner(), true)
+                            .map(|_| JsonValue::Null),
+                        #[cfg(feature = "raw-number")]
+                        _ if parser.raw_numbers => take_raw_number(peek, parser, allow_inf_nan, create_cow),
                         _ => parser
                             .consume_number::<NumberAny>(peek.into_inner(), allow_inf_nan)
                             .map_err(|e| {
@@ -459,40 +1606,40 @@ fn take_value_recursive<'j, 's>(
                             .consume_string::<StringDecoder>(tape, allow_partial.allow_trailing_str())
                             .map(|s| JsonValue::Str(create_cow(s))),
                         Peek::Array => {
-                            let array = Arc::new(SmallVec::new());
+                            let array = new_array(pool);
                             match parser.array_first() {
                                 Ok(Some(first_peek)) => {
-                                    push_recursion!(first_peek, RecursedValue::Array(array));
-                                    // immediately jump to process the first value in the array
-                                    continue 'recursion;
+                                    push_recursion!('recursion, first_peek, ARRAY, RecursedValue::Array(array))
                                 }
                                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                _ => (),
-                            };
-                            Ok(JsonValue::Array(array))
+                                _ => Ok(JsonValue::Array(array)),
+                            }
                         }
                         Peek::Object => {
-                            let object = Arc::new(LazyIndexMap::new());
+                            let object = new_object(pool);
                             match parser.object_first::<StringDecoder>(tape) {
                                 Ok(Some(first_key)) => match parser.peek() {
-                                    Ok(peek) => {
-                                        push_recursion!(
-                                            peek,
-                                            RecursedValue::Object {
-                                                partial: object,
-                                                next_key: create_cow(first_key)
-                                            }
-                                        );
-                                        continue 'recursion;
-                                    }
+                                    Ok(peek) => push_recursion!(
+                                        'recursion,
+                                        peek,
+                                        OBJECT,
+                                        RecursedValue::Object {
+                                            partial: object,
+                                            next_key: create_key(first_key)
+                                        }
+                                    ),
                                     Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                    _ => (),
+                                    _ => Ok(JsonValue::Object(object)),
                                 },
                                 Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
-                                _ => (),
-                            };
-                            Ok(JsonValue::Object(object))
+                                _ => Ok(JsonValue::Object(object)),
+                            }
                         }
+                        _ if parser.at_nan_inf_as_null(peek.into_inner()) => parser
+                            .consume_number::<NumberRange>(peek.into_inner(), true)
+                            .map(|_| JsonValue::Null),
+                        #[cfg(feature = "raw-number")]
+                        _ if parser.raw_numbers => take_raw_number(peek, parser, allow_inf_nan, create_cow),
                         _ => parser
                             .consume_number::<NumberAny>(peek.into_inner(), allow_inf_nan)
                             .map_err(|e| {
@@ -519,9 +1666,10 @@ fn take_value_recursive<'j, 's>(
                                         Ok(next_peek) => {
                                             // object continuing
                                             partial.insert(
-                                                std::mem::replace(next_key, create_cow(yet_another_key)),
+                                                std::mem::replace(next_key, create_key(yet_another_key)),
                                                 value,
                                             );
+                                            check_duplicate_key(parser, partial, next_key)?;
                                             peek = next_peek;
                                             continue;
                                         }
@@ -582,10 +1730,9 @@ fn take_value_recursive<'j, 's>(
                     match parser.object_step::<StringDecoder>(tape) {
                         Ok(Some(next_key)) => match parser.peek() {
                             Ok(next_peek) => {
-                                current_recursion = RecursedValue::Object {
-                                    partial,
-                                    next_key: create_cow(next_key),
-                                };
+                                let next_key = create_key(next_key);
+                                check_duplicate_key(parser, &partial, &next_key)?;
+                                current_recursion = RecursedValue::Object { partial, next_key };
                                 break next_peek;
                             }
                             Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
@@ -631,7 +1778,7 @@ pub(crate) fn take_value_skip(
             }
         }
         _ => parser
-            .consume_number::<NumberRange>(peek.into_inner(), allow_inf_nan)
+            .consume_number::<NumberRange>(peek.into_inner(), allow_inf_nan || parser.nan_inf_as_null)
             .map(drop)
             .map_err(|e| {
                 if !peek.is_num() {
@@ -646,6 +1793,55 @@ pub(crate) fn take_value_skip(
 const ARRAY: bool = false;
 const OBJECT: bool = true;
 
+/// Skip the rest of an array (`in_object == ARRAY`) or object whose next value is `peek`, however deeply
+/// nested it is, used in place of recursing when [ParseOptions::max_depth_error_recovery] is enabled.
+fn skip_nested(
+    mut peek: Peek,
+    in_object: bool,
+    parser: &mut Parser,
+    tape: &mut Tape,
+    allow_inf_nan: bool,
+) -> JsonResult<()> {
+    let mut stack: Vec<bool> = vec![in_object];
+    loop {
+        match peek {
+            Peek::Array => {
+                if let Some(next_peek) = parser.array_first()? {
+                    stack.push(ARRAY);
+                    peek = next_peek;
+                    continue;
+                }
+            }
+            Peek::Object => {
+                if parser.object_first::<StringDecoderRange>(tape)?.is_some() {
+                    stack.push(OBJECT);
+                    peek = parser.peek()?;
+                    continue;
+                }
+            }
+            // not an array or object, so the recursion limit doesn't matter
+            _ => take_value_skip(peek, parser, tape, 0, allow_inf_nan)?,
+        }
+
+        peek = loop {
+            match stack.last() {
+                Some(&ARRAY) => {
+                    if let Some(next_peek) = parser.array_step()? {
+                        break next_peek;
+                    }
+                }
+                Some(&OBJECT) => {
+                    if parser.object_step::<StringDecoderRange>(tape)?.is_some() {
+                        break parser.peek()?;
+                    }
+                }
+                None => return Ok(()),
+            }
+            stack.pop();
+        };
+    }
+}
+
 #[inline(never)] // this is an iterative algo called only from take_value_skip, no point in inlining
 fn take_value_skip_recursive(
     mut peek: Peek,
@@ -697,7 +1893,7 @@ fn take_value_skip_recursive(
             }
             _ => {
                 parser
-                    .consume_number::<NumberRange>(peek.into_inner(), allow_inf_nan)
+                    .consume_number::<NumberRange>(peek.into_inner(), allow_inf_nan || parser.nan_inf_as_null)
                     .map_err(|e| {
                         if !peek.is_num() {
                             json_error!(ExpectedSomeValue, parser.index)
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use smallvec::SmallVec;
+
+use crate::errors::JsonError;
+use crate::lazy_index_map::LazyIndexMap;
+use crate::parse::Parser;
+use crate::string_decoder::Tape;
+use crate::value::{take_value_pooled, JsonArray, JsonObject, JsonValue};
+use crate::ParseOptions;
+
+/// A pool of arrays and objects which [JsonValue]s are built from and returned to, to avoid allocating
+/// them afresh for every document, e.g. in a request handler which parses one document per request.
+///
+/// Arrays and objects are returned to the pool, keeping their memory, when the [PooledJsonValue] holding
+/// them is dropped. The pool isn't `Sync`, so use one pool per thread, e.g. in a `thread_local!`.
+///
+/// ```rust
+/// use jiter::{JsonValue, JsonValuePool};
+///
+/// let pool = JsonValuePool::new();
+/// for data in [br#"{"id": 1, "tags": ["a"]}"#, br#"{"id": 2, "tags": ["b"]}"#] {
+///     let value = pool.parse(data, false).unwrap();
+///     let JsonValue::Object(object) = &*value else {
+///         panic!("expected an object");
+///     };
+///     assert!(object.get("id").is_some());
+/// }
+/// assert_eq!(pool.len(), 2);
+/// ```
+#[derive(Default)]
+pub struct JsonValuePool {
+    // the arrays and objects are always empty, so they don't borrow from any data, see `cast_array`
+    arrays: RefCell<Vec<JsonArray<'static>>>,
+    objects: RefCell<Vec<JsonObject<'static>>>,
+}
+
+impl fmt::Debug for JsonValuePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonValuePool")
+            .field("arrays", &self.arrays.borrow().len())
+            .field("objects", &self.objects.borrow().len())
+            .finish()
+    }
+}
+
+impl JsonValuePool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of arrays and objects in the pool, ready to be reused.
+    pub fn len(&self) -> usize {
+        self.arrays.borrow().len() + self.objects.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all the arrays and objects in the pool, freeing their memory.
+    pub fn clear(&mut self) {
+        self.arrays.get_mut().clear();
+        self.objects.get_mut().clear();
+    }
+
+    /// Like [JsonValue::parse], but arrays and objects are taken from the pool.
+    pub fn parse<'j>(&self, data: &'j [u8], allow_inf_nan: bool) -> Result<PooledJsonValue<'j, '_>, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            ..ParseOptions::default()
+        };
+        self.parse_with_options(data, options)
+    }
+
+    /// Like [JsonValue::parse_with_options], but arrays and objects are taken from the pool.
+    pub fn parse_with_options<'j>(
+        &self,
+        data: &'j [u8],
+        options: ParseOptions,
+    ) -> Result<PooledJsonValue<'j, '_>, JsonError> {
+        let mut parser = Parser::new(data);
+        parser.set_options(options);
+
+        let mut tape = Tape::default();
+        let peek = parser.peek()?;
+        let value = PooledJsonValue {
+            value: take_value_pooled(
+                peek,
+                &mut parser,
+                &mut tape,
+                options.recursion_limit_or_default(),
+                options.allow_inf_nan,
+                options.partial_mode,
+                self,
+            )?,
+            pool: self,
+        };
+        if !options.partial_mode.is_active() {
+            parser.finish()?;
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn array<'j>(&self) -> JsonArray<'j> {
+        match self.arrays.borrow_mut().pop() {
+            // SAFETY: arrays in the pool are empty
+            Some(array) => unsafe { cast_array(array) },
+            None => Arc::new(SmallVec::new()),
+        }
+    }
+
+    pub(crate) fn object<'j>(&self) -> JsonObject<'j> {
+        match self.objects.borrow_mut().pop() {
+            // SAFETY: objects in the pool are empty
+            Some(object) => unsafe { cast_object(object) },
+            None => Arc::new(LazyIndexMap::new()),
+        }
+    }
+
+    /// Return the arrays and objects in `value` to the pool, those which are shared with other values are left.
+    fn recycle(&self, value: JsonValue<'_>) {
+        match value {
+            JsonValue::Array(mut array) => {
+                if let Some(elements) = Arc::get_mut(&mut array) {
+                    for element in elements.drain(..) {
+                        self.recycle(element);
+                    }
+                    // SAFETY: the array has just been emptied
+                    self.arrays.borrow_mut().push(unsafe { cast_array(array) });
+                }
+            }
+            JsonValue::Object(mut object) => {
+                if let Some(map) = Arc::get_mut(&mut object) {
+                    for (_, value) in map.drain() {
+                        self.recycle(value);
+                    }
+                    // SAFETY: the object has just been emptied
+                    self.objects.borrow_mut().push(unsafe { cast_object(object) });
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Change the lifetime of an array, the lifetime doesn't affect its layout.
+///
+/// # Safety
+/// `array` must be empty, so it doesn't hold any borrowed data.
+unsafe fn cast_array<'j>(array: JsonArray<'_>) -> JsonArray<'j> {
+    Arc::from_raw(Arc::into_raw(array).cast())
+}
+
+/// Like [cast_array], for objects.
+///
+/// # Safety
+/// `object` must be empty, including its lookup map.
+unsafe fn cast_object<'j>(object: JsonObject<'_>) -> JsonObject<'j> {
+    Arc::from_raw(Arc::into_raw(object).cast())
+}
+
+/// A [JsonValue] parsed by [JsonValuePool::parse], its arrays and objects are returned to the pool when
+/// it's dropped.
+///
+/// Arrays and objects which are still shared when it's dropped, e.g. because they were cloned out of the value,
+/// are dropped as usual instead.
+pub struct PooledJsonValue<'j, 'p> {
+    value: JsonValue<'j>,
+    pool: &'p JsonValuePool,
+}
+
+impl fmt::Debug for PooledJsonValue<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<'j> PooledJsonValue<'j, '_> {
+    /// Take the value, so its arrays and objects aren't returned to the pool.
+    pub fn into_inner(mut self) -> JsonValue<'j> {
+        std::mem::replace(&mut self.value, JsonValue::Null)
+    }
+}
+
+impl<'j> Deref for PooledJsonValue<'j, '_> {
+    type Target = JsonValue<'j>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl DerefMut for PooledJsonValue<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl Drop for PooledJsonValue<'_, '_> {
+    fn drop(&mut self) {
+        self.pool.recycle(std::mem::replace(&mut self.value, JsonValue::Null));
+    }
+}
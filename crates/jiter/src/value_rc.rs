@@ -0,0 +1,216 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+
+#[cfg(feature = "num-bigint")]
+use num_bigint::BigInt;
+use smallvec::SmallVec;
+
+use crate::errors::{json_error, JsonError, JsonResult, JsonType};
+use crate::lazy_index_map::LazyIndexMap;
+use crate::number_decoder::{NumberAny, NumberInt, NumberRange};
+use crate::parse::{Parser, Peek};
+use crate::string_decoder::{StringDecoder, Tape};
+#[cfg(feature = "raw-number")]
+use crate::value::raw_number_type;
+use crate::value::{check_duplicate_key, JsonValue};
+use crate::{ParseOptions, PartialMode};
+
+/// Like [JsonValue], but arrays and objects are reference counted with [Rc] instead of [Arc](std::sync::Arc).
+///
+/// Cloning and dropping arrays and objects avoids atomic operations, which makes this cheaper than
+/// [JsonValue] where values are cloned and dropped a lot, at the cost of not being `Send` or `Sync`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValueRc<'s> {
+    Null,
+    Bool(bool),
+    Int(i64),
+    #[cfg(feature = "num-bigint")]
+    BigInt(BigInt),
+    Float(f64),
+    /// See [JsonValue::RawNumber].
+    #[cfg(feature = "raw-number")]
+    RawNumber(Cow<'s, str>),
+    Str(Cow<'s, str>),
+    Array(JsonArrayRc<'s>),
+    Object(JsonObjectRc<'s>),
+}
+
+pub type JsonArrayRc<'s> = Rc<SmallVec<[JsonValueRc<'s>; 8]>>;
+pub type JsonObjectRc<'s> = Rc<LazyIndexMap<Cow<'s, str>, JsonValueRc<'s>>>;
+
+impl<'j> JsonValueRc<'j> {
+    /// Parse a JSON enum from a byte slice, returning a borrowed version of the enum.
+    pub fn parse(data: &'j [u8], allow_inf_nan: bool) -> Result<Self, JsonError> {
+        let options = ParseOptions {
+            allow_inf_nan,
+            ..ParseOptions::default()
+        };
+        Self::parse_with_options(data, options)
+    }
+
+    /// Parse a JSON enum from a byte slice using [ParseOptions], returning a borrowed version of the enum.
+    pub fn parse_with_options(data: &'j [u8], options: ParseOptions) -> Result<Self, JsonError> {
+        let mut parser = Parser::new(data);
+        parser.set_options(options);
+
+        let mut tape = Tape::default();
+        let peek = parser.peek()?;
+        let v = take_value_rc(
+            peek,
+            &mut parser,
+            &mut tape,
+            options.recursion_limit_or_default(),
+            options.allow_inf_nan,
+            options.partial_mode,
+        )?;
+        if !options.partial_mode.is_active() {
+            parser.finish()?;
+        }
+        Ok(v)
+    }
+
+    /// Get the [JsonType] of this value.
+    pub fn json_type(&self) -> JsonType {
+        match self {
+            JsonValueRc::Null => JsonType::Null,
+            JsonValueRc::Bool(_) => JsonType::Bool,
+            JsonValueRc::Int(_) => JsonType::Int,
+            #[cfg(feature = "num-bigint")]
+            JsonValueRc::BigInt(_) => JsonType::Int,
+            JsonValueRc::Float(_) => JsonType::Float,
+            #[cfg(feature = "raw-number")]
+            JsonValueRc::RawNumber(s) => raw_number_type(s),
+            JsonValueRc::Str(_) => JsonType::String,
+            JsonValueRc::Array(_) => JsonType::Array,
+            JsonValueRc::Object(_) => JsonType::Object,
+        }
+    }
+}
+
+impl<'s> From<&JsonValue<'s>> for JsonValueRc<'s> {
+    fn from(value: &JsonValue<'s>) -> Self {
+        match value {
+            JsonValue::Null => JsonValueRc::Null,
+            JsonValue::Bool(b) => JsonValueRc::Bool(*b),
+            JsonValue::Int(i) => JsonValueRc::Int(*i),
+            #[cfg(feature = "num-bigint")]
+            JsonValue::BigInt(b) => JsonValueRc::BigInt(b.clone()),
+            JsonValue::Float(f) => JsonValueRc::Float(*f),
+            #[cfg(feature = "raw-number")]
+            JsonValue::RawNumber(s) => JsonValueRc::RawNumber(s.clone()),
+            JsonValue::Str(s) => JsonValueRc::Str(s.clone()),
+            JsonValue::Array(array) => JsonValueRc::Array(Rc::new(array.iter().map(Into::into).collect())),
+            JsonValue::Object(object) => {
+                let mut new_object = LazyIndexMap::new();
+                for (k, v) in object.iter() {
+                    new_object.insert(k.clone(), v.into());
+                }
+                JsonValueRc::Object(Rc::new(new_object))
+            }
+        }
+    }
+}
+
+/// Parse a value, recursing into arrays and objects up to `recursion_limit` deep.
+///
+/// In partial mode, an error which is allowed for partial data ends the current array or object, returning
+/// what has been parsed so far, each enclosing array or object then hits the same error and ends too.
+fn take_value_rc<'j>(
+    peek: Peek,
+    parser: &mut Parser<'j>,
+    tape: &mut Tape,
+    recursion_limit: u8,
+    allow_inf_nan: bool,
+    allow_partial: PartialMode,
+) -> JsonResult<JsonValueRc<'j>> {
+    let partial_active = allow_partial.is_active();
+    match peek {
+        Peek::True => {
+            parser.consume_true()?;
+            Ok(JsonValueRc::Bool(true))
+        }
+        Peek::False => {
+            parser.consume_false()?;
+            Ok(JsonValueRc::Bool(false))
+        }
+        Peek::Null => {
+            parser.consume_null()?;
+            Ok(JsonValueRc::Null)
+        }
+        Peek::String => {
+            let s = parser.consume_string::<StringDecoder>(tape, allow_partial.allow_trailing_str())?;
+            Ok(JsonValueRc::Str(s.into()))
+        }
+        Peek::Array => {
+            let mut array: SmallVec<[JsonValueRc<'j>; 8]> = SmallVec::new();
+            let mut next_peek = parser.array_first();
+            loop {
+                let peek = match next_peek {
+                    Ok(Some(peek)) => peek,
+                    Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
+                    Ok(None) | Err(_) => break,
+                };
+                // as with `JsonValue`, only non-empty arrays and objects count towards the recursion limit
+                if recursion_limit == 0 {
+                    return Err(json_error!(RecursionLimitExceeded, parser.index));
+                }
+                match take_value_rc(peek, parser, tape, recursion_limit - 1, allow_inf_nan, allow_partial) {
+                    Ok(value) => array.push(value),
+                    Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
+                    Err(_) => break,
+                }
+                next_peek = parser.array_step();
+            }
+            Ok(JsonValueRc::Array(Rc::new(array)))
+        }
+        Peek::Object => {
+            let mut object = LazyIndexMap::new();
+            let mut next_key = parser.object_first::<StringDecoder>(tape).map(|k| k.map(Cow::from));
+            loop {
+                let key = match next_key {
+                    Ok(Some(key)) => {
+                        check_duplicate_key(parser, &object, &key)?;
+                        key
+                    }
+                    Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
+                    Ok(None) | Err(_) => break,
+                };
+                let value = parser.peek().and_then(|peek| {
+                    if recursion_limit == 0 {
+                        return Err(json_error!(RecursionLimitExceeded, parser.index));
+                    }
+                    take_value_rc(peek, parser, tape, recursion_limit - 1, allow_inf_nan, allow_partial)
+                });
+                match value {
+                    Ok(value) => object.insert(key, value),
+                    Err(e) if !(partial_active && e.allowed_if_partial()) => return Err(e),
+                    Err(_) => break,
+                }
+                next_key = parser.object_step::<StringDecoder>(tape).map(|k| k.map(Cow::from));
+            }
+            Ok(JsonValueRc::Object(Rc::new(object)))
+        }
+        _ if parser.at_nan_inf_as_null(peek.into_inner()) => parser
+            .consume_number::<NumberRange>(peek.into_inner(), true)
+            .map(|_| JsonValueRc::Null),
+        #[cfg(feature = "raw-number")]
+        _ if parser.raw_numbers => match parser.consume_raw_number(peek.into_inner(), allow_inf_nan) {
+            Ok(s) => Ok(JsonValueRc::RawNumber(Cow::Borrowed(s))),
+            Err(_) if !peek.is_num() => Err(json_error!(ExpectedSomeValue, parser.index)),
+            Err(e) => Err(e),
+        },
+        _ => match parser.consume_number::<NumberAny>(peek.into_inner(), allow_inf_nan) {
+            Ok(NumberAny::Int(NumberInt::Int(int))) => Ok(JsonValueRc::Int(int)),
+            #[cfg(feature = "num-bigint")]
+            Ok(NumberAny::Int(NumberInt::BigInt(big_int))) => Ok(JsonValueRc::BigInt(big_int)),
+            Ok(NumberAny::Float(float)) => Ok(JsonValueRc::Float(float)),
+            Err(e) => {
+                if !peek.is_num() {
+                    Err(json_error!(ExpectedSomeValue, parser.index))
+                } else {
+                    Err(e)
+                }
+            }
+        },
+    }
+}
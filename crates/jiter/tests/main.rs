@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::fs::File;
 use std::io::Read;
 use std::iter;
+use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -10,8 +11,10 @@ use num_bigint::BigInt;
 use smallvec::smallvec;
 
 use jiter::{
-    Jiter, JiterErrorType, JiterResult, JsonErrorType, JsonType, JsonValue, LazyIndexMap, LinePosition, NumberAny,
-    NumberInt, PartialMode, Peek,
+    parse_cst, parse_prefix, parse_prefix_with_options, CstKind, ErrorDetail, EscapeAction, FloatSource,
+    IncrementalParser, Jiter, JiterErrorType, JiterResult, JsonErrorType, JsonEvent, JsonType, JsonValue,
+    JsonValuePool, JsonValueRc, KeySet, LazyIndexMap, LinePosition, NumberAny, NumberCoercion, NumberInt, ParseOptions,
+    PartialMode, PathSegment, Peek, UnknownFields, ValueConversionError, ValueStats,
 };
 
 fn json_vec(jiter: &mut Jiter, peek: Option<Peek>) -> JiterResult<Vec<String>> {
@@ -978,6 +981,73 @@ fn test_recursion_limit_incr() {
     }
 }
 
+#[test]
+fn test_custom_recursion_limit() {
+    let deep = format!("{}{}", "[".repeat(250), "]".repeat(250));
+    let e = JsonValue::parse(deep.as_bytes(), false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+    JsonValue::parse_with_recursion_limit(deep.as_bytes(), false, 255).unwrap();
+    let options = ParseOptions::new().with_recursion_limit(255);
+    JsonValue::parse_owned_with_options(deep.as_bytes(), options).unwrap();
+    Jiter::new(deep.as_bytes())
+        .with_recursion_limit(255)
+        .next_skip()
+        .unwrap();
+
+    let json = br#"{"a": [{"b": 1}]}"#;
+    JsonValue::parse_with_recursion_limit(json, false, 3).unwrap();
+    let e = JsonValue::parse_with_recursion_limit(json, false, 2).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+    assert_eq!(e.index, 13);
+    let e = JsonValueRc::parse_with_options(json, ParseOptions::new().with_recursion_limit(2)).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+
+    // values and skipping through a jiter
+    Jiter::new(json).with_recursion_limit(3).next_value().unwrap();
+    Jiter::new(json).with_recursion_limit(3).next_skip().unwrap();
+    let e = Jiter::new(json).with_recursion_limit(2).next_value().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::RecursionLimitExceeded)
+    );
+    let e = Jiter::new(json).with_recursion_limit(2).next_skip().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::RecursionLimitExceeded)
+    );
+    let e = Jiter::new(json)
+        .with_options(ParseOptions::new().with_recursion_limit(2))
+        .next_value_owned()
+        .unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::RecursionLimitExceeded)
+    );
+}
+
+#[test]
+fn jiter_next_value_with_limit() {
+    let json = br#"{"trusted": [[[[1]]]], "payload": [[[[2]]]]}"#;
+    let mut jiter = Jiter::new(json);
+    assert_eq!(jiter.next_object().unwrap(), Some("trusted"));
+    jiter.next_value().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("payload"));
+    let e = jiter.next_value_with_limit(2).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::RecursionLimitExceeded)
+    );
+
+    let mut jiter = Jiter::new(json);
+    assert_eq!(jiter.next_object().unwrap(), Some("trusted"));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("payload"));
+    let value = jiter.next_value_with_limit(10).unwrap();
+    assert_eq!(value, JsonValue::parse(b"[[[[2]]]]", false).unwrap());
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+}
+
 #[test]
 fn test_recursion_limit_skip_array() {
     let json = (0..2000).map(|_| "[ ").collect::<String>();
@@ -1111,6 +1181,51 @@ fn lazy_index_map_big_get() {
     assert_eq!(map.get("other"), None);
 }
 
+#[test]
+fn lazy_index_map_get_all() {
+    let value = JsonValue::parse(br#"{"a": 1, "b": 2, "a": 3, "c": 4, "b": 5, "a": 6}"#, false).unwrap();
+    let JsonValue::Object(map) = value else {
+        panic!("expected an object");
+    };
+    assert_eq!(
+        map.get_all("a").collect::<Vec<_>>(),
+        [&JsonValue::Int(1), &JsonValue::Int(3), &JsonValue::Int(6)]
+    );
+    assert_eq!(
+        map.get_all("b").collect::<Vec<_>>(),
+        [&JsonValue::Int(2), &JsonValue::Int(5)]
+    );
+    assert_eq!(map.get_all("c").collect::<Vec<_>>(), [&JsonValue::Int(4)]);
+    assert_eq!(map.get_all("other").count(), 0);
+
+    // `get` returns the last entry for duplicate keys, whatever was looked up before
+    for key in ["c", "a", "b", "a", "c", "b"] {
+        let last = map.get_all(key).last();
+        assert_eq!(map.get(key), last);
+    }
+    assert_eq!(map.get("a"), Some(&JsonValue::Int(6)));
+
+    // including for duplicates inserted after a lookup
+    let mut map: LazyIndexMap<Cow<'_, str>, JsonValue<'_>> = LazyIndexMap::new();
+    map.insert("a".into(), JsonValue::Int(1));
+    map.insert("b".into(), JsonValue::Int(2));
+    assert_eq!(map.get("a"), Some(&JsonValue::Int(1)));
+    map.insert("a".into(), JsonValue::Int(3));
+    assert_eq!(map.get("b"), Some(&JsonValue::Int(2)));
+    assert_eq!(map.get("a"), Some(&JsonValue::Int(3)));
+
+    // same once the lookup map is used
+    let mut big: LazyIndexMap<Cow<'_, str>, JsonValue<'_>> = LazyIndexMap::new();
+    for i in 0..30 {
+        big.insert((i % 10).to_string().into(), JsonValue::Int(i));
+    }
+    assert_eq!(big.get("3"), Some(&JsonValue::Int(23)));
+    assert_eq!(
+        big.get_all("3").collect::<Vec<_>>(),
+        [&JsonValue::Int(3), &JsonValue::Int(13), &JsonValue::Int(23)]
+    );
+}
+
 #[test]
 fn lazy_index_map_clone() {
     let mut map: LazyIndexMap<Cow<'_, str>, JsonValue<'_>> = LazyIndexMap::default();
@@ -1317,6 +1432,40 @@ fn test_owned_value() {
     );
 }
 
+#[test]
+fn test_owned_value_accessors() {
+    let value = value_owned();
+    assert_eq!(value.get("int"), Some(&JsonValue::Int(1)));
+    assert_eq!(value.get("const"), Some(&JsonValue::Bool(true)));
+    assert_eq!(value["float"], JsonValue::Float(1.2));
+    assert_eq!(value.get("missing"), None);
+
+    let array = &value["array"];
+    assert_eq!(array.get_index(0), Some(&JsonValue::Int(1)));
+    assert_eq!(array[1], JsonValue::Bool(false));
+    assert_eq!(value["array"][2], JsonValue::Null);
+    assert_eq!(array.get_index(3), None);
+
+    // the wrong variant returns `None`
+    assert_eq!(value.get_index(0), None);
+    assert_eq!(array.get("int"), None);
+    assert_eq!(value["int"].get("x"), None);
+}
+
+#[test]
+#[should_panic(expected = "key \"missing\" not found in object")]
+fn test_index_missing_key() {
+    let value = value_owned();
+    let _ = &value["missing"];
+}
+
+#[test]
+#[should_panic(expected = "cannot index object with 0")]
+fn test_index_wrong_type() {
+    let value = value_owned();
+    let _ = &value[0];
+}
+
 fn value_into_static() -> JsonValue<'static> {
     let s = r#"{ "big_int": 92233720368547758070, "const": true, "float": 1.2, "array": [1, false, null, "x"]}"#
         .to_string();
@@ -1658,6 +1807,14 @@ fn jiter_partial_string() {
         e.error_type,
         JiterErrorType::JsonError(JsonErrorType::EofWhileParsingList)
     );
+
+    // values are still parsed strictly
+    let mut jiter = Jiter::new(br#"["foo"#).with_allow_partial_strings();
+    let e = jiter.next_value().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingString)
+    );
 }
 
 #[test]
@@ -1725,7 +1882,7 @@ fn test_value_partial_object() {
         _ => panic!("expected object"),
     };
     assert_eq!(obj.len(), 7);
-    let pairs = obj.iter().collect::<Vec<_>>();
+    let pairs = obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>();
     assert_eq!(pairs[0].clone(), (Cow::Borrowed("a"), JsonValue::Str("value".into())));
     assert_eq!(pairs[1].clone(), (Cow::Borrowed("b"), JsonValue::Bool(true)));
     assert_eq!(pairs[2].clone(), (Cow::Borrowed("c"), JsonValue::Bool(false)));
@@ -1769,3 +1926,3061 @@ fn test_partial_medium_response() {
         assert!(matches!(value, JsonValue::Object(_)));
     }
 }
+
+#[test]
+fn parse_options_value() {
+    let options = ParseOptions::new().with_allow_inf_nan();
+    let value = JsonValue::parse_with_options(b"[NaN, 1]", options).unwrap();
+    match value {
+        JsonValue::Array(array) => {
+            assert!(matches!(array[0], JsonValue::Float(f) if f.is_nan()));
+            assert_eq!(array[1], JsonValue::Int(1));
+        }
+        _ => panic!("expected array"),
+    }
+
+    let e = JsonValue::parse_with_options(b"[NaN, 1]", ParseOptions::new()).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+
+    let options = ParseOptions::new().with_partial_mode(PartialMode::TrailingStrings);
+    let value = JsonValue::parse_with_options(br#"["foo", "ba"#, options).unwrap();
+    assert_eq!(
+        value,
+        JsonValue::Array(Arc::new(smallvec![
+            JsonValue::Str("foo".into()),
+            JsonValue::Str("ba".into())
+        ]))
+    );
+
+    let value = JsonValue::parse_owned_with_options(b"Infinity", ParseOptions::new().with_allow_inf_nan()).unwrap();
+    assert_eq!(value, JsonValue::Float(f64::INFINITY));
+}
+
+#[test]
+fn parse_options_jiter() {
+    let options = ParseOptions::new()
+        .with_allow_inf_nan()
+        .with_partial_mode(PartialMode::TrailingStrings);
+    let mut jiter = Jiter::new(br#"[Infinity, "foo"#).with_options(options);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Infinity));
+    assert!(jiter.next_float().unwrap().is_infinite());
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::String));
+    assert_eq!(jiter.next_str().unwrap(), "foo");
+
+    let mut jiter = Jiter::new(b"Infinity").with_options(ParseOptions::new());
+    let e = jiter.next_float().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedSomeValue)
+    );
+}
+
+#[test]
+fn jiter_skip_to_depth() {
+    let mut jiter = Jiter::new(br#"{"a": [1, {"b": [2, 3]}, 4], "c": true}"#);
+    assert_eq!(jiter.current_depth(), 0);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.current_depth(), 1);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Object));
+    assert_eq!(jiter.next_object().unwrap(), Some("b"));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'2')));
+    assert_eq!(jiter.current_depth(), 4);
+
+    // skip the pending value `2` and everything after it, back into the outer object
+    jiter.skip_to_depth(1).unwrap();
+    assert_eq!(jiter.current_depth(), 1);
+    assert_eq!(jiter.next_key().unwrap(), Some("c"));
+    assert!(jiter.next_bool().unwrap());
+
+    // already at the target depth, nothing to do
+    jiter.skip_to_depth(1).unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+    assert_eq!(jiter.current_depth(), 0);
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn jiter_skip_to_depth_top_level() {
+    let mut jiter = Jiter::new(br#"[[1, 2], {"x": null}] "#);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    jiter.skip_to_depth(0).unwrap();
+    assert_eq!(jiter.current_depth(), 0);
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn jiter_float_with_source() {
+    let mut jiter = Jiter::new(b"[1.5, 1e999, -1e999, Infinity, -Infinity, NaN]").with_allow_inf_nan();
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    assert_eq!(jiter.next_float_with_source().unwrap(), (1.5, FloatSource::Finite));
+    let mut sources = vec![];
+    while jiter.array_step().unwrap().is_some() {
+        let (float, source) = jiter.next_float_with_source().unwrap();
+        assert!(!float.is_finite());
+        sources.push(source);
+    }
+    assert_eq!(
+        sources,
+        vec![
+            FloatSource::Overflow,
+            FloatSource::Overflow,
+            FloatSource::Literal,
+            FloatSource::Literal,
+            FloatSource::Literal
+        ]
+    );
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn jiter_expect_key() {
+    let mut jiter = Jiter::new(br#"{"a": 1, "b": 2, "c": 3}"#);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+    jiter.expect_key("b").unwrap();
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(2));
+    let e = jiter.expect_key("x").unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::UnexpectedKey {
+            expected: "x".to_string(),
+            found: Some("c".to_string())
+        }
+    );
+    assert_eq!(e.to_string(), r#"expected key "x" but found "c" at index 15"#);
+
+    let mut jiter = Jiter::new(br#"{"a": 1}"#);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_skip().unwrap();
+    let e = jiter.expect_key("b").unwrap_err();
+    assert_eq!(e.to_string(), r#"expected key "b" but found end of object at index 7"#);
+}
+
+#[test]
+fn json_macro() {
+    let key = "dynamic".to_string();
+    let value = jiter::json!({
+        "a": [1, -2, 3.5, {"b": true}],
+        "c": null,
+        "d": [],
+        "e": {},
+        (key): "string".to_string(),
+        "f": [false, [null], {"g": "h"},],
+    });
+    let expected = JsonValue::parse(
+        br#"{
+            "a": [1, -2, 3.5, {"b": true}],
+            "c": null,
+            "d": [],
+            "e": {},
+            "dynamic": "string",
+            "f": [false, [null], {"g": "h"}]
+        }"#,
+        false,
+    )
+    .unwrap();
+    assert_eq!(value, expected);
+
+    let value: JsonValue<'static> = jiter::json!("foo");
+    assert_eq!(value, JsonValue::Str("foo".into()));
+    assert_eq!(jiter::json!(null), JsonValue::Null);
+    assert_eq!(jiter::json!(1 + 2), JsonValue::Int(3));
+}
+
+#[test]
+fn parse_prefix_framed() {
+    let data = br#"{"a": 1}  [2]"#;
+    let (value, consumed) = parse_prefix(data, false).unwrap();
+    assert_eq!(value, jiter::json!({"a": 1}));
+    assert_eq!(consumed, 8);
+
+    let (value, consumed) = parse_prefix(data, true).unwrap();
+    assert_eq!(value, jiter::json!({"a": 1}));
+    assert_eq!(consumed, 10);
+
+    let (value, consumed) = parse_prefix(&data[consumed..], true).unwrap();
+    assert_eq!(value, jiter::json!([2]));
+    assert_eq!(consumed, 3);
+
+    let e = parse_prefix(b"  ", true).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingValue);
+
+    // with options, trailing comments count as whitespace
+    let options = ParseOptions::new().with_allow_inf_nan().with_allow_comments();
+    let data = b"[NaN] /* c */ [1]";
+    let (value, consumed) = parse_prefix_with_options(data, true, options).unwrap();
+    assert!(matches!(value, JsonValue::Array(array) if matches!(array[0], JsonValue::Float(f) if f.is_nan())));
+    assert_eq!(consumed, 14);
+    let (value, consumed) = parse_prefix_with_options(&data[consumed..], true, options).unwrap();
+    assert_eq!(value, jiter::json!([1]));
+    assert_eq!(consumed, 3);
+    let e = parse_prefix(data, true).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+}
+
+#[test]
+fn jiter_remaining_count() {
+    let mut jiter = Jiter::new(br#"[1, [2, 3], {"a": 4, "b": [5]}, 6]"#);
+    assert_eq!(jiter.remaining_array_count().unwrap(), None);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    assert_eq!(jiter.remaining_array_count().unwrap(), Some(4));
+    assert_eq!(jiter.remaining_object_count().unwrap(), None);
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.remaining_array_count().unwrap(), Some(3));
+    jiter.array_step().unwrap();
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Object));
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.remaining_object_count().unwrap(), Some(2));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.remaining_object_count().unwrap(), Some(1));
+    // the parser position is unchanged
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    jiter.skip_to_depth(1).unwrap();
+    assert_eq!(jiter.remaining_array_count().unwrap(), Some(1));
+    jiter.skip_to_depth(0).unwrap();
+    jiter.finish().unwrap();
+
+    let jiter = Jiter::new(b"[]");
+    assert_eq!(jiter.remaining_array_count().unwrap(), None);
+}
+
+#[test]
+fn partial_string_truncated_surrogate_pair() {
+    let json_bytes = br#"["a\uD83D\uDE00"#;
+    let escape_start = 4;
+    for i in escape_start..json_bytes.len() {
+        let partial_json = &json_bytes[..i];
+        let value = JsonValue::parse_with_config(partial_json, false, PartialMode::TrailingStrings).unwrap();
+        assert_eq!(value, jiter::json!(["a"]), "truncated at {i}");
+
+        let mut jiter = Jiter::new(partial_json).with_allow_partial_strings();
+        assert_eq!(jiter.next_array().unwrap(), Some(Peek::String));
+        assert_eq!(jiter.next_bytes().unwrap(), b"a", "truncated at {i}");
+    }
+    let value = JsonValue::parse_with_config(json_bytes, false, PartialMode::TrailingStrings).unwrap();
+    assert_eq!(value, jiter::json!(["a😀"]));
+
+    // without partial strings, the incomplete string is dropped
+    let value = JsonValue::parse_with_config(&json_bytes[..9], false, PartialMode::On).unwrap();
+    assert_eq!(value, jiter::json!([]));
+}
+
+#[test]
+fn value_deep_clone() {
+    let json = br#"{"a": [1, {"b": "c"}], "d": []}"#.to_vec();
+    let value = JsonValue::parse(&json, false).unwrap();
+    let copy = value.deep_clone();
+    drop(value);
+    drop(json);
+    assert_eq!(copy, jiter::json!({"a": [1, {"b": "c"}], "d": []}));
+
+    // cloning a value shares its containers, a deep clone never does
+    let shared = copy.clone();
+    let JsonValue::Object(object) = copy.deep_clone() else {
+        panic!("expected object")
+    };
+    assert_eq!(Arc::strong_count(&object), 1);
+    let Some(JsonValue::Array(array)) = object.get("a") else {
+        panic!("expected array")
+    };
+    assert_eq!(Arc::strong_count(array), 1);
+    drop(shared);
+}
+
+#[test]
+fn jiter_trace() {
+    let mut trace = Vec::new();
+    let mut jiter = Jiter::new(br#"{"a": [1, true], "b": {}, "c": "x"}"#).with_trace(&mut trace);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    jiter.next_int().unwrap();
+    jiter.array_step().unwrap();
+    jiter.next_bool().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), None);
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    assert_eq!(jiter.next_object().unwrap(), None);
+    assert_eq!(jiter.next_key_bytes().unwrap(), Some(b"c".as_ref()));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+    drop(jiter);
+
+    let trace = String::from_utf8(trace).unwrap();
+    assert_eq!(
+        trace.lines().collect::<Vec<_>>(),
+        [
+            "{ @ 0",
+            "key(a) @ 5",
+            "[ @ 6",
+            "int @ 7",
+            "bool @ 10",
+            "] @ 14",
+            "key(b) @ 21",
+            "{ @ 22",
+            "} @ 23",
+            "key(c) @ 30",
+            "skip @ 31",
+            "} @ 34",
+        ]
+    );
+}
+
+#[test]
+fn value_partial_eq_scalars() {
+    let value = jiter::json!(["foo", 42, true, 1.5, null]);
+    let JsonValue::Array(array) = value else {
+        panic!("expected array")
+    };
+    assert!(array[0] == "foo");
+    assert!(array[0] == *"foo");
+    let owned = "foo".to_string();
+    assert!(array[0] == owned);
+    assert!(array[0] != "bar");
+    assert!(array[1] == 42);
+    assert!(array[1] != 42.0);
+    assert!(array[2] == true);
+    assert!(array[2] != 1);
+    assert!(array[3] == 1.5);
+    assert!(array[4] != false);
+    assert!(array[4] != "null");
+}
+
+#[test]
+fn number_module() {
+    use jiter::number::{consume_infinity, consume_nan, AbstractNumberDecoder, NumberFloat, NumberRange};
+
+    assert_eq!(consume_infinity(b"-Infinity,", 1).unwrap(), 9);
+    assert_eq!(consume_nan(b"NaN", 0).unwrap(), 3);
+    let e = consume_nan(b"Nope", 0).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeIdent);
+
+    let (float, end) = NumberFloat::decode(b"[1e2]", 1, b'1', false).unwrap();
+    assert_eq!(end, 4);
+    assert!((float - 100.0).abs() < f64::EPSILON);
+
+    let (range, end) = NumberRange::decode(b"Infinity", 0, b'I', true).unwrap();
+    assert_eq!((range.range, range.is_int, end), (0..8, false, 8));
+}
+
+#[test]
+fn jiter_read_arrays_into() {
+    let mut buffer = [0.0; 4];
+    let mut jiter = Jiter::new(b"[1, 2.5, -3e2]");
+    assert_eq!(jiter.read_f64_array_into(&mut buffer).unwrap(), 3);
+    assert_eq!(
+        buffer[..3].iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["1", "2.5", "-300"]
+    );
+    jiter.finish().unwrap();
+
+    let mut buffer = [0.0_f32; 2];
+    let mut jiter = Jiter::new(b"[]");
+    assert_eq!(jiter.read_f32_array_into(&mut buffer).unwrap(), 0);
+
+    let mut buffer = [0; 3];
+    let mut jiter = Jiter::new(b"[[1, 2, 3], [4, 5, 6, 7]]");
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.read_i64_array_into(&mut buffer).unwrap(), 3);
+    assert_eq!(buffer, [1, 2, 3]);
+    jiter.array_step().unwrap();
+    let e = jiter.read_i64_array_into(&mut buffer).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::ArrayTooLong { capacity: 3 });
+    assert_eq!(
+        e.description(&jiter),
+        "array has more than 3 elements at line 1 column 23"
+    );
+
+    let mut jiter = Jiter::new(b"[1, 2.5]");
+    let e = jiter.read_i64_array_into(&mut buffer).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Int,
+            actual: JsonType::Float
+        }
+    );
+}
+
+#[test]
+fn value_stats() {
+    let value = jiter::json!({"a": [1, 2.5, {"b": null}], "c": "d", "e": [[], true]});
+    assert_eq!(
+        value.stats(),
+        ValueStats {
+            nodes: 10,
+            max_depth: 3,
+            nulls: 1,
+            bools: 1,
+            ints: 1,
+            floats: 1,
+            strings: 1,
+            arrays: 3,
+            objects: 2,
+        }
+    );
+    assert_eq!(jiter::json!(1).stats().max_depth, 0);
+}
+
+#[test]
+fn jiter_first_peek() {
+    for empty in [b"".as_ref(), b" \n\t ", b"\xEF\xBB\xBF", b"\xEF\xBB\xBF  "] {
+        let mut jiter = Jiter::new(empty);
+        assert_eq!(jiter.first_peek().unwrap(), None);
+        jiter.finish().unwrap();
+    }
+
+    let mut jiter = Jiter::new(b"\xEF\xBB\xBF [1]");
+    assert_eq!(jiter.first_peek().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+
+    // the parser isn't at the start of the data, so the "BOM" is not skipped
+    let mut jiter = Jiter::new(b"1 \xEF\xBB\xBF");
+    jiter.next_int().unwrap();
+    jiter.skip_bom_and_whitespace();
+    assert_eq!(jiter.current_index(), 2);
+}
+
+#[test]
+fn allow_raw_control_chars() {
+    let json = b"{\"tab\tkey\": \"line\none\", \"long\": \"0123456789abcdef0123456789\x01abcdef\"}";
+    let e = JsonValue::parse(json, false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ControlCharacterWhileParsingString);
+
+    let options = ParseOptions::new().with_allow_raw_control_chars();
+    let value = JsonValue::parse_with_options(json, options).unwrap();
+    assert_eq!(
+        value,
+        jiter::json!({"tab\tkey": "line\none", "long": "0123456789abcdef0123456789\x01abcdef"})
+    );
+
+    let mut jiter = Jiter::new(json).with_allow_raw_control_chars();
+    assert_eq!(jiter.next_object().unwrap(), Some("tab\tkey"));
+    assert_eq!(jiter.next_bytes().unwrap(), b"line\none");
+    assert_eq!(jiter.next_key_bytes().unwrap(), Some(b"long".as_ref()));
+    assert_eq!(jiter.next_str().unwrap(), "0123456789abcdef0123456789\x01abcdef");
+
+    let mut jiter = Jiter::new(json);
+    let e = jiter.next_object().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ControlCharacterWhileParsingString)
+    );
+}
+
+#[test]
+fn value_try_into() {
+    let string: String = jiter::json!("foo").try_into().unwrap();
+    assert_eq!(string, "foo");
+    let int: i64 = jiter::json!(42).try_into().unwrap();
+    assert_eq!(int, 42);
+    let float: f64 = jiter::json!(42).try_into().unwrap();
+    assert!((float - 42.0).abs() < f64::EPSILON);
+    let boolean: bool = jiter::json!(true).try_into().unwrap();
+    assert!(boolean);
+    let array: Vec<JsonValue> = jiter::json!([1, "two"]).try_into().unwrap();
+    assert_eq!(array, vec![JsonValue::Int(1), JsonValue::Str("two".into())]);
+
+    let error = i64::try_from(jiter::json!(1.5)).unwrap_err();
+    assert_eq!(
+        error,
+        ValueConversionError::WrongType {
+            expected: JsonType::Int,
+            actual: JsonType::Float
+        }
+    );
+    assert_eq!(error.to_string(), "expected int but found float");
+    let error = String::try_from(jiter::json!({})).unwrap_err();
+    assert_eq!(error.to_string(), "expected string but found object");
+    assert!(bool::try_from(JsonValue::Null).is_err());
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn value_try_into_big_int() {
+    let value = JsonValue::parse(b"92233720368547758070", false).unwrap();
+    assert_eq!(
+        i64::try_from(value.clone()).unwrap_err(),
+        ValueConversionError::NumberOutOfRange
+    );
+    let f: f64 = value.try_into().unwrap();
+    assert!((f - 9.223_372_036_854_776e19).abs() < 1e5);
+}
+
+#[test]
+fn jiter_peek_object_tag() {
+    let json = br#"[{"radius": 1.5, "type": "circle"}, {"type": "square"}, {"side": 2}, {}]"#;
+    let mut jiter = Jiter::new(json);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Object));
+    assert_eq!(jiter.peek_object_tag("type").unwrap(), Some("circle".to_string()));
+    // the object can still be read from the start
+    assert_eq!(jiter.next_object().unwrap(), Some("radius"));
+    assert!((jiter.next_float().unwrap() - 1.5).abs() < f64::EPSILON);
+    jiter.skip_to_depth(1).unwrap();
+
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_object_tag("type").unwrap(), Some("square".to_string()));
+    jiter.next_skip().unwrap();
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_object_tag("type").unwrap(), None);
+    assert_eq!(
+        jiter.peek_object_tag("side").unwrap_err().error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+    jiter.next_skip().unwrap();
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_object_tag("type").unwrap(), None);
+}
+
+#[test]
+fn extended_whitespace() {
+    let json = "\u{feff}{\u{c}\"a\"\u{b}:\u{a0}[1,\u{2028}2\u{3000}]\u{2029}}\u{205f}".as_bytes();
+    let e = JsonValue::parse(json, false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+
+    let options = ParseOptions::new().with_extended_whitespace();
+    let value = JsonValue::parse_with_options(json, options).unwrap();
+    assert_eq!(value, jiter::json!({"a": [1, 2]}));
+
+    let mut jiter = Jiter::new(json).with_extended_whitespace();
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::new(b'2')));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), None);
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    // whitespace within strings is unchanged, other non-ASCII characters are still rejected between tokens
+    let value = JsonValue::parse_with_options("\"\u{a0}\"".as_bytes(), options).unwrap();
+    assert_eq!(value, JsonValue::Str("\u{a0}".into()));
+    let e = JsonValue::parse_with_options("[1,\u{2030}2]".as_bytes(), options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+}
+
+#[test]
+fn jiter_next_scalar_str() {
+    let mut jiter = Jiter::new(br#"["42", 42, -1.5e3, "caf\u00e9", null]"#);
+    jiter.next_array().unwrap();
+    let id = jiter.next_scalar_str().unwrap();
+    assert_eq!(id, "42");
+    assert!(matches!(id, Cow::Borrowed(_)));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_scalar_str().unwrap(), "42");
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_scalar_str().unwrap(), "-1.5e3");
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_scalar_str().unwrap(), "café");
+    jiter.array_step().unwrap();
+    let e = jiter.next_scalar_str().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Null
+        }
+    );
+}
+
+#[test]
+fn lazy_index_map_iter_order() {
+    let mut map: LazyIndexMap<Cow<'_, str>, JsonValue<'_>> = LazyIndexMap::new();
+    for i in 0..20 {
+        map.insert(format!("k{}", 19 - i).into(), JsonValue::Int(i));
+    }
+    let check = |map: &LazyIndexMap<Cow<'_, str>, JsonValue<'_>>| {
+        let values = map.values().collect::<Vec<_>>();
+        assert_eq!(values.len(), 20);
+        assert_eq!(values[0], &JsonValue::Int(0));
+        assert_eq!(values[19], &JsonValue::Int(19));
+        let (k, v) = map.iter().next().unwrap();
+        assert_eq!(k, "k19");
+        assert_eq!(v, &JsonValue::Int(0));
+        assert_eq!(map.iter().next_back().unwrap().0, "k0");
+    };
+    // before the lookup map is built
+    check(&map);
+    // a lookup with more than 16 entries builds the map
+    assert_eq!(map.get("k0"), Some(&JsonValue::Int(19)));
+    check(&map);
+
+    for value in map.values_mut() {
+        if let JsonValue::Int(i) = value {
+            *i *= 10;
+        }
+    }
+    assert_eq!(map.get("k0"), Some(&JsonValue::Int(190)));
+    assert_eq!(map.values().nth(1), Some(&JsonValue::Int(10)));
+}
+
+#[test]
+fn jiter_next_raw_str_bytes() {
+    let mut jiter = Jiter::new(br#"["plain", "a\"b\u00e9", 1]"#);
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.next_raw_str_bytes().unwrap(), (b"plain".as_slice(), false));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_raw_str_bytes().unwrap(), (br#"a\"b\u00e9"#.as_slice(), true));
+    jiter.array_step().unwrap();
+    let e = jiter.next_raw_str_bytes().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+
+    let mut jiter = Jiter::new(br#""bad \x escape""#);
+    let e = jiter.next_raw_str_bytes().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::InvalidEscape));
+}
+
+#[test]
+fn value_parse_with_length() {
+    let data = br#"  {"a": [1, Infinity]} {"b": 2}"#;
+    let (value, length) = JsonValue::parse_with_length(data, true).unwrap();
+    assert_eq!(length, 22);
+    assert_eq!(value, jiter::json!({"a": [1, f64::INFINITY]}));
+    let (value, length) = JsonValue::parse_with_length(&data[length..], false).unwrap();
+    assert_eq!(length, 9);
+    assert_eq!(value, jiter::json!({"b": 2}));
+
+    let e = JsonValue::parse_with_length(b"[1, NaN]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+    // `parse` still rejects trailing data
+    assert!(JsonValue::parse(data, true).is_err());
+
+    // options apply as they do to `parse_with_options`
+    let options = ParseOptions::new().with_allow_comments();
+    let (value, length) = JsonValue::parse_with_options_and_length(b"/* a */ [1] /* b */", options).unwrap();
+    assert_eq!(value, jiter::json!([1]));
+    assert_eq!(length, 11);
+    let options = ParseOptions::new().with_recursion_limit(1);
+    let e = JsonValue::parse_with_options_and_length(b"[[1]] x", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+    let options = ParseOptions::new().with_catch_duplicate_keys();
+    let e = JsonValue::parse_with_options_and_length(br#"{"a": 1, "a": 2} x"#, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("a".to_string()));
+}
+
+#[test]
+fn jiter_with_number_parser() {
+    use jiter::number::{AbstractNumberDecoder, NumberFloat};
+    use jiter::JsonError;
+
+    /// rejects subnormal floats
+    struct NoSubnormals;
+
+    impl AbstractNumberDecoder for NoSubnormals {
+        type Output = f64;
+
+        fn decode(data: &[u8], index: usize, first: u8, allow_inf_nan: bool) -> Result<(f64, usize), JsonError> {
+            let (float, end) = NumberFloat::decode(data, index, first, allow_inf_nan)?;
+            if float.is_subnormal() {
+                Err(JsonError {
+                    error_type: JsonErrorType::NumberOutOfRange,
+                    index,
+                })
+            } else {
+                Ok((float, end))
+            }
+        }
+    }
+
+    let data = b"[1.5, 1e-310]";
+    let mut jiter = Jiter::new(data).with_number_parser::<NoSubnormals>();
+    jiter.next_array().unwrap();
+    assert!((jiter.next_float().unwrap() - 1.5).abs() < f64::EPSILON);
+    jiter.array_step().unwrap();
+    let e = jiter.next_float().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::NumberOutOfRange));
+    assert_eq!(e.index, 6);
+
+    // the default decoder accepts subnormals
+    let mut jiter = Jiter::new(data);
+    jiter.next_array().unwrap();
+    jiter.next_float().unwrap();
+    jiter.array_step().unwrap();
+    assert!(jiter.next_float().unwrap().is_subnormal());
+}
+
+#[test]
+fn jiter_finish_and_remaining() {
+    let data = br#"{"a": 1}  [2] trailing"#;
+    let mut jiter = Jiter::new(data);
+    jiter.next_skip().unwrap();
+    let remaining = jiter.finish_and_remaining();
+    assert_eq!(remaining, b"[2] trailing");
+    assert_eq!(jiter.current_index(), 10);
+
+    let mut jiter = Jiter::new(remaining);
+    assert_eq!(jiter.next_value().unwrap(), jiter::json!([2]));
+    assert_eq!(jiter.finish_and_remaining(), b"trailing");
+
+    let mut jiter = Jiter::new(b"true \n");
+    assert!(jiter.next_bool().unwrap());
+    assert_eq!(jiter.finish_and_remaining(), b"");
+    jiter.finish().unwrap();
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn value_coerce_numbers() {
+    let original = JsonValue::parse(
+        br#"[1, 2.0, 2.5, {"a": -3e2, "b": [1e300]}, "4", 9223372036854775808]"#,
+        false,
+    )
+    .unwrap();
+
+    let mut value = original.clone();
+    value.coerce_numbers(NumberCoercion::IntsWherePossible);
+    let big_float = jiter::json!(9_223_372_036_854_775_808_f64);
+    let JsonValue::Array(array) = &value else {
+        panic!("expected array")
+    };
+    assert_eq!(
+        array[..3],
+        [JsonValue::Int(1), JsonValue::Int(2), JsonValue::Float(2.5)]
+    );
+    assert_eq!(array[3], jiter::json!({"a": -300, "b": [1e300]}));
+    assert_eq!(array[4], JsonValue::Str("4".into()));
+    // doesn't fit in an i64, so is unchanged
+    assert!(matches!(array[5], JsonValue::BigInt(_)));
+    // the original wasn't modified
+    assert_eq!(original.stats().floats, 4);
+
+    let mut value = original;
+    value.coerce_numbers(NumberCoercion::AllFloat);
+    assert_eq!(
+        value,
+        jiter::json!([1.0, 2.0, 2.5, {"a": -300.0, "b": [1e300]}, "4", big_float])
+    );
+    assert_eq!(value.stats().ints, 0);
+
+    let mut value = JsonValue::BigInt(42.into());
+    value.coerce_numbers(NumberCoercion::IntsWherePossible);
+    assert_eq!(value, JsonValue::Int(42));
+}
+
+#[cfg(feature = "base64")]
+#[test]
+fn jiter_next_base64() {
+    use jiter::Base64Alphabet;
+
+    let mut jiter = Jiter::new(br#"["aGk/Pz4+", "aGk_Pz4-", "aGk", "aGk\/", "not base64!", 1]"#);
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.next_base64(Base64Alphabet::Standard).unwrap(), b"hi??>>");
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_base64(Base64Alphabet::UrlSafe).unwrap(), b"hi??>>");
+    jiter.array_step().unwrap();
+    // padding is optional
+    assert_eq!(jiter.next_base64(Base64Alphabet::Standard).unwrap(), b"hi");
+    jiter.array_step().unwrap();
+    // escapes are decoded first
+    assert_eq!(jiter.next_base64(Base64Alphabet::Standard).unwrap(), b"hi?");
+    jiter.array_step().unwrap();
+    let e = jiter.next_base64(Base64Alphabet::Standard).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::InvalidBase64("Invalid symbol 32, offset 3.".to_string())
+    );
+    assert_eq!(e.index, 41);
+    assert_eq!(
+        e.description(&jiter),
+        "invalid base64: Invalid symbol 32, offset 3. at line 1 column 42"
+    );
+    jiter.array_step().unwrap();
+    let e = jiter.next_base64(Base64Alphabet::Standard).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+}
+
+#[test]
+fn jiter_peek_string_byte_len() {
+    let mut jiter = Jiter::new(br#"[ "hello", "a\nb\u00e9", 1]"#);
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.peek_string_byte_len().unwrap(), 5);
+    // the parser hasn't moved
+    assert_eq!(jiter.next_str().unwrap(), "hello");
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_string_byte_len().unwrap(), 10);
+    let s = jiter.next_str().unwrap();
+    assert_eq!(s, "a\nb\u{e9}");
+    assert_eq!(s.len(), 5);
+    jiter.array_step().unwrap();
+    let e = jiter.peek_string_byte_len().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+
+    let jiter = Jiter::new(br#""unterminated"#);
+    let e = jiter.peek_string_byte_len().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingString)
+    );
+}
+
+#[test]
+fn value_rc_parse() {
+    let data = br#"{"a": [1, 2.5, "x", null, true], "b": {"c": {}}, "d": []}"#;
+    let value = JsonValueRc::parse(data, false).unwrap();
+    assert_eq!(value, JsonValueRc::from(&JsonValue::parse(data, false).unwrap()));
+    assert_eq!(value.json_type(), JsonType::Object);
+    let JsonValueRc::Object(object) = &value else {
+        panic!("expected object")
+    };
+    let keys = object.keys().collect::<Vec<_>>();
+    assert_eq!(keys, ["a", "b", "d"]);
+    let JsonValueRc::Array(array) = object.get("a").unwrap() else {
+        panic!("expected array")
+    };
+    assert_eq!(
+        array.as_slice(),
+        [
+            JsonValueRc::Int(1),
+            JsonValueRc::Float(2.5),
+            JsonValueRc::Str("x".into()),
+            JsonValueRc::Null,
+            JsonValueRc::Bool(true)
+        ]
+    );
+    // cloning shares the containers
+    let clone = value.clone();
+    let JsonValueRc::Object(clone_object) = &clone else {
+        panic!("expected object")
+    };
+    assert!(Rc::ptr_eq(object, clone_object));
+
+    let e = JsonValueRc::parse(b"[1, 2] x", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::TrailingCharacters);
+    let e = JsonValueRc::parse(b"[1, x]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+    assert_eq!(e.index, 4);
+}
+
+#[test]
+fn value_rc_partial_and_recursion_limit() {
+    for data in [&br#"[1, {"a": [2, "x"#[..], br"[", br#"{"a": 1, "b"#] {
+        let options = ParseOptions::new().with_partial_mode(PartialMode::On);
+        let value = JsonValueRc::parse_with_options(data, options).unwrap();
+        let expected = JsonValue::parse_with_options(data, options).unwrap();
+        assert_eq!(value, JsonValueRc::from(&expected));
+    }
+
+    // the recursion limit matches `JsonValue`, where empty arrays and objects don't count
+    for (depth, inner) in [(200, "1"), (201, ""), (201, "1"), (200, "{\"a\": 1}"), (200, "{}")] {
+        let data = format!("{}{inner}{}", "[".repeat(depth), "]".repeat(depth));
+        let result = JsonValueRc::parse(data.as_bytes(), false);
+        match JsonValue::parse(data.as_bytes(), false) {
+            Ok(value) => assert_eq!(result.unwrap(), JsonValueRc::from(&value)),
+            Err(e) => assert_eq!(result.unwrap_err(), e),
+        };
+    }
+    let e = JsonValueRc::parse(format!("{}1{}", "[".repeat(201), "]".repeat(201)).as_bytes(), false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+}
+
+#[test]
+fn jiter_object_contains_key() {
+    let data = br#"{"data": {"error": 1}, "error": {"code": 42}}"#;
+    let mut jiter = Jiter::new(data);
+    assert!(jiter.object_contains_key("error").unwrap());
+    assert!(!jiter.object_contains_key("code").unwrap());
+    // the parser hasn't moved
+    assert_eq!(jiter.next_object().unwrap(), Some("data"));
+    assert!(jiter.object_contains_key("error").unwrap());
+    assert!(!jiter.object_contains_key("data").unwrap());
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("error"));
+    assert!(jiter.object_contains_key("code").unwrap());
+
+    let e = Jiter::new(b"[1]").object_contains_key("a").unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Object,
+            actual: JsonType::Array
+        }
+    );
+    assert!(!Jiter::new(b"{}").object_contains_key("a").unwrap());
+}
+
+#[test]
+fn jiter_events() {
+    let data = br#"{"a": [1, 2.5, "x\ny", null, []], "b\u00e9": {}, "c": true} tail"#;
+    let mut jiter = Jiter::new(data);
+    let events = jiter.events().collect::<JiterResult<Vec<_>>>().unwrap();
+    assert_eq!(
+        events,
+        [
+            JsonEvent::StartObject,
+            JsonEvent::Key("a".into()),
+            JsonEvent::StartArray,
+            JsonEvent::Int(1),
+            JsonEvent::Float(2.5),
+            JsonEvent::Str("x\ny".into()),
+            JsonEvent::Null,
+            JsonEvent::StartArray,
+            JsonEvent::EndArray,
+            JsonEvent::EndArray,
+            JsonEvent::Key("b\u{e9}".into()),
+            JsonEvent::StartObject,
+            JsonEvent::EndObject,
+            JsonEvent::Key("c".into()),
+            JsonEvent::Bool(true),
+            JsonEvent::EndObject,
+        ]
+    );
+    // keys and strings without escapes borrow from the data
+    assert!(matches!(&events[1], JsonEvent::Key(Cow::Borrowed("a"))));
+    // the iterator stops after the value
+    assert_eq!(jiter.finish_and_remaining(), b"tail");
+
+    // works with iterator combinators
+    let mut jiter = Jiter::new(br#"[{"id": 1}, {"id": 2}, {"name": "x"}]"#);
+    let keys = jiter
+        .events()
+        .filter_map(|event| match event {
+            Ok(JsonEvent::Key(key)) => Some(key),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(keys, ["id", "id", "name"]);
+
+    // scalars at the top level
+    let mut jiter = Jiter::new(b" 123 ");
+    assert_eq!(jiter.events().collect::<Vec<_>>(), [Ok(JsonEvent::Int(123))]);
+    jiter.finish().unwrap();
+
+    // the iterator ends after an error
+    let mut jiter = Jiter::new(b"[1, x, 2]");
+    let events = jiter.events().collect::<Vec<_>>();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1], Ok(JsonEvent::Int(1)));
+    let e = events[2].as_ref().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedSomeValue)
+    );
+    assert_eq!(e.index, 4);
+}
+
+#[test]
+fn max_array_elements() {
+    let options = ParseOptions::new().with_max_array_elements(3);
+    for data in [
+        &b"[1, 2, 3]"[..],
+        b"[]",
+        b"[[1, 2, 3], [[4, 5, 6]], 7]",
+        br#"{"a": [1, 2, 3], "b": 1, "c": 2, "d": 3}"#,
+    ] {
+        JsonValue::parse_with_options(data, options).unwrap();
+        JsonValueRc::parse_with_options(data, options).unwrap();
+        Jiter::new(data).with_max_array_elements(3).next_skip().unwrap();
+    }
+    for (data, index) in [
+        (&b"[1, 2, 3, 4]"[..], 10),
+        (b"[[1, 2, 3, 4]]", 11),
+        (b"[[1], [2], [3], [4]]", 16),
+    ] {
+        let e = JsonValue::parse_with_options(data, options).unwrap_err();
+        assert_eq!(e.error_type, JsonErrorType::TooManyElements);
+        assert_eq!(e.index, index);
+        let e = JsonValueRc::parse_with_options(data, options).unwrap_err();
+        assert_eq!(e.index, index);
+        let e = Jiter::new(data).with_max_array_elements(3).next_skip().unwrap_err();
+        assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TooManyElements));
+        assert_eq!(e.index, index);
+    }
+
+    let e = JsonValue::parse_with_options(b"[1]", ParseOptions::new().with_max_array_elements(0)).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::TooManyElements);
+    assert_eq!(e.to_string(), "too many elements in array or object at index 1");
+
+    // stepping through an array with `Jiter`
+    let mut jiter = Jiter::new(b"[1, 2, 3]").with_max_array_elements(2);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    jiter.next_int().unwrap();
+    jiter.array_step().unwrap().unwrap();
+    jiter.next_int().unwrap();
+    let e = jiter.array_step().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TooManyElements));
+    assert_eq!(e.index, 7);
+}
+
+#[test]
+fn max_object_entries() {
+    let options = ParseOptions::new().with_max_object_entries(2);
+    for data in [
+        &br#"{"a": 1, "b": 2}"#[..],
+        b"{}",
+        br#"{"a": {"b": 1, "c": 2}, "d": [1, 2, 3, 4]}"#,
+    ] {
+        JsonValue::parse_with_options(data, options).unwrap();
+        Jiter::new(data).with_max_object_entries(2).next_skip().unwrap();
+    }
+    // duplicate keys are counted
+    for (data, index) in [
+        (&br#"{"a": 1, "b": 2, "c": 3}"#[..], 17),
+        (br#"{"a": 1, "a": 2, "a": 3}"#, 17),
+        (br#"[{"a": {"b": 1, "c": 2, "d": 3}}]"#, 24),
+    ] {
+        let e = JsonValue::parse_with_options(data, options).unwrap_err();
+        assert_eq!(e.error_type, JsonErrorType::TooManyElements);
+        assert_eq!(e.index, index);
+        let e = Jiter::new(data).with_max_object_entries(2).next_skip().unwrap_err();
+        assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TooManyElements));
+        assert_eq!(e.index, index);
+    }
+
+    let mut jiter = Jiter::new(br#"{"a": 1, "b": 2, "c": 3}"#).with_max_object_entries(2);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_int().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    jiter.next_int().unwrap();
+    let e = jiter.next_key().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TooManyElements));
+
+    // without limits, nothing changes
+    let data = format!("[{}]", vec!["1"; 1000].join(","));
+    JsonValue::parse(data.as_bytes(), false).unwrap();
+}
+
+#[test]
+fn value_merge_patch() {
+    // examples from RFC 7386 appendix A
+    let cases = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, r"{}"),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (
+            r#"{"a":{"b":"c"}}"#,
+            r#"{"a":{"b":"d","c":null}}"#,
+            r#"{"a":{"b":"d"}}"#,
+        ),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, r"null", r"null"),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r"[1,2]", r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        (r"{}", r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+    ];
+    for (target, patch, expected) in cases {
+        let mut value = JsonValue::parse(target.as_bytes(), false).unwrap();
+        value.merge(&JsonValue::parse(patch.as_bytes(), false).unwrap());
+        assert_eq!(
+            value,
+            JsonValue::parse(expected.as_bytes(), false).unwrap(),
+            "{target} + {patch}"
+        );
+    }
+}
+
+#[test]
+fn value_diff() {
+    let old = jiter::json!({"a": 1, "b": {"c": [1, 2], "d": "x", "e": true}, "f": "gone"});
+    let new = jiter::json!({"a": 1, "b": {"c": [1, 2, 3], "d": "x", "g": {"h": 1}}, "i": 2.5});
+    let patch = JsonValue::diff(&old, &new);
+    assert_eq!(
+        patch,
+        jiter::json!({"b": {"c": [1, 2, 3], "e": null, "g": {"h": 1}}, "f": null, "i": 2.5})
+    );
+
+    let pairs = [
+        (old.clone(), new.clone()),
+        (new.clone(), old.clone()),
+        (old.clone(), old.clone()),
+        (old.clone(), jiter::json!([1, {"a": null}])),
+        (jiter::json!("x"), new.clone()),
+        (jiter::json!({"a": {"b": 1}}), jiter::json!({"a": [1]})),
+        (jiter::json!({"a": [1]}), jiter::json!({"a": {"b": 1}})),
+    ];
+    for (old, new) in pairs {
+        let patch = JsonValue::diff(&old, &new);
+        let mut merged = old.clone();
+        merged.merge(&patch);
+        assert_eq!(merged, new, "{old:?} -> {new:?}");
+    }
+    // no changes gives an empty patch
+    assert_eq!(JsonValue::diff(&old, &old), jiter::json!({}));
+}
+
+#[test]
+fn jiter_escape_handler() {
+    fn handler(c: u8) -> EscapeAction {
+        match c {
+            b'\'' => EscapeAction::Replace('\''),
+            b'e' => EscapeAction::Replace('\u{e9}'),
+            b'x' => EscapeAction::Literal,
+            _ => EscapeAction::Error,
+        }
+    }
+
+    let mut jiter = Jiter::new(br#"{"k\'": "it\'s \e \x", "raw": "\e"}"#).with_escape_handler(handler);
+    assert_eq!(jiter.next_object().unwrap(), Some("k'"));
+    assert_eq!(jiter.next_str().unwrap(), "it's \u{e9} \\x");
+    assert_eq!(jiter.next_key().unwrap(), Some("raw"));
+    assert_eq!(jiter.next_raw_str_bytes().unwrap(), (br"\e".as_slice(), true));
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let mut jiter = Jiter::new(br#""\q""#).with_escape_handler(handler);
+    let e = jiter.next_str().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::InvalidEscape));
+    assert_eq!(e.index, 2);
+
+    let options = ParseOptions::default().with_escape_handler(handler);
+    let value = JsonValue::parse_with_options(br#"["\'"]"#, options).unwrap();
+    assert_eq!(value, jiter::json!(["'"]));
+    let e = JsonValue::parse(br#"["\'"]"#, false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::InvalidEscape);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn jiter_next_chrono_datetime() {
+    let mut jiter = Jiter::new(br#"["2024-02-29T12:30:00.5+01:00", "2024-02-30T00:00:00Z", 1]"#);
+    jiter.next_array().unwrap();
+    let dt = jiter.next_chrono_datetime().unwrap();
+    assert_eq!(dt.to_rfc3339(), "2024-02-29T12:30:00.500+01:00");
+    jiter.array_step().unwrap();
+    let e = jiter.next_chrono_datetime().unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::InvalidDateTime(_)));
+    assert_eq!(e.index, 32);
+    jiter.array_step().unwrap();
+    let e = jiter.next_chrono_datetime().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn jiter_next_time_datetime() {
+    let mut jiter = Jiter::new(br#"["2024-02-29T12:30:00Z", "yesterday"]"#);
+    jiter.next_array().unwrap();
+    let dt = jiter.next_time_datetime().unwrap();
+    assert_eq!(dt.unix_timestamp(), 1_709_209_800);
+    jiter.array_step().unwrap();
+    let e = jiter.next_time_datetime().unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::InvalidDateTime(_)));
+    assert_eq!(e.index, 25);
+    assert!(e.to_string().starts_with("invalid datetime: "), "{e}");
+}
+
+#[test]
+fn value_from_iter() {
+    let array: JsonValue = vec![1, 2, 3].into_iter().map(JsonValue::Int).collect();
+    assert_eq!(array, jiter::json!([1, 2, 3]));
+
+    let object: JsonValue = [("a", JsonValue::Int(1)), ("b", JsonValue::Null)].into_iter().collect();
+    assert_eq!(object, jiter::json!({"a": 1, "b": null}));
+
+    let keys = ["x".to_string(), "x".to_string()];
+    let object: JsonValue = keys.into_iter().zip([true.into(), false.into()]).collect();
+    assert_eq!(object, JsonValue::parse(br#"{"x": true, "x": false}"#, false).unwrap());
+}
+
+#[test]
+fn jiter_string_scratch_capacity() {
+    let json = br#"["a\nb", "\u00e9\u00e9\u00e9\u00e9", "plain"]"#;
+    let mut jiter = Jiter::new(json).with_string_scratch_capacity(64);
+    assert!(jiter.string_scratch_capacity() >= 64);
+    let capacity = jiter.string_scratch_capacity();
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::String));
+    assert_eq!(jiter.known_str().unwrap(), "a\nb");
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.known_str().unwrap(), "\u{e9}\u{e9}\u{e9}\u{e9}");
+    // the scratch buffer is reused rather than reallocated
+    assert_eq!(jiter.string_scratch_capacity(), capacity);
+
+    let mut jiter = Jiter::new(json);
+    assert_eq!(jiter.string_scratch_capacity(), 0);
+    jiter.next_array().unwrap();
+    jiter.known_str().unwrap();
+    assert!(jiter.string_scratch_capacity() > 0);
+}
+
+#[test]
+fn jiter_next_enum() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Status {
+        Active,
+        Closed,
+    }
+    let variants = [("active", Status::Active), ("closed", Status::Closed)];
+
+    let mut jiter = Jiter::new(br#"["closed", "active", "open", 1]"#);
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.next_enum(&variants).unwrap(), Status::Closed);
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_enum(&variants).unwrap(), Status::Active);
+    jiter.array_step().unwrap();
+    let e = jiter.next_enum(&variants).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::UnknownVariant {
+            expected: vec!["active".to_string(), "closed".to_string()],
+            found: "open".to_string()
+        }
+    );
+    assert_eq!(e.index, 21);
+    assert_eq!(
+        e.to_string(),
+        r#"expected one of [active, closed] but found "open" at index 21"#
+    );
+    jiter.array_step().unwrap();
+    let e = jiter.next_enum(&variants).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+}
+
+#[test]
+fn value_normalize_floats() {
+    let mut value = JsonValue::parse(br#"{"a": [-0.0, 0.1, 1e300, -1.5], "b": -0, "c": NaN}"#, true).unwrap();
+    value.normalize_floats();
+    let JsonValue::Object(object) = &value else {
+        panic!("expected object");
+    };
+    let JsonValue::Array(array) = object.get("a").unwrap() else {
+        panic!("expected array");
+    };
+    let floats: Vec<u64> = array
+        .iter()
+        .map(|v| f64::try_from(v.clone()).unwrap().to_bits())
+        .collect();
+    assert_eq!(floats, [0.0, 0.1, 1e300, -1.5].map(f64::to_bits));
+    // `-0` is an int, so isn't changed
+    assert_eq!(object.get("b"), Some(&JsonValue::Int(0)));
+    let JsonValue::Float(nan) = object.get("c").unwrap() else {
+        panic!("expected float");
+    };
+    assert_eq!(nan.to_bits(), f64::NAN.to_bits());
+}
+
+#[test]
+fn jiter_next_key_as() {
+    let mut jiter = Jiter::new(br#"{"1": "a", "-2": "b", "x": "c"}"#);
+    assert_eq!(jiter.next_object_as::<i32>().unwrap(), Some(1));
+    assert_eq!(jiter.next_str().unwrap(), "a");
+    assert_eq!(jiter.next_key_as::<i32>().unwrap(), Some(-2));
+    assert_eq!(jiter.next_str().unwrap(), "b");
+    let e = jiter.next_key_as::<i32>().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::InvalidKey {
+            key: "x".to_string(),
+            message: "invalid digit found in string".to_string()
+        }
+    );
+    assert_eq!(e.index, 22);
+    assert_eq!(
+        e.to_string(),
+        r#"invalid key "x": invalid digit found in string at index 22"#
+    );
+
+    let mut jiter = Jiter::new(br#"{"1.5": null}"#);
+    assert_eq!(jiter.next_object_as::<f64>().unwrap(), Some(1.5));
+    jiter.next_null().unwrap();
+    assert_eq!(jiter.next_key_as::<f64>().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let mut jiter = Jiter::new(b"{}");
+    assert_eq!(jiter.next_object_as::<u8>().unwrap(), None);
+
+    // the error is at the key, not a quote in a comment before it
+    let mut jiter = Jiter::new(br#"{"a": 1, /* "x" */ "zz": 2}"#).with_allow_comments();
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_int().unwrap();
+    let e = jiter.next_key_as::<i32>().unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::InvalidKey { .. }));
+    assert_eq!(e.index, 19);
+}
+
+#[test]
+fn jiter_next_int_lenient() {
+    use jiter::number::{AbstractNumberDecoder, NumberIntLenient};
+
+    let mut jiter = Jiter::new(b"[007, -00012, 00, 42, 0, 1.5]");
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.next_int_lenient().unwrap(), (NumberInt::Int(7), true));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_int_lenient().unwrap(), (NumberInt::Int(-12), true));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_int_lenient().unwrap(), (NumberInt::Int(0), true));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_int_lenient().unwrap(), (NumberInt::Int(42), false));
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.next_int_lenient().unwrap(), (NumberInt::Int(0), false));
+    jiter.array_step().unwrap();
+    let e = jiter.next_int_lenient().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Int,
+            actual: JsonType::Float
+        }
+    );
+
+    // strict parsing is unchanged
+    let e = Jiter::new(b"007").next_int().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::InvalidNumber));
+
+    let data = b"-0099, 1";
+    let ((int, leading_zeros), end) = NumberIntLenient::decode(data, 0, data[0], false).unwrap();
+    assert_eq!(int, NumberInt::Int(-99));
+    assert!(leading_zeros);
+    assert_eq!(end, 5);
+}
+
+#[test]
+fn jiter_subparser() {
+    let data = b"{\"envelope\": 1,\n \"payload\": {\"a\": [1, 2], \"b\": \"x\"}} ";
+    let mut jiter = Jiter::new(data);
+    assert_eq!(jiter.next_object().unwrap(), Some("envelope"));
+    jiter.next_int().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("payload"));
+    let peek = jiter.peek().unwrap();
+    let start = jiter.current_index();
+    jiter.known_skip(peek).unwrap();
+    let raw = jiter.slice_to_current(start);
+    assert_eq!(raw, br#"{"a": [1, 2], "b": "x"}"#);
+
+    let mut sub = jiter.subparser(raw).with_max_array_elements(1);
+    assert_eq!(sub.current_index(), start);
+    assert_eq!(sub.next_object().unwrap(), Some("a"));
+    let e = sub.next_value().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TooManyElements));
+    assert_eq!(e.index, 38);
+    assert_eq!(sub.error_position(e.index), LinePosition::new(2, 23));
+
+    // the subparser ends where `raw` ends
+    let mut sub = jiter.subparser(raw);
+    sub.next_skip().unwrap();
+    sub.finish().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+}
+
+#[test]
+#[should_panic(expected = "`raw` is not a slice of this Jiter's data")]
+fn jiter_subparser_foreign_slice() {
+    let jiter = Jiter::new(b"[1, 2]");
+    let _ = jiter.subparser(b"[1, 2]".to_vec().as_slice());
+}
+
+#[test]
+fn value_canonical_hash() {
+    use std::hash::Hasher;
+
+    fn hash(json: &[u8]) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        JsonValue::parse(json, true).unwrap().canonical_hash(&mut hasher);
+        hasher.finish()
+    }
+
+    assert_eq!(hash(br#"{"a":1,"b":2}"#), hash(br#"{"b":2,"a":1}"#));
+    assert_eq!(
+        hash(br#"{"x": [1.0, -0.0, {"b": null, "a": "s"}]}"#),
+        hash(br#"{"x": [1e0, 0.0, {"a": "s", "b": null}]}"#)
+    );
+    // the last of duplicate keys wins, as with lookups
+    assert_eq!(hash(br#"{"a": 1, "a": 2}"#), hash(br#"{"a": 2}"#));
+    assert_eq!(hash(b"NaN"), hash(b"NaN"));
+
+    assert_ne!(hash(br#"{"a":1,"b":2}"#), hash(br#"{"a":2,"b":1}"#));
+    assert_ne!(hash(b"[1, 2]"), hash(b"[2, 1]"));
+    assert_ne!(hash(b"1"), hash(b"1.0"));
+    assert_ne!(hash(br#"["ab", "c"]"#), hash(br#"["a", "bc"]"#));
+    assert_ne!(hash(b"[[], []]"), hash(b"[[[]]]"));
+}
+
+#[test]
+fn max_depth_error_recovery() {
+    let deep = format!("{}1{}", "[".repeat(1000), "]".repeat(1000));
+    let json = format!(r#"{{"a": 1, "deep": [{deep}, {{"x": {deep}}}], "b": [2]}}"#);
+    let e = JsonValue::parse(json.as_bytes(), false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::RecursionLimitExceeded);
+
+    let options = ParseOptions::new().with_max_depth_error_recovery();
+    let value = JsonValue::parse_with_options(json.as_bytes(), options).unwrap();
+    let JsonValue::Object(object) = &value else {
+        panic!("expected object");
+    };
+    assert_eq!(object.get("a"), Some(&JsonValue::Int(1)));
+    assert_eq!(object.get("b"), Some(&jiter::json!([2])));
+    let JsonValue::Array(deep) = object.get("deep").unwrap() else {
+        panic!("expected array");
+    };
+    assert_eq!(deep.len(), 2);
+    let mut depth = 0;
+    let mut current = &deep[0];
+    while let JsonValue::Array(array) = current {
+        assert_eq!(array.len(), 1);
+        current = &array[0];
+        depth += 1;
+    }
+    assert_eq!(current, &JsonValue::Null);
+    // with the outer object and the "deep" array, the value is nested up to the recursion limit of 200
+    assert_eq!(depth, 198);
+
+    let mut jiter = Jiter::new(json.as_bytes()).with_max_depth_error_recovery();
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("deep"));
+    let deep = jiter.next_value().unwrap();
+    let JsonValue::Array(array) = deep else {
+        panic!("expected array");
+    };
+    assert_eq!(array.len(), 2);
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    assert_eq!(jiter.next_value().unwrap(), jiter::json!([2]));
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    // skipped data must still be valid
+    let invalid = format!("{}1,]{}", "[".repeat(300), "]".repeat(299));
+    let e = JsonValue::parse_with_options(invalid.as_bytes(), options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::TrailingComma);
+}
+
+#[test]
+fn jiter_peek_is_empty_container() {
+    let mut jiter = Jiter::new(b"[[], [ \n ], {}, {\"a\": 1}, [0], 1, \"x\"]");
+    jiter.next_array().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), Some(true));
+    // nothing is consumed
+    assert_eq!(jiter.next_array().unwrap(), None);
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), Some(true));
+    jiter.next_skip().unwrap();
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), Some(true));
+    assert_eq!(jiter.next_object().unwrap(), None);
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), Some(false));
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_int().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), Some(false));
+    jiter.next_skip().unwrap();
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), None);
+    jiter.next_int().unwrap();
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_is_empty_container().unwrap(), None);
+
+    let e = Jiter::new(b"{ ").peek_is_empty_container().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingObject)
+    );
+}
+
+#[test]
+fn jiter_next_skip_hashed() {
+    use std::hash::Hasher;
+
+    fn hash_values(json: &[u8]) -> Vec<u64> {
+        let mut jiter = Jiter::new(json);
+        let mut values = Vec::new();
+        let mut peek = jiter.next_array().unwrap();
+        while peek.is_some() {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            jiter.next_skip_hashed(&mut hasher).unwrap();
+            values.push(hasher.finish());
+            peek = jiter.array_step().unwrap();
+        }
+        jiter.finish().unwrap();
+        values
+    }
+
+    let before = hash_values(br#"[{"a": [1, 2]}, "x",  true]"#);
+    let after = hash_values(br#"[{"a": [1, 3]}, "x", true ]"#);
+    assert_eq!(before.len(), 3);
+    assert_ne!(before[0], after[0]);
+    assert_eq!(before[1..], after[1..]);
+
+    let mut jiter = Jiter::new(b"[1, ]");
+    jiter.next_array().unwrap();
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    jiter.next_skip_hashed(&mut hasher).unwrap();
+    let e = jiter.array_step().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::JsonError(JsonErrorType::TrailingComma));
+}
+
+#[test]
+fn value_get_path() {
+    use PathSegment::{Index, Key};
+
+    let mut value = jiter::json!({"a": [1, {"b": "x"}], "c": null});
+    assert_eq!(value.get_path(&[]), Some(&value));
+    assert_eq!(
+        value.get_path(&[Key("a"), Index(1), Key("b")]),
+        Some(&JsonValue::from("x"))
+    );
+    assert_eq!(value.get_path(&[Key("c")]), Some(&JsonValue::Null));
+    assert_eq!(value.get_path(&[Key("a"), Index(2)]), None);
+    assert_eq!(value.get_path(&[Key("a"), Key("0")]), None);
+    assert_eq!(value.get_path(&[Index(0)]), None);
+    assert_eq!(value.get_path(&[Key("missing")]), None);
+
+    let shared = value.clone();
+    *value.get_path_mut(&[Key("a"), Index(0)]).unwrap() = JsonValue::Int(2);
+    assert_eq!(value, jiter::json!({"a": [2, {"b": "x"}], "c": null}));
+    // the shared value isn't changed
+    assert_eq!(shared, jiter::json!({"a": [1, {"b": "x"}], "c": null}));
+    assert!(value.get_path_mut(&[Key("c"), Key("d")]).is_none());
+}
+
+#[test]
+fn value_pointer() {
+    let mut value = jiter::json!({"a": [1, {"b": "x"}], "c/d": null, "e~f": 2, "": 3, "01": 4});
+    assert_eq!(value.pointer(""), Some(&value));
+    assert_eq!(value.pointer("/a/1/b"), Some(&JsonValue::from("x")));
+    assert_eq!(value.pointer("/c~1d"), Some(&JsonValue::Null));
+    assert_eq!(value.pointer("/e~0f"), Some(&JsonValue::Int(2)));
+    assert_eq!(value.pointer("/"), Some(&JsonValue::Int(3)));
+    // keys aren't indexes, so leading zeros are fine
+    assert_eq!(value.pointer("/01"), Some(&JsonValue::Int(4)));
+    assert_eq!(value.pointer("/a/01"), None);
+    assert_eq!(value.pointer("/a/+1"), None);
+    assert_eq!(value.pointer("/a/-"), None);
+    assert_eq!(value.pointer("/a/2"), None);
+    assert_eq!(value.pointer("/a/0/b"), None);
+    assert_eq!(value.pointer("a"), None);
+
+    let shared = value.clone();
+    *value.pointer_mut("/a/0").unwrap() = JsonValue::Int(5);
+    assert_eq!(value.pointer("/a/0"), Some(&JsonValue::Int(5)));
+    // the shared value isn't changed
+    assert_eq!(shared.pointer("/a/0"), Some(&JsonValue::Int(1)));
+    assert!(value.pointer_mut("/missing").is_none());
+}
+
+#[test]
+fn jiter_next_object_fields() {
+    const FIELDS: &[(&str, bool)] = &[("a", true), ("b", false), ("c", true)];
+
+    fn read(json: &[u8], unknown: UnknownFields) -> JiterResult<(Vec<String>, Vec<String>)> {
+        let mut jiter = Jiter::new(json);
+        let mut found = Vec::new();
+        let unknown = jiter.next_object_fields(FIELDS, unknown, |jiter, field| {
+            found.push(format!("{}={}", FIELDS[field].0, jiter.next_float()?));
+            Ok(())
+        })?;
+        jiter.finish()?;
+        let unknown = unknown.into_iter().map(|(k, v)| format!("{k}={v:?}")).collect();
+        Ok((found, unknown))
+    }
+
+    let (found, unknown) = read(br#"{"a": 1, "b": 2, "c": 3}"#, UnknownFields::Error).unwrap();
+    assert_eq!(found, ["a=1", "b=2", "c=3"]);
+    assert!(unknown.is_empty());
+
+    // out of order, optional field missing, unknown fields collected
+    let (found, unknown) = read(br#"{"c": 3, "x": [1], "a": 1, "y": null}"#, UnknownFields::Collect).unwrap();
+    assert_eq!(found, ["c=3", "a=1"]);
+    assert_eq!(unknown, ["x=Array([Int(1)])", "y=Null"]);
+
+    let (found, unknown) = read(br#"{"x": {"z": 1}, "a": 1, "c": 3}"#, UnknownFields::Skip).unwrap();
+    assert_eq!(found, ["a=1", "c=3"]);
+    assert!(unknown.is_empty());
+
+    let e = read(br#"{"a": 1, "x": 2, "c": 3}"#, UnknownFields::Error).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::UnknownField { field: "x".to_string() });
+    assert_eq!(e.index, 9);
+
+    let e = read(br#"{"a": 1, "b": 2}"#, UnknownFields::Error).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::MissingField { field: "c".to_string() });
+    assert_eq!(e.index, 15);
+    assert_eq!(e.to_string(), r#"missing field "c" at index 15"#);
+
+    let e = read(b"{}", UnknownFields::Error).unwrap_err();
+    assert_eq!(e.to_string(), r#"missing field "a" at index 1"#);
+
+    let e = read(b"[]", UnknownFields::Error).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Object,
+            actual: JsonType::Array
+        }
+    );
+}
+
+#[test]
+fn jiter_known_keys() {
+    static KEYS: &[&str] = &["id", "name"];
+
+    let mut jiter = Jiter::new(br#"{"name": "a", "other": 1, "id": 2}"#).with_known_keys(KEYS);
+    let key = jiter.next_object().unwrap().unwrap();
+    assert_eq!(key.as_ptr(), KEYS[1].as_ptr());
+    assert_eq!(jiter.next_str().unwrap(), "a");
+
+    let key = jiter.next_key().unwrap().unwrap();
+    assert_eq!(key, "other");
+    assert_ne!(key.as_ptr(), KEYS[0].as_ptr());
+    assert_ne!(key.as_ptr(), KEYS[1].as_ptr());
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+
+    let key = jiter.next_key().unwrap().unwrap();
+    assert_eq!(key.as_ptr(), KEYS[0].as_ptr());
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(2));
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    // escaped keys are matched after decoding
+    let mut jiter = Jiter::new(br#"{"\u0069d": 1}"#).with_known_keys(KEYS);
+    let key = jiter.next_object().unwrap().unwrap();
+    assert_eq!(key.as_ptr(), KEYS[0].as_ptr());
+}
+
+#[test]
+fn error_to_structured() {
+    let json = b"[1,\n  2,]";
+    let e = JsonValue::parse(json, false).unwrap_err();
+    let detail = e.to_structured(json);
+    assert_eq!(
+        detail,
+        ErrorDetail {
+            error_type: "TrailingComma",
+            index: 8,
+            line: 2,
+            column: 5,
+            message: "trailing comma".to_string(),
+        }
+    );
+
+    let mut jiter = Jiter::new(b"\n[true]");
+    let e = jiter.next_str().unwrap_err();
+    let detail = e.to_structured(&jiter);
+    assert_eq!(detail.error_type, "WrongType");
+    assert_eq!((detail.index, detail.line, detail.column), (1, 2, 1));
+    assert_eq!(detail.message, "expected string but found array");
+
+    let e = Jiter::new(b"[1").next_value().unwrap_err();
+    assert_eq!(e.error_type.name(), "EofWhileParsingList");
+
+    #[cfg(feature = "serde")]
+    assert_eq!(
+        serde_json::to_string(&detail).unwrap(),
+        r#"{"error_type":"WrongType","index":1,"line":2,"column":1,"message":"expected string but found array"}"#
+    );
+}
+
+#[test]
+fn jiter_next_str_where() {
+    let is_id = |s: &str| s.len() == 4 && s.bytes().all(|b| b.is_ascii_digit());
+
+    let mut jiter = Jiter::new(br#"["1234", "12x4", 1234]"#);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::String));
+    assert_eq!(jiter.known_str_where(is_id).unwrap(), "1234");
+
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::String));
+    let e = jiter.next_str_where(is_id).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::ValidationFailed {
+            value: "12x4".to_string()
+        }
+    );
+    assert_eq!(e.index, 9);
+    assert_eq!(e.to_string(), r#"string "12x4" failed validation at index 9"#);
+
+    assert!(jiter.array_step().unwrap().is_some_and(Peek::is_num));
+    let e = jiter.next_str_where(is_id).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+}
+
+#[test]
+fn jiter_next_tuple() {
+    let mut jiter = Jiter::new(b"[[1.5, 2, -3e2], [255, 0, 128, 255], [true, false]]");
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Array));
+    assert_eq!(
+        jiter.next_tuple_f64::<3>().unwrap().map(|f| f.to_string()),
+        ["1.5", "2", "-300"]
+    );
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.next_tuple_i64::<4>().unwrap(), [255, 0, 128, 255]);
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.next_tuple::<2, _>(Jiter::known_bool).unwrap(), [true, false]);
+    assert_eq!(jiter.array_step().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let e = Jiter::new(b"[1, 2]").next_tuple_f64::<3>().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 3, found: 2 });
+    assert_eq!(e.index, 5);
+    assert_eq!(e.to_string(), "expected an array of 3 elements but found 2 at index 5");
+
+    let e = Jiter::new(b"[1, 2, 3]").next_tuple_i64::<2>().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 2, found: 3 });
+    assert_eq!(e.index, 7);
+
+    let e = Jiter::new(b"[1, 2.5]").next_tuple_i64::<2>().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Int,
+            actual: JsonType::Float
+        }
+    );
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn value_validate_schema() {
+    use jiter::{Schema, SchemaError, SchemaErrorType};
+
+    let user = Schema::new()
+        .with_type(JsonType::Object)
+        .with_required(&["id", "role"])
+        .with_property("id", Schema::new().with_type(JsonType::Int).with_minimum(1.0))
+        .with_property("score", Schema::new().with_type(JsonType::Float).with_maximum(10.0))
+        .with_property(
+            "role",
+            Schema::new().with_enum(vec![JsonValue::Str("admin".into()), JsonValue::Str("user".into())]),
+        );
+    let schema = Schema::new().with_type(JsonType::Array).with_items(user);
+
+    let value = JsonValue::parse(
+        br#"[{"id": 1, "role": "user", "score": 7}, {"id": 2, "role": "admin", "score": 9.5}]"#,
+        false,
+    )
+    .unwrap();
+    assert_eq!(value.validate_schema(&schema), Ok(()));
+
+    let value = JsonValue::parse(
+        br#"[{"id": 0, "score": 11}, {"id": "2", "role": "guest"}, null]"#,
+        false,
+    )
+    .unwrap();
+    let errors = value.validate_schema(&schema).unwrap_err();
+    assert_eq!(
+        errors[0],
+        SchemaError {
+            path: vec![PathSegment::Index(0)],
+            error_type: SchemaErrorType::MissingKey {
+                key: "role".to_string()
+            },
+        }
+    );
+    let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        [
+            r#"/0: missing required key "role""#,
+            "/0/id: less than the minimum of 1",
+            "/0/score: greater than the maximum of 10",
+            "/1/id: expected int but found string",
+            "/1/role: not one of the allowed values",
+            "/2: expected object but found null",
+        ]
+    );
+
+    let errors = JsonValue::Bool(true).validate_schema(&schema).unwrap_err();
+    assert_eq!(errors[0].to_string(), "/: expected array but found bool");
+}
+
+#[test]
+fn nan_inf_as_null() {
+    let json = b"[NaN, Infinity, -Infinity, -1.5, {\"x\": NaN}]";
+    let value = JsonValue::parse_with_options(json, ParseOptions::new().with_nan_inf_as_null()).unwrap();
+    assert_eq!(
+        value,
+        JsonValue::parse(b"[null, null, null, -1.5, {\"x\": null}]", false).unwrap()
+    );
+    let value = JsonValueRc::parse_with_options(json, ParseOptions::new().with_nan_inf_as_null()).unwrap();
+    assert_eq!(
+        value,
+        JsonValueRc::parse(b"[null, null, null, -1.5, {\"x\": null}]", false).unwrap()
+    );
+
+    let mut jiter = Jiter::new(json).with_nan_inf_as_null();
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::NaN));
+    assert_eq!(jiter.next_value().unwrap(), JsonValue::Null);
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Infinity));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Minus));
+    assert_eq!(jiter.next_value().unwrap(), JsonValue::Null);
+
+    // without the flag, they're still errors
+    let e = JsonValue::parse(b"[Infinity]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+    let e = JsonValue::parse_with_options(b"[-Infinite]", ParseOptions::new().with_nan_inf_as_null()).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeIdent);
+}
+
+#[test]
+fn jiter_read_typed_arrays() {
+    let mut jiter = Jiter::new(br#"[["a", "b\n", ""], [1, -2], [1.5, 2], [true, false], []]"#);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Array));
+    let strs = jiter.read_str_array().unwrap();
+    assert_eq!(strs, ["a", "b\n", ""]);
+    assert!(matches!(strs[0], Cow::Borrowed(_)));
+    assert!(matches!(strs[1], Cow::Owned(_)));
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.read_i64_array().unwrap(), [1, -2]);
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    let floats = jiter.read_f64_array().unwrap();
+    assert_eq!(floats.iter().map(ToString::to_string).collect::<Vec<_>>(), ["1.5", "2"]);
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.read_bool_array().unwrap(), [true, false]);
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert!(jiter.read_str_array().unwrap().is_empty());
+    assert_eq!(jiter.array_step().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let e = Jiter::new(br#"["a", 1]"#).read_str_array().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::String,
+            actual: JsonType::Int
+        }
+    );
+    assert_eq!(e.index, 6);
+
+    let e = Jiter::new(b"[true, null]").read_bool_array().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Bool,
+            actual: JsonType::Null
+        }
+    );
+    assert_eq!(e.index, 7);
+
+    let e = Jiter::new(b"{}").read_i64_array().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Array,
+            actual: JsonType::Object
+        }
+    );
+
+    let e = Jiter::new(br#"[1.5, "x"]"#).read_f64_array().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Float,
+            actual: JsonType::String
+        }
+    );
+    assert_eq!(e.index, 6);
+    let mut jiter = Jiter::new(b"[[1e3 , -0.5]]");
+    jiter.next_array().unwrap();
+    let floats = jiter.read_f64_array().unwrap();
+    assert_eq!(
+        floats.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["1000", "-0.5"]
+    );
+    assert_eq!(jiter.current_depth(), 1);
+}
+
+#[test]
+fn jiter_current_container() {
+    let mut jiter = Jiter::new(br#"{"a": [{}, [1]], "b": []}"#);
+    assert_eq!(jiter.current_container(), None);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    assert_eq!(jiter.current_container(), Some(JsonType::Object));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Object));
+    assert_eq!(jiter.current_container(), Some(JsonType::Array));
+    // empty containers are never entered
+    assert_eq!(jiter.next_object().unwrap(), None);
+    assert_eq!(jiter.current_container(), Some(JsonType::Array));
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Array));
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), None);
+    assert_eq!(jiter.current_container(), Some(JsonType::Array));
+    assert_eq!(jiter.array_step().unwrap(), None);
+    assert_eq!(jiter.current_container(), Some(JsonType::Object));
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    assert_eq!(jiter.next_array().unwrap(), None);
+    assert_eq!(jiter.next_key().unwrap(), None);
+    assert_eq!(jiter.current_container(), None);
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn max_total_string_bytes() {
+    let json = br#"{"ab": "cde", "f": ["g\n", "hi"]}"#;
+    // keys and values count, escapes count by their decoded length: 2 + 3 + 1 + 2 + 2 = 10
+    let options = ParseOptions::new().with_max_total_string_bytes(10);
+    assert!(JsonValue::parse_with_options(json, options).is_ok());
+    assert!(JsonValueRc::parse_with_options(json, options).is_ok());
+
+    let options = ParseOptions::new().with_max_total_string_bytes(9);
+    let e = JsonValue::parse_with_options(json, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::TooManyStringBytes);
+    assert_eq!(e.index, 27);
+    assert_eq!(e.to_string(), "too many bytes in strings at index 27");
+
+    let mut jiter = Jiter::new(json).with_max_total_string_bytes(5);
+    assert_eq!(jiter.next_object().unwrap(), Some("ab"));
+    assert_eq!(jiter.next_str().unwrap(), "cde");
+    let e = jiter.next_key().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::TooManyStringBytes)
+    );
+
+    // skipped strings aren't counted
+    let mut jiter = Jiter::new(json).with_max_total_string_bytes(3);
+    assert_eq!(jiter.next_object().unwrap(), Some("ab"));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), Some("f"));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+}
+
+#[test]
+fn jiter_peek_number_is_integer() {
+    let mut jiter = Jiter::new(b"[1, -20, 1.5, 2e3, -0, 0.0, \"1\", 01]");
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'1')));
+    assert_eq!(jiter.peek_number_is_integer().unwrap(), Some(true));
+    // nothing was consumed
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+
+    let mut kinds = Vec::new();
+    while jiter.array_step().unwrap().is_some() {
+        let is_int = jiter.peek_number_is_integer();
+        kinds.push(is_int.map_err(|e| e.error_type));
+        if kinds.last().unwrap().is_err() {
+            break;
+        }
+        jiter.next_skip().unwrap();
+    }
+    assert_eq!(
+        kinds,
+        [
+            Ok(Some(true)),
+            Ok(Some(false)),
+            Ok(Some(false)),
+            Ok(Some(true)),
+            Ok(Some(false)),
+            Ok(None),
+            Err(JiterErrorType::JsonError(JsonErrorType::InvalidNumber)),
+        ]
+    );
+
+    let mut jiter = Jiter::new(b"Infinity").with_allow_inf_nan();
+    assert_eq!(jiter.peek_number_is_integer().unwrap(), Some(false));
+}
+
+#[test]
+fn require_sorted_keys() {
+    let options = ParseOptions::new().with_require_sorted_keys();
+    let json = br#"{"a": {"z": 1, "zz": {}}, "b": [{"y": 1, "x": 2}], "\u0063": 1, "d": null}"#;
+    let e = JsonValue::parse_with_options(json, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::KeysNotSorted("x".to_string()));
+    assert_eq!(e.index, 41);
+    assert_eq!(
+        e.to_string(),
+        r#"key "x" is not sorted after the previous key at index 41"#
+    );
+
+    // nested objects are checked separately, escaped keys are compared decoded so "\u0063" sorts after "b"
+    let json = br#"{"a": {"z": 1, "zz": {}}, "b": [{"x": 1, "y": 2}], "\u0063": 1, "d": null}"#;
+    assert!(JsonValue::parse_with_options(json, options).is_ok());
+
+    // duplicates aren't sorted either
+    let e = JsonValue::parse_with_options(br#"{"a": 1, "a": 2}"#, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::KeysNotSorted("a".to_string()));
+
+    // without the option, any order is fine
+    assert!(JsonValue::parse(br#"{"b": 1, "a": 2}"#, false).is_ok());
+
+    // skipped objects are checked too
+    let mut jiter = Jiter::new(br#"[{"b": 1, "a": 2}]"#).with_require_sorted_keys();
+    let e = jiter.next_skip().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::KeysNotSorted("a".to_string()))
+    );
+}
+
+#[test]
+fn catch_duplicate_keys() {
+    let options = ParseOptions::new().with_catch_duplicate_keys();
+    // only the inner object has a duplicate, the outer "a" keys are in different objects
+    let json = b"{\"a\": 1, \"b\": [{\"a\": 2}, {\"x\": 1,\n \"a\": 3, \"x\": 4}]}";
+    let e = JsonValue::parse_with_options(json, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("x".to_string()));
+    assert_eq!(e.index, 43);
+    assert_eq!(e.get_position(json), LinePosition::new(2, 10));
+
+    // the duplicate key's value is an array or object
+    let e = JsonValue::parse_with_options(br#"{"a": 1, "a": {"b": 2}}"#, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("a".to_string()));
+    assert_eq!(e.index, 9);
+    let e = JsonValue::parse_with_options(br#"{"a": [], "\u0061": [1]}"#, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("a".to_string()));
+    assert_eq!(e.index, 10);
+
+    // large objects are checked too
+    let keys: Vec<String> = (0..40).map(|i| format!(r#""k{i}": {i}"#)).collect();
+    let json = format!("{{{}, \"k33\": 0}}", keys.join(", "));
+    let e = JsonValue::parse_with_options(json.as_bytes(), options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("k33".to_string()));
+    assert_eq!(&json[e.index..], r#""k33": 0}"#);
+    assert!(JsonValue::parse_with_options(format!("{{{}}}", keys.join(", ")).as_bytes(), options).is_ok());
+
+    let e = JsonValueRc::parse_with_options(br#"{"a": 1, "a": 2}"#, options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::DuplicateKey("a".to_string()));
+
+    // without the option, all entries are kept
+    let value = JsonValue::parse(br#"{"a": 1, "a": 2}"#, false).unwrap();
+    assert_eq!(value, jiter::json!({"a": 1, "a": 2}));
+}
+
+#[test]
+fn value_into_variants() {
+    let value = JsonValue::parse(br#"{"a": [1, 2.5, "x", true]}"#, false).unwrap();
+    let object = value.into_object().unwrap();
+    let array = object.get("a").unwrap().clone().into_array().unwrap();
+    assert_eq!(array.len(), 4);
+
+    assert_eq!(array[0].clone().into_i64(), Ok(1));
+    assert_eq!(array[1].clone().into_f64(), Ok(2.5));
+    assert_eq!(array[2].clone().into_string(), Ok(Cow::Borrowed("x")));
+    assert_eq!(array[3].clone().into_bool(), Ok(true));
+
+    // on a mismatch the value is returned unchanged, ints aren't widened to floats
+    assert_eq!(array[0].clone().into_f64(), Err(JsonValue::Int(1)));
+    assert_eq!(array[2].clone().into_i64(), Err(JsonValue::Str("x".into())));
+    assert_eq!(JsonValue::Null.into_array(), Err(JsonValue::Null));
+    assert_eq!(JsonValue::Bool(false).into_object(), Err(JsonValue::Bool(false)));
+}
+
+#[test]
+fn jiter_progress() {
+    let json = format!("[{}]", vec!["12345"; 100].join(", "));
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let calls2 = calls.clone();
+    let mut jiter = Jiter::new(json.as_bytes()).with_progress(100, move |index| calls2.lock().unwrap().push(index));
+    let value = jiter.next_value().unwrap();
+    jiter.finish().unwrap();
+    assert!(matches!(value, JsonValue::Array(array) if array.len() == 100));
+    let calls = calls.lock().unwrap();
+
+    // each element and its separator is 7 bytes
+    assert_eq!(calls.len(), 6);
+    assert!(calls.windows(2).all(|w| w[1] - w[0] >= 100 && w[1] - w[0] < 107));
+    assert!(calls[0] >= 100 && calls[0] < 107);
+
+    // the callback can borrow local state
+    let mut last = 0;
+    let mut jiter = Jiter::new(json.as_bytes()).with_progress(100, |index| last = index);
+    jiter.next_skip().unwrap();
+    drop(jiter);
+    assert!(last >= 600);
+
+    // without a callback, parsing is unchanged
+    let mut jiter = Jiter::new(json.as_bytes());
+    jiter.next_skip().unwrap();
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn jiter_progress_lookahead() {
+    let json = format!(r#"{{"items": [{}], "type": "a"}}"#, vec![r#""12345""#; 100].join(", "));
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let calls2 = calls.clone();
+    let mut jiter = Jiter::new(json.as_bytes()).with_progress(50, move |index| calls2.lock().unwrap().push(index));
+
+    // looking ahead scans past the real parser, but mustn't report that progress
+    assert!(jiter.object_contains_key("type").unwrap());
+    assert_eq!(jiter.peek_object_tag("type").unwrap().as_deref(), Some("a"));
+    assert_eq!(jiter.next_object().unwrap(), Some("items"));
+    let mut element = jiter.next_array().unwrap();
+    assert!(jiter.remaining_array_count().unwrap().is_some());
+    while element.is_some() {
+        assert_eq!(jiter.peek_string_byte_len().unwrap(), 5);
+        jiter.next_str().unwrap();
+        element = jiter.array_step().unwrap();
+    }
+    assert_eq!(jiter.next_key().unwrap(), Some("type"));
+    jiter.next_str().unwrap();
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let calls = calls.lock().unwrap();
+    assert!(calls.len() > 10);
+    assert!(calls.windows(2).all(|w| w[0] < w[1]), "{calls:?}");
+}
+
+#[test]
+fn jiter_next_value_borrowed_keys() {
+    let mut jiter = Jiter::new(br#"{"a": "x", "b\n": {"c": "y\n"}}"#);
+    let JsonValue::Object(object) = jiter.next_value().unwrap() else {
+        panic!("expected an object");
+    };
+    let keys: Vec<_> = object.keys().collect();
+    assert!(matches!(keys[0], Cow::Borrowed("a")));
+    assert!(matches!(keys[1], Cow::Owned(key) if key == "b\n"));
+    assert!(matches!(object.get("a"), Some(JsonValue::Str(Cow::Borrowed("x")))));
+
+    let Some(JsonValue::Object(inner)) = object.get("b\n") else {
+        panic!("expected an object");
+    };
+    let (key, value) = inner.iter().next().unwrap();
+    assert!(matches!(key, Cow::Borrowed("c")));
+    assert!(matches!(value, JsonValue::Str(Cow::Owned(s)) if s == "y\n"));
+}
+
+#[test]
+fn value_parse_or_null() {
+    assert_eq!(JsonValue::parse_or_null(b"", false).unwrap(), JsonValue::Null);
+    assert_eq!(JsonValue::parse_or_null(b" \r\n\t", false).unwrap(), JsonValue::Null);
+    assert_eq!(JsonValue::parse_or_null(b"null", false).unwrap(), JsonValue::Null);
+    assert_eq!(
+        JsonValue::parse_or_null(b" [1] ", false).unwrap(),
+        JsonValue::Array(Arc::new(smallvec![JsonValue::Int(1)]))
+    );
+
+    let e = JsonValue::parse_or_null(b" [1", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingList);
+}
+
+#[test]
+fn cst_comments() {
+    fn texts<'j>(comments: &[jiter::Comment<'j>]) -> Vec<&'j str> {
+        comments.iter().map(|c| c.text).collect()
+    }
+
+    let json = b"// leading\n[1.50, /* a */ \"x\" // b\n, {\"k\" /* c */ : null // d\n} /* e */] // end";
+    let doc = parse_cst(json).unwrap();
+    assert_eq!(texts(&doc.value.leading_comments), ["// leading"]);
+    assert_eq!(texts(&doc.trailing_comments), ["// end"]);
+    assert!(!doc.trailing_comments[0].is_block());
+
+    let CstKind::Array {
+        elements,
+        trailing_comments,
+    } = &doc.value.kind
+    else {
+        panic!("expected an array");
+    };
+    assert_eq!(texts(trailing_comments), ["/* e */"]);
+    assert_eq!(elements[0].kind, CstKind::Number("1.50"));
+    assert_eq!(&json[elements[0].range.clone()], b"1.50");
+    assert_eq!(texts(&elements[1].leading_comments), ["/* a */"]);
+    assert!(elements[1].leading_comments[0].is_block());
+    assert_eq!(elements[1].kind, CstKind::Str("x".into()));
+    assert_eq!(texts(&elements[2].leading_comments), ["// b"]);
+
+    let CstKind::Object {
+        members,
+        trailing_comments,
+    } = &elements[2].kind
+    else {
+        panic!("expected an object");
+    };
+    assert_eq!(texts(trailing_comments), ["// d"]);
+    assert_eq!(members[0].key, "k");
+    assert_eq!(&json[members[0].key_range.clone()], b"\"k\"");
+    assert_eq!(texts(&members[0].value.leading_comments), ["/* c */"]);
+    assert_eq!(members[0].value.kind, CstKind::Null);
+
+    let e = parse_cst(b"[1 /* x").unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingComment);
+    assert_eq!(e.index, 7);
+    let e = parse_cst(b"[1 / 2]").unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedListCommaOrEnd);
+    assert_eq!(e.index, 3);
+}
+
+#[test]
+fn jiter_expect_array_len() {
+    let mut jiter = Jiter::new(b"[[1, 2], [3, 4]]");
+    assert_eq!(jiter.expect_array_len(2).unwrap(), Some(Peek::Array));
+    let mut rows = Vec::new();
+    loop {
+        jiter.expect_array_len(2).unwrap();
+        let x = jiter.next_int().unwrap();
+        jiter.array_step().unwrap();
+        let y = jiter.next_int().unwrap();
+        assert_eq!(jiter.array_step().unwrap(), None);
+        rows.push((x, y));
+        if jiter.array_step().unwrap().is_none() {
+            break;
+        }
+    }
+    jiter.finish().unwrap();
+    assert_eq!(
+        rows,
+        [
+            (NumberInt::Int(1), NumberInt::Int(2)),
+            (NumberInt::Int(3), NumberInt::Int(4))
+        ]
+    );
+
+    // too long, the error is at the first extra element
+    let mut jiter = Jiter::new(b"[1, 2, 3]");
+    jiter.expect_array_len(2).unwrap();
+    let e = jiter.skip_to_depth(0).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 2, found: 3 });
+    assert_eq!(e.index, 7);
+    assert_eq!(
+        e.to_string(),
+        "expected an array of 2 elements but found more at index 7"
+    );
+
+    // too short, the error is at the end of the array
+    let mut jiter = Jiter::new(b"[1]");
+    jiter.expect_array_len(2).unwrap();
+    jiter.next_int().unwrap();
+    let e = jiter.array_step().unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 2, found: 1 });
+    assert_eq!(e.index, 2);
+
+    let e = Jiter::new(b"[]").expect_array_len(1).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 1, found: 0 });
+    let e = Jiter::new(b"[1]").expect_array_len(0).unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::WrongArrayLength { expected: 0, found: 1 });
+    assert_eq!(Jiter::new(b"[]").expect_array_len(0).unwrap(), None);
+}
+
+#[test]
+fn value_mutation_in_place() {
+    let mut value = JsonValue::parse(br#"{"a": {"b": 1, "c": -0.0}, "d": [1.5]}"#, false).unwrap();
+    let JsonValue::Object(object) = &value else {
+        unreachable!()
+    };
+    let object_ptr = Arc::as_ptr(object);
+    let JsonValue::Object(inner) = object.get("a").unwrap() else {
+        unreachable!()
+    };
+    let inner_ptr = Arc::as_ptr(inner);
+
+    // uniquely owned objects and arrays are mutated without being cloned
+    value.merge(&JsonValue::parse(br#"{"a": {"b": null, "e": 2}}"#, false).unwrap());
+    value.normalize_floats();
+    value.coerce_numbers(NumberCoercion::IntsWherePossible);
+    *value
+        .get_path_mut(&[PathSegment::Key("d"), PathSegment::Index(0)])
+        .unwrap() = JsonValue::Null;
+    let JsonValue::Object(object) = &value else {
+        unreachable!()
+    };
+    assert_eq!(Arc::as_ptr(object), object_ptr);
+    assert_eq!(Arc::strong_count(object), 1);
+    let JsonValue::Object(inner) = object.get("a").unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(Arc::as_ptr(inner), inner_ptr);
+    assert_eq!(
+        value,
+        JsonValue::parse(br#"{"a": {"c": 0, "e": 2}, "d": [null]}"#, false).unwrap()
+    );
+
+    // shared objects are cloned, leaving the other copy unchanged
+    let shared = value.clone();
+    value.merge(&JsonValue::parse(br#"{"f": true}"#, false).unwrap());
+    let (JsonValue::Object(object), JsonValue::Object(shared_object)) = (&value, &shared) else {
+        unreachable!()
+    };
+    assert_ne!(Arc::as_ptr(object), Arc::as_ptr(shared_object));
+    assert_eq!(Arc::strong_count(shared_object), 1);
+    assert!(shared_object.get("f").is_none());
+}
+
+#[test]
+fn jiter_comment_sink() {
+    let json = b"/* head */ {\"a\": 1, // one\n \"b\": [/**/]} // tail";
+    let mut jiter = Jiter::new(json).with_comment_sink(Vec::new());
+    let value = jiter.next_value().unwrap();
+    jiter.finish().unwrap();
+    assert_eq!(value, JsonValue::parse(br#"{"a": 1, "b": []}"#, false).unwrap());
+    let comments: Vec<_> = jiter.take_comments().into_iter().map(|r| &json[r]).collect();
+    assert_eq!(comments, [&b"/* head */"[..], b"// one", b"/**/", b"// tail"]);
+    assert!(jiter.take_comments().is_empty());
+
+    // comments are skipped without a sink too, but are an error without `allow_comments`
+    let options = ParseOptions::new().with_allow_comments();
+    let value = JsonValue::parse_with_options(b"[1 /* x */, 2]", options).unwrap();
+    assert_eq!(value, JsonValue::parse(b"[1, 2]", false).unwrap());
+    let e = JsonValue::parse(b"[1 /* x */, 2]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedListCommaOrEnd);
+
+    // an unterminated block comment runs to the end of the data
+    let e = JsonValue::parse_with_options(b"[1 /* x ]", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingList);
+}
+
+#[test]
+fn value_parse_with_keyset() {
+    let mut keyset = KeySet::new();
+    let docs: Vec<&[u8]> = vec![
+        br#"{"id": 1, "name": "a"}"#,
+        br#"{"id": 2, "name": "b", "extra": {"id": 3}}"#,
+    ];
+    let values: Vec<_> = docs
+        .iter()
+        .map(|data| JsonValue::parse_with_keyset(data, false, &keyset).unwrap())
+        .collect();
+    assert_eq!(keyset.len(), 3);
+    assert_eq!(
+        values[1],
+        JsonValue::parse(br#"{"id": 2, "name": "b", "extra": {"id": 3}}"#, false).unwrap()
+    );
+
+    // keys are borrowed from the set, so each distinct key is the same `str` in every document
+    let key_ptrs = |value: &JsonValue| -> Vec<*const u8> {
+        let JsonValue::Object(object) = value else {
+            panic!("expected an object")
+        };
+        object
+            .keys()
+            .map(|key| {
+                assert!(matches!(key, Cow::Borrowed(_)));
+                key.as_ptr()
+            })
+            .collect()
+    };
+    assert_eq!(key_ptrs(&values[0]), key_ptrs(&values[1])[..2]);
+
+    drop(values);
+    keyset.clear();
+    assert!(keyset.is_empty());
+}
+
+#[test]
+fn value_parse_array_best_effort() {
+    let json = br#"[1, {"a": }, "x]", [2, tru], 3 4, {"b": "],\"" x}, 5]"#;
+    let (elements, error) = JsonValue::parse_array_best_effort(json, false);
+    assert!(error.is_none());
+    let summary: Vec<_> = elements
+        .iter()
+        .map(|element| match element {
+            Ok(value) => format!("{value:?}"),
+            Err(e) => format!("{:?} at {}", e.error_type, e.index),
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            "Int(1)",
+            "ExpectedSomeValue at 10",
+            "Str(\"x]\")",
+            "ExpectedSomeIdent at 26",
+            "ExpectedListCommaOrEnd at 31",
+            "ExpectedObjectCommaOrEnd at 47",
+            "Int(5)",
+        ]
+    );
+
+    // errors with the array itself are returned separately, along with the elements before them
+    let (elements, error) = JsonValue::parse_array_best_effort(b"[1, x, 2", false);
+    assert_eq!(elements.len(), 3);
+    assert_eq!(error.unwrap().error_type, JsonErrorType::EofWhileParsingList);
+    let (elements, error) = JsonValue::parse_array_best_effort(b"[1] x", false);
+    assert_eq!(elements.len(), 1);
+    assert_eq!(error.unwrap().error_type, JsonErrorType::TrailingCharacters);
+    let (elements, error) = JsonValue::parse_array_best_effort(b" {}", false);
+    assert!(elements.is_empty());
+    let error = error.unwrap();
+    assert_eq!(error.error_type, JsonErrorType::ExpectedArray);
+    assert_eq!(error.index, 1);
+}
+
+#[test]
+fn undefined_as_null() {
+    let options = ParseOptions::new().with_undefined_as_null();
+    let value = JsonValue::parse_with_options(br#"[1, undefined, {"a": undefined}, null]"#, options).unwrap();
+    assert_eq!(
+        value,
+        JsonValue::parse(br#"[1, null, {"a": null}, null]"#, false).unwrap()
+    );
+
+    let mut jiter = Jiter::new(b"[undefined, undefined]").with_undefined_as_null();
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::Null));
+    jiter.known_null().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Null));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.array_step().unwrap(), None);
+    jiter.finish().unwrap();
+
+    let e = JsonValue::parse_with_options(b"[undefine]", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeIdent);
+    assert_eq!(e.index, 9);
+
+    // strict parsing is unchanged
+    let e = JsonValue::parse(b"[1, undefined]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+}
+
+#[test]
+fn jiter_fold_array() {
+    let json = br#"[{"id": 1, "tags": ["a", "b"]}, {"id": 2, "tags": []}, {"id": 3}]"#;
+    // only the first key of each object is read, the rest is skipped
+    let ids = jiter::fold_array(json, Vec::new(), |mut ids, jiter| {
+        assert_eq!(jiter.next_object()?, Some("id"));
+        ids.push(jiter.next_int()?);
+        Ok(ids)
+    })
+    .unwrap();
+    assert_eq!(ids, [NumberInt::Int(1), NumberInt::Int(2), NumberInt::Int(3)]);
+
+    // elements `f` doesn't read at all are skipped too
+    let mut jiter = Jiter::new(b"[[1, 2, 3], [], 4] true");
+    assert_eq!(jiter.fold_array(0, |count, _| Ok(count + 1)).unwrap(), 3);
+    assert!(jiter.next_bool().unwrap());
+    jiter.finish().unwrap();
+
+    assert_eq!(jiter::fold_array(b"[]", 0, |count, _| Ok(count + 1)).unwrap(), 0);
+    let e = jiter::fold_array(b"[1, 2] x", 0, |count, _| Ok(count + 1)).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::TrailingCharacters)
+    );
+    let e = jiter::fold_array(b"[1, \"x\"]", 0.0, |sum, jiter| Ok(sum + jiter.next_float()?)).unwrap_err();
+    assert_eq!(e.index, 4);
+}
+
+#[test]
+fn allow_leading_decimal_point() {
+    let options = ParseOptions::new().with_allow_leading_decimal_point();
+    let value = JsonValue::parse_with_options(b"[.5, -.5, .25e2, 1.5]", options).unwrap();
+    assert_eq!(value, JsonValue::parse(b"[0.5, -0.5, 25.0, 1.5]", false).unwrap());
+
+    let mut jiter = Jiter::new(b"[.5, -.5, .5]").with_allow_leading_decimal_point();
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::new(b'.')));
+    assert!((jiter.next_float().unwrap() - 0.5).abs() < f64::EPSILON);
+    jiter.array_step().unwrap();
+    assert_eq!(jiter.peek_number_is_integer().unwrap(), Some(false));
+    assert!((jiter.next_float().unwrap() + 0.5).abs() < f64::EPSILON);
+    jiter.array_step().unwrap();
+    let e = jiter.next_int().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::WrongType {
+            expected: JsonType::Int,
+            actual: JsonType::Float
+        }
+    );
+
+    let e = JsonValue::parse_with_options(b"-.", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingValue);
+    assert_eq!(e.index, 2);
+
+    // strict parsing still rejects them
+    let e = JsonValue::parse(b".5", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+    assert_eq!(e.index, 0);
+    let e = JsonValue::parse(b"-.5", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::InvalidNumber);
+    assert_eq!(e.index, 1);
+    let e = Jiter::new(b".5").next_float().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedSomeValue)
+    );
+}
+
+#[test]
+fn jiter_snapshot_restore() {
+    let json = br#"[{"kind": "point", "x": 1}, [1, 2], 3]"#;
+    let mut jiter = Jiter::new(json).with_require_sorted_keys();
+    jiter.next_array().unwrap();
+    // try reading each element as an object with an int `kind`, falling back to a value
+    let mut values = Vec::new();
+    loop {
+        let snapshot = jiter.snapshot();
+        if matches!(jiter.next_object(), Ok(Some("kind"))) {
+            assert!(jiter.next_int().is_err());
+        }
+        jiter.restore(&snapshot);
+        values.push(jiter.next_value_owned().unwrap());
+        if jiter.array_step().unwrap().is_none() {
+            break;
+        }
+    }
+    jiter.finish().unwrap();
+    let JsonValue::Array(expected) = JsonValue::parse(json, false).unwrap() else {
+        panic!("expected an array");
+    };
+    assert_eq!(values.as_slice(), expected.as_slice());
+
+    // the previous key is restored, so sorted keys can be read again
+    let mut jiter = Jiter::new(br#"{"a": 1, "b": 2}"#).with_require_sorted_keys();
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    jiter.next_skip().unwrap();
+    let snapshot = jiter.snapshot();
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    jiter.restore(&snapshot);
+    assert_eq!(jiter.next_key().unwrap(), Some("b"));
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(2));
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+}
+
+#[test]
+fn value_pool_reuse() {
+    let pool = JsonValuePool::new();
+    let json = br#"{"a": [1, 2, {"b": []}], "c": {}}"#;
+    let value = pool.parse(json, false).unwrap();
+    assert_eq!(*value, JsonValue::parse(json, false).unwrap());
+    let JsonValue::Object(object) = &*value else {
+        panic!("expected an object");
+    };
+    let object_ptr = Arc::as_ptr(object).cast::<u8>();
+    assert!(pool.is_empty());
+    drop(value);
+    assert_eq!(pool.len(), 5);
+
+    // arrays and objects from the pool are reused, and come back empty
+    let value = pool.parse(br#"{"x": [true]}"#, false).unwrap();
+    let JsonValue::Object(object) = &*value else {
+        panic!("expected an object");
+    };
+    assert_eq!(Arc::as_ptr(object).cast::<u8>(), object_ptr);
+    assert_eq!(*value, JsonValue::parse(br#"{"x": [true]}"#, false).unwrap());
+    assert_eq!(pool.len(), 3);
+
+    // shared arrays and objects aren't returned to the pool
+    let JsonValue::Object(object) = value.clone() else {
+        panic!("expected an object");
+    };
+    drop(value);
+    assert_eq!(pool.len(), 3);
+    drop(object);
+
+    let e = pool.parse(b"[1, 2", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::EofWhileParsingList);
+}
+
+#[test]
+fn jiter_array_iter() {
+    let mut jiter = Jiter::new(b"[]");
+    assert_eq!(jiter.array_iter().unwrap().count(), 0);
+    jiter.finish().unwrap();
+
+    // elements which aren't read are skipped, including nested arrays
+    let mut jiter = Jiter::new(br#"[1, [2, [3]], {"a": []}, "x"]"#);
+    let peeks: Vec<Peek> = jiter.array_iter().unwrap().map(Result::unwrap).collect();
+    assert_eq!(peeks, [Peek::new(b'1'), Peek::Array, Peek::Object, Peek::String]);
+    jiter.finish().unwrap();
+
+    // nested iterators, with elements partly read
+    let mut jiter = Jiter::new(br#"[[1, 2], [], [3, "x"]]"#);
+    let mut outer = jiter.array_iter().unwrap();
+    let mut firsts = Vec::new();
+    while let Some(peek) = outer.next() {
+        assert_eq!(peek.unwrap(), Peek::Array);
+        let mut inner = outer.jiter().array_iter().unwrap();
+        if let Some(peek) = inner.next() {
+            firsts.push(inner.jiter().known_int(peek.unwrap()).unwrap());
+        }
+    }
+    assert_eq!(firsts, [NumberInt::Int(1), NumberInt::Int(3)]);
+    jiter.finish().unwrap();
+
+    // stopping early leaves the jiter inside the array
+    let mut jiter = Jiter::new(b"[1, 2, 3]");
+    let mut seen = 0;
+    for peek in jiter.array_iter().unwrap() {
+        seen += 1;
+        if peek.unwrap() == Peek::new(b'2') {
+            break;
+        }
+    }
+    assert_eq!(seen, 2);
+    assert_eq!(jiter.current_depth(), 1);
+    jiter.skip_to_depth(0).unwrap();
+    jiter.finish().unwrap();
+
+    let mut jiter = Jiter::new(b"[1, 2 3]");
+    let results: Vec<_> = jiter.array_iter().unwrap().collect();
+    assert_eq!(results.len(), 3);
+    let e = results[2].as_ref().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedListCommaOrEnd)
+    );
+}
+
+#[test]
+fn parse_from_str() {
+    let json = r#"{"café": ["£10", "café \"ok\"", "😀 😀", 1.5]}"#;
+    let value = JsonValue::parse_str(json, false).unwrap();
+    assert_eq!(value, JsonValue::parse(json.as_bytes(), false).unwrap());
+    assert_eq!(value, jiter::json!({"café": ["£10", "café \"ok\"", "😀 😀", 1.5]}));
+
+    let mut jiter = Jiter::from_str(r#"["ünïcode", "tab\tescaped\\t", {"kéy": "ü"}]"#);
+    assert_eq!(jiter.next_array().unwrap(), Some(Peek::String));
+    assert_eq!(jiter.known_str().unwrap(), "ünïcode");
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::String));
+    assert_eq!(jiter.known_str().unwrap(), "tab\tescaped\\t");
+    assert_eq!(jiter.array_step().unwrap(), Some(Peek::Object));
+    assert_eq!(jiter.known_object().unwrap(), Some("kéy"));
+    assert_eq!(jiter.next_str().unwrap(), "ü");
+    assert_eq!(jiter.next_key().unwrap(), None);
+    assert_eq!(jiter.array_step().unwrap(), None);
+    jiter.finish().unwrap();
+
+    // control characters are still an error
+    let e = JsonValue::parse_str("[\"é\u{1}\"]", false).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ControlCharacterWhileParsingString);
+    assert_eq!(e.index, 4);
+    let e = Jiter::from_str("\"é\n\"").next_str().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ControlCharacterWhileParsingString)
+    );
+}
+
+#[test]
+fn jiter_next_raw_value() {
+    let json = br#" { "a": {"b": [1, {"c": "}"}]}, "d": [ ] , "e": "x\"y", "f": 1.5e3 } "#;
+    let mut jiter = Jiter::new(json);
+    assert_eq!(jiter.next_object().unwrap(), Some("a"));
+    let raw = jiter.next_raw_value().unwrap();
+    assert_eq!(raw, br#"{"b": [1, {"c": "}"}]}"#);
+    assert_eq!(
+        JsonValue::parse(raw, false).unwrap(),
+        jiter::json!({"b": [1, {"c": "}"}]})
+    );
+    assert_eq!(jiter.next_key().unwrap(), Some("d"));
+    assert_eq!(jiter.next_raw_value().unwrap(), b"[ ]");
+    assert_eq!(jiter.next_key().unwrap(), Some("e"));
+    assert_eq!(jiter.next_raw_value().unwrap(), br#""x\"y""#);
+    assert_eq!(jiter.next_key().unwrap(), Some("f"));
+    assert_eq!(jiter.next_raw_value().unwrap(), b"1.5e3");
+    assert_eq!(jiter.next_key().unwrap(), None);
+    jiter.finish().unwrap();
+
+    // the whole document
+    let mut jiter = Jiter::new(json);
+    let raw = jiter.next_raw_value().unwrap();
+    assert_eq!(raw, &json[1..json.len() - 1]);
+    assert_eq!(
+        JsonValue::parse(raw, false).unwrap(),
+        JsonValue::parse(json, false).unwrap()
+    );
+    jiter.finish().unwrap();
+
+    // a number at the end of the data
+    let mut jiter = Jiter::new(b"  -123");
+    assert_eq!(jiter.next_raw_value().unwrap(), b"-123");
+    jiter.finish().unwrap();
+
+    let e = Jiter::new(b"[1, 2").next_raw_value().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingList)
+    );
+}
+
+#[cfg(feature = "raw-number")]
+#[test]
+fn value_raw_numbers() {
+    let json = br#"{"price": 19.990000000000000000001, "ids": [12345678901234567890123, -0, 1e400, 2E-5], "n": null}"#;
+    let options = ParseOptions::new().with_raw_numbers();
+    let value = JsonValue::parse_with_options(json, options).unwrap();
+    let JsonValue::Object(object) = &value else {
+        panic!("expected object")
+    };
+    assert_eq!(
+        object.get("price"),
+        Some(&JsonValue::RawNumber(Cow::Borrowed("19.990000000000000000001")))
+    );
+    let raw: Vec<_> = match object.get("ids") {
+        Some(JsonValue::Array(array)) => array
+            .iter()
+            .map(|v| match v {
+                JsonValue::RawNumber(s) => s.as_ref(),
+                v => panic!("expected raw number, got {v:?}"),
+            })
+            .collect(),
+        v => panic!("expected array, got {v:?}"),
+    };
+    assert_eq!(raw, ["12345678901234567890123", "-0", "1e400", "2E-5"]);
+    assert_eq!(object.get("n"), Some(&JsonValue::Null));
+    assert_eq!(object.get("price").unwrap().json_type(), JsonType::Float);
+    assert_eq!(JsonValue::RawNumber("-12".into()).json_type(), JsonType::Int);
+
+    // round trip to an owned value, and numbers are still validated
+    let owned = value.to_static();
+    assert_eq!(owned, value);
+    assert!(matches!(owned, JsonValue::Object(_)));
+    let owned = JsonValue::parse_owned_with_options(b"[1.50]", options).unwrap();
+    assert_eq!(owned, jiter::json!([JsonValue::RawNumber("1.50".into())]));
+
+    let e = JsonValue::parse_with_options(b"[01]", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::InvalidNumber);
+    let e = JsonValue::parse_with_options(b"[NaN]", options).unwrap_err();
+    assert_eq!(e.error_type, JsonErrorType::ExpectedSomeValue);
+    let value = JsonValue::parse_with_options(b"-Infinity", options.with_allow_inf_nan()).unwrap();
+    assert_eq!(value, JsonValue::RawNumber("-Infinity".into()));
+    assert_eq!(value.json_type(), JsonType::Float);
+
+    // through a jiter and with partial data
+    let mut jiter = Jiter::new(b"[1.0, 2").with_options(options.with_partial_mode(PartialMode::On));
+    assert_eq!(
+        jiter.next_value().unwrap(),
+        jiter::json!([JsonValue::RawNumber("1.0".into()), JsonValue::RawNumber("2".into())])
+    );
+
+    let value = JsonValueRc::parse_with_options(b"[3.14]", options).unwrap();
+    assert_eq!(
+        value,
+        JsonValueRc::from(&jiter::json!([JsonValue::RawNumber("3.14".into())]))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_from_slice() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Rect { w: u32, h: u32 },
+        Line(i8, i8),
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Drawing {
+        name: String,
+        shapes: Vec<Shape>,
+        #[serde(default)]
+        scale: Option<f32>,
+        layers: BTreeMap<u8, Vec<bool>>,
+    }
+
+    let data = br#"{
+        "name": "ab\nc",
+        "shapes": ["Empty", {"Circle": 1}, {"Rect": {"w": 2, "h": 3}}, {"Line": [-1, 1]}],
+        "unknown": [{"nested": null}],
+        "layers": {"1": [true], "20": []}
+    }"#;
+    let drawing: Drawing = jiter::from_slice(data).unwrap();
+    assert_eq!(
+        drawing,
+        Drawing {
+            name: "ab\nc".to_string(),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.0),
+                Shape::Rect { w: 2, h: 3 },
+                Shape::Line(-1, 1)
+            ],
+            scale: None,
+            layers: BTreeMap::from([(1, vec![true]), (20, vec![])]),
+        }
+    );
+
+    // number coercion, ints are accepted for floats, and big ints for wider types
+    let nums: (f64, u64, i128, Option<u8>) =
+        jiter::from_slice(b"[1, 18446744073709551615, -9223372036854775809, null]").unwrap();
+    assert_eq!(nums, (1.0, u64::MAX, -9_223_372_036_854_775_809, None));
+    let e = jiter::from_slice::<u8>(b"256").unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::Custom("invalid value: integer `256`, expected u8".to_string())
+    );
+    let e = jiter::from_slice::<i64>(b"1.5").unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::Custom(_)));
+
+    // errors from the `Deserialize` impl point at the value, so can be mapped to a line and column
+    let data = b"{\n  \"name\": \"x\",\n  \"shapes\": [\"Square\"],\n  \"layers\": {}\n}";
+    let e = jiter::from_slice::<Drawing>(data).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::Custom(
+            "unknown variant `Square`, expected one of `Empty`, `Circle`, `Rect`, `Line`".to_string()
+        )
+    );
+    assert_eq!(e.get_position(&Jiter::new(data)), LinePosition::new(3, 14));
+    let data = b"{\"name\": \"x\",\n\"layers\": {}}";
+    let e = jiter::from_slice::<Drawing>(data).unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::Custom("missing field `shapes`".to_string())
+    );
+    assert_eq!(e.index, 0);
+    let e = jiter::from_slice::<HashMap<u8, bool>>(br#"{"1": true, "x": false}"#).unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::InvalidKey { .. }));
+
+    // JSON errors are reported as usual
+    let e = jiter::from_slice::<Vec<u8>>(b"[1, 2").unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingList)
+    );
+    let e = jiter::from_slice::<Vec<u8>>(b"[1] x").unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::TrailingCharacters)
+    );
+    let e = jiter::from_slice::<(u8,)>(b"[1, 2]").unwrap_err();
+    assert_eq!(e.error_type, JiterErrorType::ArrayTooLong { capacity: 1 });
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_from_slice_borrowed() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Borrowed<'a> {
+        plain: &'a str,
+        #[serde(borrow)]
+        escaped: Cow<'a, str>,
+    }
+
+    let data = br#"{"plain": "foo", "escaped": "b\"ar"}"#;
+    let value: Borrowed = jiter::from_slice_borrowed(data).unwrap();
+    assert_eq!(value.plain, "foo");
+    assert_eq!(value.plain.as_ptr(), data[11..].as_ptr());
+    assert_eq!(value.escaped, "b\"ar");
+    assert!(matches!(value.escaped, Cow::Owned(_)));
+
+    // strings with escapes can't be borrowed
+    let e = jiter::from_slice_borrowed::<&str>(br#""a\nb""#).unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::Custom(_)));
+}
+
+#[test]
+fn incremental_parser() {
+    let data = br#" [1, -2.5e3, "a\"b\u00e9c", true, null, {"x": [1, {"y": "z"}]}, [], 123456789012345678 ] "#;
+    let expected = JsonValue::parse(data, false).unwrap();
+    let JsonValue::Array(expected) = expected else {
+        panic!("expected an array");
+    };
+
+    // every split into chunks of `size` bytes, including inside strings, numbers and literals
+    for size in 1..data.len() {
+        let mut parser = IncrementalParser::new();
+        let mut values = Vec::new();
+        for chunk in data.chunks(size) {
+            parser.push_bytes(chunk);
+            while let Some(value) = parser.try_next_value().unwrap() {
+                values.push(value);
+            }
+        }
+        assert!(parser.is_done());
+        parser.finish().unwrap();
+        assert_eq!(values, expected.as_slice(), "chunk size {size}");
+    }
+
+    // a number isn't complete until what follows it arrives
+    let mut parser = IncrementalParser::new();
+    parser.push_bytes(b"[12");
+    assert_eq!(parser.try_next_value().unwrap(), None);
+    parser.push_bytes(b"3 ");
+    assert_eq!(parser.try_next_value().unwrap(), None);
+    parser.push_bytes(b"]");
+    assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(123)));
+    assert_eq!(parser.try_next_value().unwrap(), None);
+
+    // empty array
+    let mut parser = IncrementalParser::new();
+    parser.push_bytes(b"[ ]");
+    assert_eq!(parser.try_next_value().unwrap(), None);
+    assert!(parser.is_done());
+}
+
+#[test]
+fn incremental_parser_errors() {
+    // error indexes are relative to all the data pushed
+    let mut parser = IncrementalParser::new();
+    parser.push_bytes(b"[1, 2");
+    assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(1)));
+    parser.push_bytes(b", x]");
+    assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(2)));
+    let e = parser.try_next_value().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedSomeValue)
+    );
+    assert_eq!(e.index, 7);
+
+    // not an array
+    let mut parser = IncrementalParser::new();
+    parser.push_bytes(b"{}");
+    let e = parser.try_next_value().unwrap_err();
+    assert!(matches!(e.error_type, JiterErrorType::WrongType { .. }));
+
+    // the data ends early, or has more after the array
+    let mut parser = IncrementalParser::new();
+    parser.push_bytes(b"[1, 2");
+    assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(1)));
+    let e = parser.finish().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::EofWhileParsingList)
+    );
+    assert_eq!(e.index, 5);
+    parser.push_bytes(b"] x");
+    assert_eq!(parser.try_next_value().unwrap(), Some(JsonValue::Int(2)));
+    let e = parser.try_next_value().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::TrailingCharacters)
+    );
+    assert_eq!(e.index, 7);
+}
+
+#[test]
+fn jiter_next_document() {
+    let data = b"{\"a\": [1, 2]}\n\"x\"\r\n\n\n  \r\n-1.5\ntrue\r\n[]\n\n{\"b\": null}";
+    let mut jiter = Jiter::new(data);
+    let mut values = Vec::new();
+    while let Some(peek) = jiter.next_document().unwrap() {
+        values.push(jiter.known_value(peek).unwrap());
+    }
+    assert_eq!(
+        values,
+        [
+            jiter::json!({"a": [1, 2]}),
+            JsonValue::Str("x".into()),
+            JsonValue::Float(-1.5),
+            JsonValue::Bool(true),
+            jiter::json!([]),
+            jiter::json!({"b": null}),
+        ]
+    );
+    // calling again at the end is fine
+    assert_eq!(jiter.next_document().unwrap(), None);
+
+    // a trailing newline, and leading blank lines
+    let mut jiter = Jiter::new(b"\n\n1\n2\n");
+    assert_eq!(jiter.next_document().unwrap(), Some(Peek::new(b'1')));
+    assert_eq!(jiter.next_int().unwrap(), NumberInt::Int(1));
+    assert_eq!(jiter.next_document().unwrap(), Some(Peek::new(b'2')));
+    jiter.next_skip().unwrap();
+    assert_eq!(jiter.next_document().unwrap(), None);
+
+    // empty data has no documents
+    assert_eq!(Jiter::new(b"  \n").next_document().unwrap(), None);
+
+    // garbage is rejected, as are two documents on one line
+    let mut jiter = Jiter::new(b"1\nx");
+    assert_eq!(jiter.next_document().unwrap(), Some(Peek::new(b'1')));
+    jiter.next_skip().unwrap();
+    let e = jiter.next_document().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::ExpectedSomeValue)
+    );
+    assert_eq!(e.index, 2);
+    let mut jiter = Jiter::new(b"{} {}");
+    assert_eq!(jiter.next_document().unwrap(), Some(Peek::Object));
+    jiter.next_skip().unwrap();
+    let e = jiter.next_document().unwrap_err();
+    assert_eq!(
+        e.error_type,
+        JiterErrorType::JsonError(JsonErrorType::TrailingCharacters)
+    );
+    assert_eq!(e.index, 3);
+}
+
+#[test]
+fn value_to_json_string() {
+    let data = r#"{"a": [1, -2.5, 1e300, -0.0, true, null], "b\"\\": "x\ny\u0001\u001f\b\f\r\t/é💩",
+        "empty": {"o": {}, "a": []}, "nested": [[{"k": [{}]}]]}"#;
+    let value = JsonValue::parse(data.as_bytes(), false).unwrap();
+    let serde_value: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(value.to_json_string(), serde_json::to_string(&serde_value).unwrap());
+    assert_eq!(
+        value.to_json_string_pretty(2),
+        serde_json::to_string_pretty(&serde_value).unwrap()
+    );
+
+    // the output parses back to the same value
+    let compact = value.to_json_string();
+    let owned = value.to_static();
+    assert_eq!(JsonValue::parse(compact.as_bytes(), false).unwrap().to_static(), owned);
+    let pretty = value.to_json_string_pretty(4);
+    assert_eq!(JsonValue::parse(pretty.as_bytes(), false).unwrap().to_static(), owned);
+
+    // floats round-trip
+    for f in [
+        0.1,
+        1.0 / 3.0,
+        f64::MAX,
+        f64::MIN_POSITIVE,
+        5e-324,
+        1.5e-7,
+        1e16,
+        -123.456,
+    ] {
+        let s = JsonValue::Float(f).to_json_string();
+        assert_eq!(
+            JsonValue::parse(s.as_bytes(), false).unwrap(),
+            JsonValue::Float(f),
+            "{s}"
+        );
+    }
+
+    // non-finite floats aren't valid JSON, so are written as null
+    let value = jiter::json!([f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+    assert_eq!(value.to_json_string(), "[null,null,null]");
+
+    assert_eq!(jiter::json!([]).to_json_string_pretty(2), "[]");
+    assert_eq!(jiter::json!({}).to_json_string_pretty(2), "{}");
+    assert_eq!(jiter::json!({"a": []}).to_json_string_pretty(0), "{\n\"a\": []\n}");
+    assert_eq!(JsonValue::Str("a".into()).to_json_string_pretty(2), r#""a""#);
+
+    // duplicate keys are all written
+    let value = JsonValue::parse(br#"{"a": 1, "b": 2, "a": 3}"#, false).unwrap();
+    assert_eq!(value.to_json_string(), r#"{"a":1,"b":2,"a":3}"#);
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn value_to_json_string_bigint() {
+    // big ints are written exactly, not rounded via a float
+    let data = "[12345678901234567890123,-98765432109876543210,9223372036854775808]";
+    let value = JsonValue::parse(data.as_bytes(), false).unwrap();
+    assert!(matches!(&value, JsonValue::Array(array) if matches!(array[0], JsonValue::BigInt(_))));
+    assert_eq!(value.to_json_string(), data);
+    assert_eq!(
+        value.to_json_string_pretty(2),
+        "[\n  12345678901234567890123,\n  -98765432109876543210,\n  9223372036854775808\n]"
+    );
+}